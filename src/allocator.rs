@@ -0,0 +1,314 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::error::AllocatorError;
+use crate::rng::SplitMix64;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AllocationStrategy {
+    Sequential,
+    Random(u64),
+    Shuffled(u64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AllocatorState {
+    pub capacity: usize,
+    pub strategy: AllocationStrategy,
+    pub allocated: Vec<u8>,
+    pub rng_state: u64,
+    pub cursor: usize,
+}
+
+#[derive(Debug)]
+pub struct IndexAllocator {
+    capacity: usize,
+    strategy: AllocationStrategy,
+    allocated: Vec<u8>,
+    count: usize,
+    rng: SplitMix64,
+    cursor: usize,
+    order: Vec<usize>,
+}
+
+impl IndexAllocator {
+    pub fn new(capacity: usize, strategy: AllocationStrategy) -> Self {
+        let seed = match strategy {
+            AllocationStrategy::Sequential => 0,
+            AllocationStrategy::Random(seed) | AllocationStrategy::Shuffled(seed) => seed,
+        };
+
+        Self {
+            capacity,
+            strategy,
+            allocated: vec![0u8; capacity.div_ceil(8)],
+            count: 0,
+            rng: SplitMix64::new(seed),
+            cursor: 0,
+            order: Self::order_for(capacity, strategy),
+        }
+    }
+
+    pub fn from_state(state: AllocatorState) -> Result<Self, AllocatorError> {
+        if state.allocated.len() != state.capacity.div_ceil(8) {
+            return Err(AllocatorError::InvalidState(format!(
+                "allocation bitmap holds {} bytes, expected {}",
+                state.allocated.len(),
+                state.capacity.div_ceil(8)
+            )));
+        }
+        if state.cursor > state.capacity {
+            return Err(AllocatorError::InvalidState(format!(
+                "cursor {} is past capacity {}",
+                state.cursor, state.capacity
+            )));
+        }
+
+        let count = state
+            .allocated
+            .iter()
+            .map(|byte| byte.count_ones() as usize)
+            .sum();
+        Ok(Self {
+            capacity: state.capacity,
+            strategy: state.strategy,
+            allocated: state.allocated,
+            count,
+            rng: SplitMix64::new(state.rng_state),
+            cursor: state.cursor,
+            order: Self::order_for(state.capacity, state.strategy),
+        })
+    }
+
+    pub fn to_state(&self) -> AllocatorState {
+        AllocatorState {
+            capacity: self.capacity,
+            strategy: self.strategy,
+            allocated: self.allocated.clone(),
+            rng_state: self.rng.state(),
+            cursor: self.cursor,
+        }
+    }
+
+    pub fn allocate(&mut self) -> Result<usize, AllocatorError> {
+        if self.count >= self.capacity {
+            return Err(AllocatorError::Exhausted(self.capacity));
+        }
+
+        let index = match self.strategy {
+            AllocationStrategy::Sequential => self.next_free_from(self.cursor),
+            AllocationStrategy::Shuffled(_) => {
+                while self.is_allocated(self.order[self.cursor]) {
+                    self.cursor += 1;
+                }
+                self.order[self.cursor]
+            }
+            AllocationStrategy::Random(_) => {
+                // Probe a few random slots, then fall back to a scan from a
+                // random start so a nearly full list still terminates quickly.
+                let mut index = None;
+                for _ in 0..16 {
+                    let candidate = self.rng.below(self.capacity);
+                    if !self.is_allocated(candidate) {
+                        index = Some(candidate);
+                        break;
+                    }
+                }
+                match index {
+                    Some(index) => index,
+                    None => {
+                        let start = self.rng.below(self.capacity);
+                        self.next_free_from(start)
+                    }
+                }
+            }
+        };
+
+        self.mark(index);
+        if matches!(
+            self.strategy,
+            AllocationStrategy::Sequential | AllocationStrategy::Shuffled(_)
+        ) {
+            self.cursor += 1;
+        }
+        Ok(index)
+    }
+
+    /// Allocates one index per item, or none if fewer are free.
+    pub fn allocate_for<T>(
+        &mut self,
+        items: impl IntoIterator<Item = T>,
+    ) -> Result<Vec<(T, usize)>, AllocatorError> {
+        let items: Vec<T> = items.into_iter().collect();
+        if items.len() > self.remaining() {
+            return Err(AllocatorError::Exhausted(self.capacity));
+        }
+        items
+            .into_iter()
+            .map(|item| Ok((item, self.allocate()?)))
+            .collect()
+    }
+
+    pub fn mark_allocated(&mut self, index: usize) -> Result<(), AllocatorError> {
+        if index >= self.capacity {
+            return Err(AllocatorError::IndexOutOfRange(index));
+        }
+        if self.is_allocated(index) {
+            return Err(AllocatorError::AlreadyAllocated(index));
+        }
+        self.mark(index);
+        Ok(())
+    }
+
+    pub fn is_allocated(&self, index: usize) -> bool {
+        index < self.capacity && self.allocated[index / 8] & (1 << (index % 8)) != 0
+    }
+
+    pub fn allocated_count(&self) -> usize {
+        self.count
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.capacity - self.count
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn mark(&mut self, index: usize) {
+        self.allocated[index / 8] |= 1 << (index % 8);
+        self.count += 1;
+    }
+
+    // Only called while at least one slot is free.
+    fn next_free_from(&mut self, start: usize) -> usize {
+        let mut index = start % self.capacity;
+        while self.is_allocated(index) {
+            index = (index + 1) % self.capacity;
+        }
+        if matches!(self.strategy, AllocationStrategy::Sequential) {
+            self.cursor = index;
+        }
+        index
+    }
+
+    fn order_for(capacity: usize, strategy: AllocationStrategy) -> Vec<usize> {
+        match strategy {
+            AllocationStrategy::Shuffled(seed) => {
+                let mut order: Vec<usize> = (0..capacity).collect();
+                let mut rng = SplitMix64::new(seed);
+                for i in (1..capacity).rev() {
+                    order.swap(i, rng.below(i + 1));
+                }
+                order
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(allocator: &mut IndexAllocator) -> Vec<usize> {
+        let mut indices = Vec::new();
+        while let Ok(index) = allocator.allocate() {
+            indices.push(index);
+        }
+        indices
+    }
+
+    #[test]
+    fn test_sequential() {
+        let mut allocator = IndexAllocator::new(10, AllocationStrategy::Sequential);
+        allocator.mark_allocated(2).unwrap();
+
+        assert_eq!(drain(&mut allocator), vec![0, 1, 3, 4, 5, 6, 7, 8, 9]);
+        match allocator.allocate() {
+            Err(AllocatorError::Exhausted(10)) => (),
+            _ => panic!("Expected Exhausted error"),
+        }
+    }
+
+    #[test]
+    fn test_random_and_shuffled_cover_every_index() {
+        for strategy in [
+            AllocationStrategy::Random(7),
+            AllocationStrategy::Shuffled(7),
+        ] {
+            let mut allocator = IndexAllocator::new(100, strategy);
+            let mut indices = drain(&mut allocator);
+            assert_ne!(indices, (0..100).collect::<Vec<_>>());
+
+            indices.sort_unstable();
+            assert_eq!(indices, (0..100).collect::<Vec<_>>());
+            assert_eq!(allocator.remaining(), 0);
+        }
+    }
+
+    #[test]
+    fn test_restore_continues_sequence() {
+        for strategy in [
+            AllocationStrategy::Sequential,
+            AllocationStrategy::Random(3),
+            AllocationStrategy::Shuffled(3),
+        ] {
+            let mut original = IndexAllocator::new(64, strategy);
+            for _ in 0..20 {
+                original.allocate().unwrap();
+            }
+
+            let mut restored = IndexAllocator::from_state(original.to_state()).unwrap();
+            assert_eq!(restored.allocated_count(), 20);
+            assert_eq!(drain(&mut restored), drain(&mut original));
+        }
+    }
+
+    #[test]
+    fn test_allocate_for() {
+        let mut allocator = IndexAllocator::new(4, AllocationStrategy::Sequential);
+        assert_eq!(
+            allocator.allocate_for(["a", "b", "c"]).unwrap(),
+            vec![("a", 0), ("b", 1), ("c", 2)]
+        );
+        match allocator.allocate_for(["d", "e"]) {
+            Err(AllocatorError::Exhausted(4)) => (),
+            _ => panic!("Expected Exhausted error"),
+        }
+        assert_eq!(allocator.remaining(), 1);
+    }
+
+    #[test]
+    fn test_invalid_operations() {
+        let mut allocator = IndexAllocator::new(4, AllocationStrategy::Sequential);
+        allocator.allocate().unwrap();
+
+        match allocator.mark_allocated(0) {
+            Err(AllocatorError::AlreadyAllocated(0)) => (),
+            _ => panic!("Expected AlreadyAllocated error"),
+        }
+        match allocator.mark_allocated(4) {
+            Err(AllocatorError::IndexOutOfRange(4)) => (),
+            _ => panic!("Expected IndexOutOfRange error"),
+        }
+
+        let mut state = allocator.to_state();
+        state.allocated.push(0);
+        assert!(IndexAllocator::from_state(state).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_state_serializes() {
+        let mut allocator = IndexAllocator::new(16, AllocationStrategy::Shuffled(11));
+        allocator.allocate().unwrap();
+
+        let json = serde_json::to_string(&allocator.to_state()).unwrap();
+        let state: AllocatorState = serde_json::from_str(&json).unwrap();
+        assert_eq!(state, allocator.to_state());
+    }
+}
@@ -0,0 +1,1610 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use coset::{iana, CborSerializable, CoseSign1, TaggedCborSerializable};
+use p256::ecdsa::signature::{Signer as _, Verifier};
+use p256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use p256::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use serde::Serialize;
+use serde_json::{json, Value};
+use vc_status_list::{
+    CwtStatusListToken, FetchedToken, JwkSet, JwtStatusListToken, ReqwestTransport,
+    SignatureVerifier, Signer, StatusChange, StatusCheckReport, StatusClaim, StatusList,
+    StatusListBuilder, StatusListClient, StatusListDecoder, StatusListDiff, StatusListReference,
+    StatusListTokenVerifier, StatusListUri, StatusRegistry, StatusType, Transport,
+    DEFAULT_BUCKET_SIZE,
+};
+
+type CliResult<T> = Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(
+    name = "tsl",
+    version,
+    about = "Create, inspect and verify Token Status Lists"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// `json` prints each result as a JSON document, and `watch` as JSON Lines
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Builds a status list from a CSV (`idx,status` rows) or a JSON array of statuses
+    Create {
+        /// Input file, or `-` for stdin
+        input: String,
+        #[arg(long, default_value_t = 1)]
+        bits: u8,
+        /// Write CBOR instead of JSON
+        #[arg(long)]
+        cbor: bool,
+    },
+    /// Builds a list from an issuer database dump and writes it as a signed token
+    Build(BuildArgs),
+    /// Sets one status in an existing list and writes the updated list
+    SetStatus {
+        list: String,
+        index: usize,
+        /// Status name (e.g. INVALID), number or hex value (e.g. 0x0b)
+        status: String,
+        #[arg(long)]
+        cbor: bool,
+    },
+    /// Prints every status as JSON Lines
+    Decode { list: String },
+    /// Shows bit width, sizes, status counts and spec conformance
+    Inspect { list: String },
+    /// Prints the indices that changed between two list or token files
+    Diff {
+        old: String,
+        new: String,
+        /// Print change counts per index bucket as JSON instead
+        #[arg(long)]
+        summary: bool,
+        #[arg(long, default_value_t = DEFAULT_BUCKET_SIZE)]
+        bucket_size: usize,
+        /// JWK set file or URL to verify token files with; unverified otherwise
+        #[arg(long)]
+        jwks: Option<String>,
+        /// Path to a PEM-encoded ES256 public key to verify token files with
+        #[arg(long, conflicts_with = "jwks")]
+        key: Option<String>,
+    },
+    /// Polls a status list per its ttl and prints changes as they appear
+    Watch {
+        uri: String,
+        /// JWK set file or URL to verify the tokens with; unverified otherwise
+        #[arg(long)]
+        jwks: Option<String>,
+        /// Path to a PEM-encoded ES256 public key to verify the tokens with
+        #[arg(long, conflicts_with = "jwks")]
+        key: Option<String>,
+        /// Seconds between polls for tokens without a ttl
+        #[arg(long, default_value_t = 300)]
+        interval: u64,
+        /// Stop after this many polls
+        #[arg(long)]
+        count: Option<u64>,
+    },
+    /// Verifies the signature and claims of a CWT or JWT status list token
+    VerifyToken {
+        token: String,
+        /// Path to a PEM-encoded ES256 (P-256) public key
+        #[arg(long)]
+        key: String,
+        /// Expected `sub`; claims are only checked when given
+        #[arg(long)]
+        uri: Option<String>,
+        /// Verification time in seconds since the epoch (defaults to now)
+        #[arg(long)]
+        now: Option<u64>,
+        #[arg(long, default_value_t = 0)]
+        leeway: u64,
+    },
+    /// Verifies a token against an issuer's JWK set and prints the report and status histogram
+    Verify {
+        /// Token file, or the status list URL to fetch the token from
+        token: String,
+        /// JWK set file or URL
+        #[arg(long)]
+        jwks: String,
+        /// The `sub` the token must carry
+        #[arg(long)]
+        expect_sub: String,
+        /// Verification time in seconds since the epoch (defaults to now)
+        #[arg(long)]
+        now: Option<u64>,
+        #[arg(long, default_value_t = 0)]
+        leeway: u64,
+    },
+    /// Fetches and verifies a status list and prints the status of one credential
+    #[command(group = ArgGroup::new("reference").required(true))]
+    #[command(group = ArgGroup::new("trust").required(true))]
+    Check {
+        /// Status list URI; use with --idx
+        #[arg(long, group = "reference", requires = "idx")]
+        uri: Option<String>,
+        #[arg(long)]
+        idx: Option<usize>,
+        /// A referenced token (JWT, SD-JWT or CWT file) whose `status` claim to check;
+        /// a status list embedded in a JWT header is checked without fetching
+        #[arg(long, group = "reference", conflicts_with = "idx")]
+        token: Option<String>,
+        /// JWK set file or URL
+        #[arg(long, group = "trust")]
+        jwks: Option<String>,
+        /// Path to a PEM-encoded ES256 public key
+        #[arg(long, group = "trust")]
+        key: Option<String>,
+        #[arg(long)]
+        now: Option<u64>,
+        #[arg(long, default_value_t = 0)]
+        leeway: u64,
+    },
+    /// Fetches a status list token, in whichever form the server serves, and checks it
+    Fetch {
+        uri: String,
+        /// Save the raw token to this file
+        #[arg(long = "out", short = 'o')]
+        out: Option<String>,
+        /// Path to a PEM-encoded ES256 public key; verifies and inspects the token when given
+        #[arg(long)]
+        key: Option<String>,
+        #[arg(long)]
+        now: Option<u64>,
+    },
+}
+
+#[derive(Args)]
+#[command(group = ArgGroup::new("dump").required(true))]
+struct BuildArgs {
+    /// JSON dump: an array of statuses, or of `{"idx": .., "status": ..}` objects
+    #[arg(long, group = "dump")]
+    from_json: Option<String>,
+    /// CSV dump of `idx,status` rows
+    #[arg(long, group = "dump")]
+    from_csv: Option<String>,
+    /// Number of statuses in the list; indices the dump leaves out get the default status
+    #[arg(long)]
+    size: Option<usize>,
+    /// Status of indices the dump leaves out
+    #[arg(long, default_value = "valid")]
+    default: String,
+    #[arg(long, default_value_t = 1)]
+    bits: u8,
+    /// The token's `sub`: the URI the list is published at
+    #[arg(long)]
+    uri: String,
+    /// Path to a PEM-encoded ES256 (P-256) private key in PKCS#8 form
+    #[arg(long)]
+    key: String,
+    /// Key ID to put in the token header
+    #[arg(long)]
+    kid: Option<String>,
+    #[arg(long, value_enum, default_value_t = TokenForm::Jwt)]
+    form: TokenForm,
+    #[arg(long)]
+    ttl: Option<u64>,
+    /// Sets `exp` this many seconds after `iat`
+    #[arg(long)]
+    lifetime: Option<u64>,
+    /// Issuance time in seconds since the epoch (defaults to now)
+    #[arg(long)]
+    now: Option<u64>,
+    /// Token file to write
+    #[arg(long = "out", short = 'o')]
+    out: String,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum TokenForm {
+    Jwt,
+    Cwt,
+}
+
+struct Es256Signer(SigningKey, Option<String>);
+
+impl Es256Signer {
+    fn from_pem(pem: &str, kid: Option<String>) -> CliResult<Self> {
+        let key =
+            SigningKey::from_pkcs8_pem(pem).map_err(|e| format!("invalid private key: {}", e))?;
+        Ok(Self(key, kid))
+    }
+}
+
+impl Signer for Es256Signer {
+    fn algorithm(&self) -> iana::Algorithm {
+        iana::Algorithm::ES256
+    }
+
+    fn key_id(&self) -> Option<Vec<u8>> {
+        self.1.as_ref().map(|kid| kid.as_bytes().to_vec())
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let signature: Signature = self.0.sign(data);
+        Ok(signature.to_vec())
+    }
+}
+
+struct Es256Verifier(VerifyingKey);
+
+impl Es256Verifier {
+    fn from_pem(pem: &str) -> CliResult<Self> {
+        let key = VerifyingKey::from_public_key_pem(pem)
+            .map_err(|e| format!("invalid public key: {}", e))?;
+        Ok(Self(key))
+    }
+}
+
+impl SignatureVerifier for Es256Verifier {
+    fn verify(
+        &self,
+        algorithm: iana::Algorithm,
+        data: &[u8],
+        signature: &[u8],
+    ) -> Result<(), String> {
+        if algorithm != iana::Algorithm::ES256 {
+            return Err(format!("unsupported algorithm {:?}", algorithm));
+        }
+        let signature = Signature::from_slice(signature).map_err(|e| e.to_string())?;
+        self.0.verify(data, &signature).map_err(|e| e.to_string())
+    }
+}
+
+// The keys a command trusts: a single PEM key or an issuer's JWK set.
+enum Trust {
+    Key(Es256Verifier),
+    Jwks(JwkSet),
+}
+
+impl SignatureVerifier for Trust {
+    fn verify(
+        &self,
+        algorithm: iana::Algorithm,
+        data: &[u8],
+        signature: &[u8],
+    ) -> Result<(), String> {
+        self.verify_with_key_id(None, algorithm, data, signature)
+    }
+
+    fn verify_with_key_id(
+        &self,
+        key_id: Option<&[u8]>,
+        algorithm: iana::Algorithm,
+        data: &[u8],
+        signature: &[u8],
+    ) -> Result<(), String> {
+        match self {
+            Trust::Key(key) => key.verify_with_key_id(key_id, algorithm, data, signature),
+            Trust::Jwks(jwks) => jwks.verify_with_key_id(key_id, algorithm, data, signature),
+        }
+    }
+}
+
+fn read_input(path: &str) -> CliResult<Vec<u8>> {
+    if path == "-" {
+        let mut input = Vec::new();
+        io::stdin().read_to_end(&mut input)?;
+        return Ok(input);
+    }
+    fs::read(path).map_err(|e| format!("{}: {}", path, e).into())
+}
+
+fn is_url(source: &str) -> bool {
+    source.starts_with("https://") || source.starts_with("http://")
+}
+
+// Reads a file, or fetches a URL.
+async fn read_source(source: &str, accept: &str) -> CliResult<Vec<u8>> {
+    if !is_url(source) {
+        return read_input(source);
+    }
+    let response = ReqwestTransport::default().get(source, accept).await?;
+    if !(200..300).contains(&response.status) {
+        return Err(format!("{}: HTTP status {}", source, response.status).into());
+    }
+    Ok(response.body)
+}
+
+async fn load_token(source: &str) -> CliResult<FetchedToken> {
+    if is_url(source) {
+        let uri = StatusListUri::parse_with_schemes(source, &["https", "http"])?;
+        return Ok(StatusListClient::new().fetch_token(&uri).await?);
+    }
+    Ok(read_token(read_input(source)?))
+}
+
+async fn load_jwks(source: &str) -> CliResult<JwkSet> {
+    let jwks = read_source(source, "application/jwk-set+json, application/json").await?;
+    Ok(JwkSet::from_json(&String::from_utf8(jwks)?)?)
+}
+
+async fn load_trust(jwks: Option<&str>, key: Option<&str>) -> CliResult<Option<Trust>> {
+    match (jwks, key) {
+        (Some(jwks), _) => Ok(Some(Trust::Jwks(load_jwks(jwks).await?))),
+        (None, Some(key)) => Ok(Some(Trust::Key(load_key(key)?))),
+        (None, None) => Ok(None),
+    }
+}
+
+// The claims of a JWT or SD-JWT, or None for other tokens. The signature is
+// not checked.
+fn jwt_claims(bytes: &[u8]) -> CliResult<Option<Value>> {
+    let Ok(text) = std::str::from_utf8(bytes) else {
+        return Ok(None);
+    };
+    let jws = text.trim().split('~').next().unwrap_or_default();
+    let Some(payload) = jws.split('.').nth(1) else {
+        return Ok(None);
+    };
+    Ok(Some(serde_json::from_slice(&base64url::decode(payload)?)?))
+}
+
+// One claim of a CWT, as CBOR. The signature is not checked.
+fn cwt_claim(bytes: &[u8], key: i64) -> CliResult<Vec<u8>> {
+    let sign1 = CoseSign1::from_tagged_slice(bytes)
+        .or_else(|_| CoseSign1::from_slice(bytes))
+        .map_err(|e| format!("not a JWT or CWT: {}", e))?;
+    let claims: ciborium::Value =
+        ciborium::de::from_reader(sign1.payload.as_deref().unwrap_or_default())?;
+    let claim = claims
+        .as_map()
+        .and_then(|claims| {
+            claims
+                .iter()
+                .find(|(claim, _)| claim.as_integer() == Some(key.into()))
+        })
+        .map(|(_, value)| value)
+        .ok_or(format!("token has no claim {}", key))?;
+    let mut cbor = Vec::new();
+    ciborium::ser::into_writer(claim, &mut cbor)?;
+    Ok(cbor)
+}
+
+// Takes the `status` claim from a JWT or SD-JWT, or from claim 65535 of a
+// CWT. The token's own signature is not checked.
+fn read_reference(bytes: &[u8]) -> CliResult<StatusListReference> {
+    if let Some(claims) = jwt_claims(bytes)? {
+        let status = claims.get("status").ok_or("token has no status claim")?;
+        return Ok(StatusClaim::from_json(&status.to_string())?.status_list);
+    }
+    Ok(StatusClaim::from_cbor(&cwt_claim(bytes, 65535)?)?.status_list)
+}
+
+// The list and ttl of a status list token, checked against `trust` when
+// given.
+fn token_list(token: &FetchedToken, trust: Option<&Trust>) -> CliResult<(StatusList, Option<u64>)> {
+    match (token, trust) {
+        (FetchedToken::Cwt(bytes), Some(trust)) => {
+            let token = CwtStatusListToken::verify(bytes, trust)?;
+            Ok((token.status_list, token.ttl))
+        }
+        (FetchedToken::Jwt(jwt), Some(trust)) => {
+            let token = JwtStatusListToken::verify(jwt, trust)?;
+            Ok((token.status_list, token.ttl))
+        }
+        (FetchedToken::Cwt(bytes), None) => {
+            let ttl = cwt_claim(bytes, 65534)
+                .ok()
+                .and_then(|ttl| ciborium::de::from_reader(&ttl[..]).ok());
+            Ok((StatusList::from_cbor(&cwt_claim(bytes, 65533)?)?, ttl))
+        }
+        (FetchedToken::Jwt(jwt), None) => {
+            let claims = jwt_claims(jwt.as_bytes())?.ok_or("not a JWT")?;
+            let status_list = claims
+                .get("status_list")
+                .ok_or("token has no status_list claim")?;
+            Ok((
+                StatusList::from_json(&status_list.to_string())?,
+                claims.get("ttl").and_then(Value::as_u64),
+            ))
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ChangeRecord {
+    index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    old: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new: Option<String>,
+}
+
+impl fmt::Display for ChangeRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => writeln!(f, "{}: {} -> {}", self.index, old, new),
+            (None, Some(new)) => writeln!(f, "{}: + {}", self.index, new),
+            (Some(old), None) => writeln!(f, "{}: - {}", self.index, old),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+struct ChangeList(Vec<ChangeRecord>);
+
+impl fmt::Display for ChangeList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|change| write!(f, "{}", change))
+    }
+}
+
+fn describe_changes(diff: &StatusListDiff) -> ChangeList {
+    let registry = StatusRegistry::default();
+    let name = |status| Some(registry.describe(status).to_string());
+    ChangeList(
+        diff.changes
+            .iter()
+            .map(|change| match *change {
+                StatusChange::Added { index, status } => ChangeRecord {
+                    index,
+                    old: None,
+                    new: name(status),
+                },
+                StatusChange::Removed { index, status } => ChangeRecord {
+                    index,
+                    old: name(status),
+                    new: None,
+                },
+                StatusChange::Modified { index, old, new } => ChangeRecord {
+                    index,
+                    old: name(old),
+                    new: name(new),
+                },
+            })
+            .collect(),
+    )
+}
+
+async fn poll(
+    client: &StatusListClient,
+    uri: &StatusListUri,
+    trust: Option<&Trust>,
+) -> CliResult<(StatusListDecoder, Option<u64>)> {
+    let token = client.fetch_token(uri).await?;
+    if let Some(trust) = trust {
+        verify_token(&token, trust, Some(uri.as_str()), now_or(None), 0)?;
+    }
+    let (status_list, ttl) = token_list(&token, None)?;
+    Ok((StatusListDecoder::new(&status_list)?, ttl))
+}
+
+// What one poll of `tsl watch` prints: the list size on the first poll,
+// then one line per change, each prefixed with the poll time.
+fn watch_step(
+    previous: Option<&StatusListDecoder>,
+    current: &StatusListDecoder,
+    now: u64,
+    output: OutputFormat,
+) -> CliResult<String> {
+    let Some(previous) = previous else {
+        return Ok(match output {
+            OutputFormat::Text => format!("{} watching {} statuses\n", now, current.len()),
+            OutputFormat::Json => format!("{}\n", json!({"time": now, "statuses": current.len()})),
+        });
+    };
+    let mut lines = String::new();
+    for change in describe_changes(&previous.diff(current)).0 {
+        match output {
+            OutputFormat::Text => lines.push_str(&format!("{} {}", now, change)),
+            OutputFormat::Json => {
+                let mut record = serde_json::to_value(&change)?;
+                record["time"] = json!(now);
+                lines.push_str(&format!("{}\n", record));
+            }
+        }
+    }
+    Ok(lines)
+}
+
+fn check(
+    token: &FetchedToken,
+    verifier: &impl SignatureVerifier,
+    reference: &StatusListReference,
+    now: u64,
+    leeway: u64,
+) -> CliResult<StatusCheckReport> {
+    let checker = StatusListTokenVerifier::new(reference.uri.clone()).with_leeway(leeway);
+    Ok(match token {
+        FetchedToken::Cwt(bytes) => checker.check_cwt(bytes, verifier, reference.idx, now)?,
+        FetchedToken::Jwt(jwt) => checker.check_jwt(jwt, verifier, reference.idx, now)?,
+    })
+}
+
+#[derive(Serialize)]
+struct CheckOutput {
+    #[serde(flatten)]
+    report: StatusCheckReport,
+    status_name: String,
+}
+
+impl CheckOutput {
+    fn new(report: StatusCheckReport) -> Self {
+        let status_name = StatusRegistry::default()
+            .describe(StatusType::from(report.status))
+            .to_string();
+        Self {
+            report,
+            status_name,
+        }
+    }
+}
+
+impl fmt::Display for CheckOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let report = &self.report;
+        write!(
+            f,
+            "uri: {}\nidx: {}\nstatus: {}\ntoken_iat: {}\n",
+            report.uri, report.idx, self.status_name, report.token_iat
+        )?;
+        if let Some(exp) = report.token_exp {
+            writeln!(f, "token_exp: {}", exp)?;
+        }
+        if let Some(reason) = &report.reason {
+            writeln!(f, "reason: {}", reason)?;
+        }
+        writeln!(f, "token_sha256: {}", report.token_sha256)
+    }
+}
+
+// Like `read_list`, but also takes status list token files.
+fn read_list_or_token(path: &str, trust: Option<&Trust>) -> CliResult<StatusList> {
+    let input = read_input(path)?;
+    let is_token = jwt_claims(&input).is_ok_and(|claims| claims.is_some())
+        || CoseSign1::from_tagged_slice(&input).is_ok()
+        || CoseSign1::from_slice(&input).is_ok();
+    if !is_token {
+        return read_list(path);
+    }
+    Ok(token_list(&read_token(input), trust)?.0)
+}
+
+// Status list files are either the JSON `status_list` object or its CBOR form.
+fn read_list(path: &str) -> CliResult<StatusList> {
+    let input = read_input(path)?;
+    match std::str::from_utf8(&input) {
+        Ok(text) if text.trim_start().starts_with('{') => Ok(StatusList::from_json(text)?),
+        _ => Ok(StatusList::from_cbor(&input)?),
+    }
+}
+
+fn write_list(status_list: &StatusList, cbor: bool) -> CliResult<()> {
+    let mut out = io::stdout().lock();
+    if cbor {
+        out.write_all(&status_list.to_cbor_bytes()?)?;
+    } else {
+        writeln!(out, "{}", status_list.to_json()?)?;
+    }
+    Ok(())
+}
+
+fn parse_status(value: &str, bits: u8) -> CliResult<StatusType> {
+    let value = value.trim();
+    let status = StatusRegistry::default()
+        .lookup(&value.to_ascii_uppercase())
+        .or_else(|| match value.strip_prefix("0x") {
+            Some(hex) => u8::from_str_radix(hex, 16).ok().map(StatusType::from),
+            None => value.parse::<u8>().ok().map(StatusType::from),
+        })
+        .ok_or_else(|| format!("unknown status {}", value))?;
+    if (status.value() as u16) >> bits != 0 {
+        return Err(format!("status {} does not fit in {} bits", status.value(), bits).into());
+    }
+    Ok(status)
+}
+
+// A JSON array holds one status per index, or `{"idx", "status"}` objects.
+// CSV rows are `idx,status`, or a bare status for the next index; an
+// `idx,status` header row is skipped.
+fn parse_statuses(input: &str, bits: u8) -> CliResult<Vec<(usize, StatusType)>> {
+    if input.trim_start().starts_with('[') {
+        let values: Vec<Value> = serde_json::from_str(input)?;
+        return values
+            .iter()
+            .enumerate()
+            .map(|(entry, value)| {
+                let (index, value) = match value {
+                    Value::Object(object) => (
+                        object
+                            .get("idx")
+                            .and_then(Value::as_u64)
+                            .ok_or_else(|| format!("entry {}: missing idx", entry))?
+                            as usize,
+                        object.get("status").unwrap_or(&Value::Null),
+                    ),
+                    value => (entry, value),
+                };
+                let status = match value {
+                    Value::String(name) => parse_status(name, bits),
+                    value => parse_status(&value.to_string(), bits),
+                };
+                status
+                    .map(|status| (index, status))
+                    .map_err(|e| format!("entry {}: {}", entry, e).into())
+            })
+            .collect();
+    }
+
+    let mut statuses = Vec::new();
+    let mut next = 0;
+    for (number, row) in input.lines().enumerate() {
+        let row = row.trim();
+        if row.is_empty() || (number == 0 && row.replace(' ', "") == "idx,status") {
+            continue;
+        }
+        let (index, status) = match row.split_once(',') {
+            Some((index, status)) => (
+                index
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| format!("line {}: invalid index {}", number + 1, index))?,
+                status,
+            ),
+            None => (next, row),
+        };
+        let status =
+            parse_status(status, bits).map_err(|e| format!("line {}: {}", number + 1, e))?;
+        statuses.push((index, status));
+        next = index + 1;
+    }
+    Ok(statuses)
+}
+
+fn create(input: &str, bits: u8) -> CliResult<StatusList> {
+    let builder = StatusListBuilder::new(bits)?;
+    for (index, status) in parse_statuses(input, bits)? {
+        builder.try_set_status(index, status)?;
+    }
+    Ok(builder.build()?)
+}
+
+fn build(args: &BuildArgs, dump: &str, signer: &impl Signer) -> CliResult<FetchedToken> {
+    let default = parse_status(&args.default, args.bits)?;
+    let builder = StatusListBuilder::new(args.bits)?.with_default_status(default)?;
+    for (index, status) in parse_statuses(dump, args.bits)? {
+        if args.size.is_some_and(|size| index >= size) {
+            return Err(format!("index {} is past --size", index).into());
+        }
+        builder.try_set_status(index, status)?;
+    }
+    if let Some(size) = args.size {
+        builder.try_pad_to(size)?;
+    }
+
+    let sub = StatusListUri::parse_with_schemes(&args.uri, &["https", "http"])?;
+    let iat = now_or(args.now);
+    let status_list = builder.build()?;
+    Ok(match args.form {
+        TokenForm::Jwt => {
+            let mut token = JwtStatusListToken::new(sub, iat, status_list);
+            token.exp = args.lifetime.map(|lifetime| iat + lifetime);
+            token.ttl = args.ttl;
+            FetchedToken::Jwt(token.sign(signer)?)
+        }
+        TokenForm::Cwt => {
+            let mut token = CwtStatusListToken::new(sub, iat, status_list);
+            token.exp = args.lifetime.map(|lifetime| iat + lifetime);
+            token.ttl = args.ttl;
+            FetchedToken::Cwt(token.sign(signer)?)
+        }
+    })
+}
+
+fn set_status(status_list: &StatusList, index: usize, status: StatusType) -> CliResult<StatusList> {
+    let decoder = StatusListDecoder::new(status_list)?;
+    let statuses = (0..decoder.len())
+        .map(|index| decoder.get_status(index))
+        .collect::<Result<Vec<_>, _>>()?;
+    let builder = StatusListBuilder::from_vec(statuses, status_list.bits())?;
+    builder.try_set_status(index, status)?;
+
+    let updated = builder.build()?;
+    Ok(match status_list.aggregation_uri() {
+        Some(uri) => updated.with_aggregation_uri(uri),
+        None => updated,
+    })
+}
+
+#[derive(Serialize)]
+struct StatusCount {
+    status: String,
+    value: u8,
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct ListReport {
+    bits: u8,
+    statuses: usize,
+    compressed_bytes: usize,
+    decompressed_bytes: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    aggregation_uri: Option<String>,
+    counts: Vec<StatusCount>,
+    revocation_rate: f64,
+    /// Spec violations; empty when the list conforms
+    conformance: Vec<String>,
+}
+
+impl fmt::Display for ListReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "bits: {}\nstatuses: {}\ncompressed: {} bytes\ndecompressed: {} bytes\n",
+            self.bits, self.statuses, self.compressed_bytes, self.decompressed_bytes
+        )?;
+        if let Some(uri) = &self.aggregation_uri {
+            writeln!(f, "aggregation_uri: {}", uri)?;
+        }
+        for count in &self.counts {
+            writeln!(f, "{}: {}", count.status, count.count)?;
+        }
+        writeln!(f, "revocation rate: {:.2}%", self.revocation_rate * 100.0)?;
+        if self.conformance.is_empty() {
+            writeln!(f, "conformance: ok")?;
+        }
+        for violation in &self.conformance {
+            writeln!(f, "conformance: {}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+fn inspect(status_list: &StatusList) -> CliResult<ListReport> {
+    let decoder = StatusListDecoder::new(status_list)?;
+    let stats = decoder.stats();
+    let registry = StatusRegistry::default();
+
+    Ok(ListReport {
+        bits: status_list.bits(),
+        statuses: decoder.len(),
+        compressed_bytes: status_list.lst().len(),
+        decompressed_bytes: decoder.raw_byte_len(),
+        aggregation_uri: status_list.aggregation_uri().map(str::to_string),
+        counts: stats
+            .counts
+            .iter()
+            .map(|(&value, &count)| StatusCount {
+                status: registry.describe(StatusType::from(value)).to_string(),
+                value,
+                count,
+            })
+            .collect(),
+        revocation_rate: stats.revocation_rate(),
+        conformance: match status_list.is_conformant() {
+            Ok(()) => Vec::new(),
+            Err(violations) => violations.iter().map(ToString::to_string).collect(),
+        },
+    })
+}
+
+// Token files hold a CWT, or a JWT in compact serialization.
+fn read_token(bytes: Vec<u8>) -> FetchedToken {
+    match String::from_utf8(bytes) {
+        Ok(text) if text.trim().split('.').count() == 3 => {
+            FetchedToken::Jwt(text.trim().to_string())
+        }
+        Ok(text) => FetchedToken::Cwt(text.into_bytes()),
+        Err(e) => FetchedToken::Cwt(e.into_bytes()),
+    }
+}
+
+#[derive(Serialize)]
+struct TokenReport {
+    sub: String,
+    iat: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exp: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ttl: Option<u64>,
+    status_list: ListReport,
+}
+
+impl fmt::Display for TokenReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "signature: ok\nsub: {}\niat: {}\n", self.sub, self.iat)?;
+        if let Some(exp) = self.exp {
+            writeln!(f, "exp: {}", exp)?;
+        }
+        if let Some(ttl) = self.ttl {
+            writeln!(f, "ttl: {}", ttl)?;
+        }
+        write!(f, "{}", self.status_list)
+    }
+}
+
+fn now_or(now: Option<u64>) -> u64 {
+    now.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0)
+    })
+}
+
+fn load_key(path: &str) -> CliResult<Es256Verifier> {
+    Es256Verifier::from_pem(&String::from_utf8(read_input(path)?)?)
+}
+
+fn verify_token(
+    token: &FetchedToken,
+    verifier: &impl SignatureVerifier,
+    uri: Option<&str>,
+    now: u64,
+    leeway: u64,
+) -> CliResult<TokenReport> {
+    let (sub, iat, exp, ttl, status_list) = match token {
+        FetchedToken::Cwt(bytes) => {
+            let token = CwtStatusListToken::verify(bytes, verifier)?;
+            (
+                token.sub,
+                token.iat,
+                token.exp,
+                token.ttl,
+                token.status_list,
+            )
+        }
+        FetchedToken::Jwt(jwt) => {
+            let token = JwtStatusListToken::verify(jwt, verifier)?;
+            (
+                token.sub,
+                token.iat,
+                token.exp,
+                token.ttl,
+                token.status_list,
+            )
+        }
+    };
+    if let Some(uri) = uri {
+        let checker = StatusListTokenVerifier::new(StatusListUri::parse_with_schemes(
+            uri,
+            &["https", "http"],
+        )?)
+        .with_leeway(leeway);
+        match token {
+            FetchedToken::Cwt(bytes) => checker.verify_cwt(bytes, verifier, now)?,
+            FetchedToken::Jwt(jwt) => checker.verify_jwt(jwt, verifier, now)?,
+        };
+    }
+    Ok(TokenReport {
+        sub: sub.to_string(),
+        iat,
+        exp,
+        ttl,
+        status_list: inspect(&status_list)?,
+    })
+}
+
+#[derive(Serialize)]
+struct HistogramRow {
+    status: String,
+    count: usize,
+    share: f64,
+}
+
+#[derive(Serialize)]
+struct VerifyReport {
+    #[serde(flatten)]
+    token: TokenReport,
+    histogram: Vec<HistogramRow>,
+}
+
+impl fmt::Display for VerifyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}histogram:", self.token)?;
+        for row in &self.histogram {
+            writeln!(
+                f,
+                "  {:<20} {:>10} {:>7.2}%",
+                row.status, row.count, row.share
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// One row per status present: its count and share of the list.
+fn histogram(token: TokenReport) -> VerifyReport {
+    let list = &token.status_list;
+    let histogram = list
+        .counts
+        .iter()
+        .map(|count| HistogramRow {
+            status: count.status.clone(),
+            count: count.count,
+            share: count.count as f64 * 100.0 / list.statuses.max(1) as f64,
+        })
+        .collect();
+    VerifyReport { token, histogram }
+}
+
+#[derive(Serialize)]
+struct FetchReport {
+    bytes: usize,
+    media_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    saved_to: Option<String>,
+    /// CWT spec violations; absent for JWTs, which are not checked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    conformance: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token: Option<TokenReport>,
+}
+
+impl fmt::Display for FetchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "fetched: {} bytes of {}", self.bytes, self.media_type)?;
+        match self.conformance.as_deref() {
+            Some([]) => writeln!(f, "conformance: ok")?,
+            Some(violations) => {
+                for violation in violations {
+                    writeln!(f, "conformance: {}", violation)?;
+                }
+            }
+            None => (),
+        }
+        match &self.token {
+            Some(token) => write!(f, "{}", token),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct BuildReport {
+    path: String,
+    bytes: usize,
+    media_type: String,
+}
+
+impl fmt::Display for BuildReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "wrote: {} bytes of {} to {}",
+            self.bytes, self.media_type, self.path
+        )
+    }
+}
+
+fn emit(output: OutputFormat, report: &(impl Serialize + fmt::Display)) -> CliResult<()> {
+    match output {
+        OutputFormat::Text => print!("{}", report),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(report)?),
+    }
+    Ok(())
+}
+
+async fn run(cli: Cli) -> CliResult<()> {
+    let output = cli.output;
+    match cli.command {
+        Command::Create { input, bits, cbor } => {
+            let input = String::from_utf8(read_input(&input)?)?;
+            write_list(&create(&input, bits)?, cbor)
+        }
+        Command::Build(args) => {
+            let dump = args.from_json.as_ref().or(args.from_csv.as_ref()).unwrap();
+            let dump = String::from_utf8(read_input(dump)?)?;
+            let signer = Es256Signer::from_pem(
+                &String::from_utf8(read_input(&args.key)?)?,
+                args.kid.clone(),
+            )?;
+            let token = build(&args, &dump, &signer)?;
+            fs::write(&args.out, token.as_bytes()).map_err(|e| format!("{}: {}", args.out, e))?;
+            emit(
+                output,
+                &BuildReport {
+                    path: args.out,
+                    bytes: token.as_bytes().len(),
+                    media_type: token.media_type().to_string(),
+                },
+            )
+        }
+        Command::SetStatus {
+            list,
+            index,
+            status,
+            cbor,
+        } => {
+            let status_list = read_list(&list)?;
+            let status = parse_status(&status, status_list.bits())?;
+            write_list(&set_status(&status_list, index, status)?, cbor)
+        }
+        Command::Decode { list } => {
+            StatusListDecoder::new(&read_list(&list)?)?.export_jsonl(io::stdout().lock())?;
+            Ok(())
+        }
+        Command::Inspect { list } => emit(output, &inspect(&read_list(&list)?)?),
+        Command::Diff {
+            old,
+            new,
+            summary,
+            bucket_size,
+            jwks,
+            key,
+        } => {
+            let trust = load_trust(jwks.as_deref(), key.as_deref()).await?;
+            let diff = read_list_or_token(&old, trust.as_ref())?
+                .diff(&read_list_or_token(&new, trust.as_ref())?)?;
+            if summary {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&diff.summarize(bucket_size))?
+                );
+                Ok(())
+            } else {
+                emit(output, &describe_changes(&diff))
+            }
+        }
+        Command::Watch {
+            uri,
+            jwks,
+            key,
+            interval,
+            count,
+        } => {
+            let uri = StatusListUri::parse_with_schemes(&uri, &["https", "http"])?;
+            let trust = load_trust(jwks.as_deref(), key.as_deref()).await?;
+            let client = StatusListClient::new();
+            let mut previous = None;
+            let mut polls = 0;
+            loop {
+                // A failed poll is reported and retried; the watch goes on.
+                let mut wait = interval;
+                match poll(&client, &uri, trust.as_ref()).await {
+                    Ok((decoder, ttl)) => {
+                        print!(
+                            "{}",
+                            watch_step(previous.as_ref(), &decoder, now_or(None), output)?
+                        );
+                        io::stdout().flush()?;
+                        previous = Some(decoder);
+                        wait = ttl.unwrap_or(interval);
+                    }
+                    Err(e) => report_error(output, &*e),
+                }
+                polls += 1;
+                if count.is_some_and(|count| polls >= count) {
+                    return Ok(());
+                }
+                tokio::time::sleep(Duration::from_secs(wait.max(1))).await;
+            }
+        }
+        Command::VerifyToken {
+            token,
+            key,
+            uri,
+            now,
+            leeway,
+        } => {
+            let token = read_token(read_input(&token)?);
+            let verifier = load_key(&key)?;
+            emit(
+                output,
+                &verify_token(&token, &verifier, uri.as_deref(), now_or(now), leeway)?,
+            )
+        }
+        Command::Verify {
+            token,
+            jwks,
+            expect_sub,
+            now,
+            leeway,
+        } => {
+            let token = load_token(&token).await?;
+            let jwks = load_jwks(&jwks).await?;
+            let report = verify_token(&token, &jwks, Some(&expect_sub), now_or(now), leeway)?;
+            emit(output, &histogram(report))
+        }
+        Command::Check {
+            uri,
+            idx,
+            token,
+            jwks,
+            key,
+            now,
+            leeway,
+        } => {
+            let referenced = token.map(|token| read_input(&token)).transpose()?;
+            let reference = match (&referenced, uri, idx) {
+                (Some(referenced), _, _) => read_reference(referenced)?,
+                (None, Some(uri), Some(idx)) => StatusListReference::new(
+                    idx,
+                    StatusListUri::parse_with_schemes(&uri, &["https", "http"])?,
+                ),
+                _ => return Err("--token or --uri with --idx is required".into()),
+            };
+            let trust = load_trust(jwks.as_deref(), key.as_deref())
+                .await?
+                .ok_or("--jwks or --key is required")?;
+            let checker = StatusListTokenVerifier::new(reference.uri.clone()).with_leeway(leeway);
+            // A list embedded in a JWT or SD-JWT is used instead of fetching.
+            let embedded = match &referenced {
+                Some(referenced) if jwt_claims(referenced)?.is_some() => checker.check_embedded(
+                    std::str::from_utf8(referenced)?,
+                    &trust,
+                    reference.idx,
+                    now_or(now),
+                )?,
+                _ => None,
+            };
+            let report = match embedded {
+                Some(report) => report,
+                None => {
+                    let token = StatusListClient::new().fetch_token(&reference.uri).await?;
+                    check(&token, &trust, &reference, now_or(now), leeway)?
+                }
+            };
+            emit(output, &CheckOutput::new(report))
+        }
+        Command::Fetch { uri, out, key, now } => {
+            let uri = StatusListUri::parse_with_schemes(&uri, &["https", "http"])?;
+            let token = StatusListClient::new().fetch_token(&uri).await?;
+            if let Some(out) = &out {
+                fs::write(out, token.as_bytes()).map_err(|e| format!("{}: {}", out, e))?;
+            }
+            let conformance = match &token {
+                FetchedToken::Cwt(bytes) => {
+                    Some(match CwtStatusListToken::check_conformance(bytes) {
+                        Ok(()) => Vec::new(),
+                        Err(violations) => violations.iter().map(ToString::to_string).collect(),
+                    })
+                }
+                FetchedToken::Jwt(_) => None,
+            };
+            let verified = match key {
+                Some(key) => Some(verify_token(
+                    &token,
+                    &load_key(&key)?,
+                    Some(uri.as_str()),
+                    now_or(now),
+                    0,
+                )?),
+                None => None,
+            };
+            emit(
+                output,
+                &FetchReport {
+                    bytes: token.as_bytes().len(),
+                    media_type: token.media_type().to_string(),
+                    saved_to: out,
+                    conformance,
+                    token: verified,
+                },
+            )
+        }
+    }
+}
+
+fn report_error(output: OutputFormat, error: &dyn Error) {
+    match output {
+        OutputFormat::Text => eprintln!("tsl: {}", error),
+        OutputFormat::Json => eprintln!("{}", json!({"error": error.to_string()})),
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let output = cli.output;
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            report_error(output, &*e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::pkcs8::{EncodePublicKey, LineEnding};
+
+    #[test]
+    fn test_cli() {
+        use clap::CommandFactory;
+
+        Cli::command().debug_assert();
+        assert!(
+            Cli::try_parse_from(["tsl", "build", "--uri", "u", "--key", "k", "-o", "t"]).is_err()
+        );
+        let cli = Cli::try_parse_from(["tsl", "inspect", "list.json", "--output", "json"]).unwrap();
+        assert_eq!(cli.output, OutputFormat::Json);
+        let cli = Cli::try_parse_from(["tsl", "fetch", "https://x", "--out", "t.cwt"]).unwrap();
+        assert_eq!(cli.output, OutputFormat::Text);
+        assert!(matches!(cli.command, Command::Fetch { out: Some(_), .. }));
+    }
+
+    #[test]
+    fn test_parse_statuses() {
+        let csv = "idx,status\n0,INVALID\n3, 0x02\nSUSPENDED\n\n";
+        assert_eq!(
+            parse_statuses(csv, 2).unwrap(),
+            vec![
+                (0, StatusType::Invalid),
+                (3, StatusType::Suspended),
+                (4, StatusType::Suspended),
+            ]
+        );
+        assert_eq!(
+            parse_statuses(r#"[0, "INVALID", 2]"#, 2).unwrap(),
+            vec![
+                (0, StatusType::Valid),
+                (1, StatusType::Invalid),
+                (2, StatusType::Suspended),
+            ]
+        );
+
+        assert_eq!(
+            parse_statuses(
+                r#"[{"idx": 5, "status": "invalid"}, {"idx": 2, "status": 1}]"#,
+                1
+            )
+            .unwrap(),
+            vec![(5, StatusType::Invalid), (2, StatusType::Invalid)]
+        );
+
+        assert_eq!(
+            parse_statuses("0,VALID\n1,4", 2).unwrap_err().to_string(),
+            "line 2: status 4 does not fit in 2 bits"
+        );
+        assert_eq!(
+            parse_statuses("x,1", 1).unwrap_err().to_string(),
+            "line 1: invalid index x"
+        );
+    }
+
+    #[test]
+    fn test_create_set_status_inspect() {
+        let status_list = create("0,VALID\n1,INVALID\n7,SUSPENDED", 2).unwrap();
+        let decoder = StatusListDecoder::new(&status_list).unwrap();
+        assert_eq!(decoder.get_status(1).unwrap(), StatusType::Invalid);
+        assert_eq!(decoder.get_status(7).unwrap(), StatusType::Suspended);
+
+        let updated = set_status(&status_list, 1, StatusType::Valid).unwrap();
+        assert_eq!(
+            StatusListDecoder::new(&updated)
+                .unwrap()
+                .get_status(1)
+                .unwrap(),
+            StatusType::Valid
+        );
+
+        let report = inspect(&status_list).unwrap().to_string();
+        assert!(report.starts_with("bits: 2\nstatuses: 8\n"));
+        assert!(report.contains("VALID: 6\nINVALID: 1\nSUSPENDED: 1\n"));
+        assert!(report.contains("revocation rate: 12.50%\n"));
+        assert!(report.ends_with("conformance: ok\n"));
+    }
+
+    #[test]
+    fn test_verify_token() {
+        let signing_key = SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let pem = signing_key
+            .verifying_key()
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap();
+        let key_path = std::env::temp_dir().join(format!("tsl-key-{}.pem", std::process::id()));
+        fs::write(&key_path, pem).unwrap();
+        let key = load_key(key_path.to_str().unwrap()).unwrap();
+
+        let uri = "https://example.com/statuslists/1";
+        let signer = Es256Signer(signing_key, None);
+        let cwt = CwtStatusListToken::new(
+            StatusListUri::parse(uri).unwrap(),
+            1000,
+            create("1,INVALID", 1).unwrap(),
+        )
+        .with_exp(2000)
+        .sign(&signer)
+        .unwrap();
+        let token = read_token(cwt.clone());
+        assert_eq!(token, FetchedToken::Cwt(cwt.clone()));
+
+        let report = verify_token(&token, &key, Some(uri), 1500, 0)
+            .unwrap()
+            .to_string();
+        assert!(report.starts_with(
+            "signature: ok\nsub: https://example.com/statuslists/1\niat: 1000\nexp: 2000\n"
+        ));
+        assert!(verify_token(&token, &key, Some(uri), 2500, 0).is_err());
+
+        let mut tampered = cwt;
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(verify_token(&read_token(tampered), &key, None, 1500, 0).is_err());
+
+        let jwt = JwtStatusListToken::new(
+            StatusListUri::parse(uri).unwrap(),
+            1000,
+            create("1,INVALID", 1).unwrap(),
+        )
+        .sign(&signer)
+        .unwrap();
+        let token = read_token(format!("{}\n", jwt).into_bytes());
+        assert_eq!(token, FetchedToken::Jwt(jwt));
+        let report = verify_token(&token, &key, Some(uri), 1500, 0).unwrap();
+        assert_eq!(report.status_list.counts[1].status, "INVALID");
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["sub"], uri);
+        assert_eq!(json["status_list"]["counts"][1]["count"], 1);
+        assert!(json.get("exp").is_none());
+        assert!(report
+            .to_string()
+            .starts_with("signature: ok\nsub: https://example.com/statuslists/1\n"));
+        assert!(verify_token(&token, &key, Some("https://example.com/x"), 1500, 0).is_err());
+
+        fs::remove_file(key_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_verify_with_jwks() {
+        let signing_key = SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let point = signing_key.verifying_key().to_encoded_point(false);
+        let jwks_path = std::env::temp_dir().join(format!("tsl-jwks-{}.json", std::process::id()));
+        fs::write(
+            &jwks_path,
+            serde_json::json!({"keys": [{
+                "kty": "EC",
+                "crv": "P-256",
+                "kid": "k1",
+                "x": base64url::encode(point.x().unwrap()),
+                "y": base64url::encode(point.y().unwrap()),
+            }]})
+            .to_string(),
+        )
+        .unwrap();
+        let jwks = load_jwks(jwks_path.to_str().unwrap()).await.unwrap();
+
+        let uri = "https://example.com/statuslists/1";
+        let jwt = JwtStatusListToken::new(
+            StatusListUri::parse(uri).unwrap(),
+            1000,
+            create("1,INVALID\n3,VALID", 1).unwrap(),
+        )
+        .sign(&Es256Signer(signing_key, Some("k1".to_string())))
+        .unwrap();
+        let report = histogram(
+            verify_token(&FetchedToken::Jwt(jwt.clone()), &jwks, Some(uri), 1500, 0).unwrap(),
+        );
+        assert_eq!(
+            serde_json::to_value(&report).unwrap()["histogram"],
+            serde_json::json!([
+                {"status": "VALID", "count": 7, "share": 87.5},
+                {"status": "INVALID", "count": 1, "share": 12.5},
+            ])
+        );
+        assert!(report.to_string().ends_with(&format!(
+            "histogram:\n  {:<20} {:>10} {:>7}%\n  {:<20} {:>10} {:>7}%\n",
+            "VALID", 7, "87.50", "INVALID", 1, "12.50"
+        )));
+        assert!(verify_token(
+            &FetchedToken::Jwt(jwt),
+            &JwkSet::default(),
+            Some(uri),
+            1500,
+            0
+        )
+        .is_err());
+        fs::remove_file(jwks_path).unwrap();
+    }
+
+    #[test]
+    fn test_build() {
+        let signing_key = SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let verifier = Es256Verifier(*signing_key.verifying_key());
+        let signer = Es256Signer(signing_key, Some("k1".to_string()));
+        let mut args = BuildArgs {
+            from_json: None,
+            from_csv: Some("dump.csv".to_string()),
+            size: Some(16),
+            default: "invalid".to_string(),
+            bits: 2,
+            uri: "https://example.com/statuslists/1".to_string(),
+            key: "key.pem".to_string(),
+            kid: None,
+            form: TokenForm::Jwt,
+            ttl: Some(300),
+            lifetime: Some(3600),
+            now: Some(1000),
+            out: "token.jwt".to_string(),
+        };
+
+        let FetchedToken::Jwt(jwt) =
+            build(&args, "idx,status\n0,VALID\n3,SUSPENDED", &signer).unwrap()
+        else {
+            panic!("Expected a JWT");
+        };
+        let token = JwtStatusListToken::verify(&jwt, &verifier).unwrap();
+        assert_eq!(
+            (token.iat, token.exp, token.ttl),
+            (1000, Some(4600), Some(300))
+        );
+        let decoder = StatusListDecoder::new(&token.status_list).unwrap();
+        assert_eq!(decoder.len(), 16);
+        assert_eq!(decoder.get_status(0).unwrap(), StatusType::Valid);
+        assert_eq!(decoder.get_status(1).unwrap(), StatusType::Invalid);
+        assert_eq!(decoder.get_status(3).unwrap(), StatusType::Suspended);
+        assert_eq!(decoder.get_status(15).unwrap(), StatusType::Invalid);
+
+        args.form = TokenForm::Cwt;
+        let FetchedToken::Cwt(cwt) = build(&args, "[0, 1]", &signer).unwrap() else {
+            panic!("Expected a CWT");
+        };
+        assert!(CwtStatusListToken::verify(&cwt, &verifier).is_ok());
+
+        assert_eq!(
+            build(&args, "16,INVALID", &signer).unwrap_err().to_string(),
+            "index 16 is past --size"
+        );
+    }
+
+    #[test]
+    fn test_read_reference() {
+        let uri = "https://example.com/statuslists/1";
+        let claim = StatusClaim::new(42, StatusListUri::parse(uri).unwrap());
+        let payload = base64url::encode(format!(
+            r#"{{"sub":"x","status":{}}}"#,
+            claim.to_json().unwrap()
+        ));
+        let jwt = format!("eyJhbGciOiJFUzI1NiJ9.{}.sig", payload);
+        assert_eq!(read_reference(jwt.as_bytes()).unwrap(), claim.status_list);
+        let sd_jwt = format!("{}~disclosure~", jwt);
+        assert_eq!(
+            read_reference(sd_jwt.as_bytes()).unwrap(),
+            claim.status_list
+        );
+
+        let status: ciborium::Value =
+            ciborium::de::from_reader(&claim.to_cbor_bytes().unwrap()[..]).unwrap();
+        let claims = ciborium::Value::Map(vec![
+            (ciborium::Value::from(2), ciborium::Value::from("holder")),
+            (ciborium::Value::from(65535), status),
+        ]);
+        let mut payload = Vec::new();
+        ciborium::ser::into_writer(&claims, &mut payload).unwrap();
+        let cwt = coset::CoseSign1Builder::new()
+            .payload(payload)
+            .build()
+            .to_tagged_vec()
+            .unwrap();
+        assert_eq!(read_reference(&cwt).unwrap(), claim.status_list);
+
+        assert!(read_reference(b"x.e30.y").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let signing_key = SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let trust = Trust::Key(Es256Verifier(*signing_key.verifying_key()));
+        let uri = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        let jwt = JwtStatusListToken::new(uri.clone(), 1000, create("1,INVALID", 1).unwrap())
+            .with_exp(2000)
+            .sign(&Es256Signer(signing_key, None))
+            .unwrap();
+        let token = FetchedToken::Jwt(jwt);
+
+        let report = check(
+            &token,
+            &trust,
+            &StatusListReference::new(1, uri.clone()),
+            1500,
+            0,
+        )
+        .unwrap();
+        let output = CheckOutput::new(report);
+        assert_eq!(
+            serde_json::to_value(&output).unwrap()["status_name"],
+            "INVALID"
+        );
+        assert!(output.to_string().starts_with(
+            "uri: https://example.com/statuslists/1\nidx: 1\nstatus: INVALID\ntoken_iat: 1000\ntoken_exp: 2000\n"
+        ));
+        let other = StatusListUri::parse("https://example.com/statuslists/2").unwrap();
+        assert!(check(&token, &trust, &StatusListReference::new(1, other), 1500, 0).is_err());
+        assert!(check(&token, &trust, &StatusListReference::new(1, uri), 2500, 0).is_err());
+    }
+
+    #[test]
+    fn test_diff_tokens() {
+        let signing_key = SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let trust = Trust::Key(Es256Verifier(*signing_key.verifying_key()));
+        let signer = Es256Signer(signing_key, None);
+        let uri = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        let dir = std::env::temp_dir();
+        let old_path = dir.join(format!("tsl-old-{}.jwt", std::process::id()));
+        let new_path = dir.join(format!("tsl-new-{}.cwt", std::process::id()));
+
+        let old = JwtStatusListToken::new(uri.clone(), 1000, create("1,INVALID", 2).unwrap())
+            .with_ttl(60)
+            .sign(&signer)
+            .unwrap();
+        fs::write(&old_path, &old).unwrap();
+        let new = CwtStatusListToken::new(uri, 2000, create("2,SUSPENDED\n5,INVALID", 2).unwrap())
+            .with_ttl(120)
+            .sign(&signer)
+            .unwrap();
+        fs::write(&new_path, &new).unwrap();
+
+        let (old_path, new_path) = (old_path.to_str().unwrap(), new_path.to_str().unwrap());
+        let diff = read_list_or_token(old_path, None)
+            .unwrap()
+            .diff(&read_list_or_token(new_path, Some(&trust)).unwrap())
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(describe_changes(&diff)).unwrap()[0],
+            serde_json::json!({"index": 1, "old": "INVALID", "new": "VALID"})
+        );
+        assert_eq!(
+            describe_changes(&diff).to_string(),
+            "1: INVALID -> VALID\n2: VALID -> SUSPENDED\n4: + VALID\n5: + INVALID\n\
+             6: + VALID\n7: + VALID\n"
+        );
+        assert_eq!(
+            token_list(&FetchedToken::Jwt(old), None).unwrap().1,
+            Some(60)
+        );
+        assert_eq!(
+            token_list(&FetchedToken::Cwt(new), None).unwrap().1,
+            Some(120)
+        );
+
+        let other = Trust::Key(Es256Verifier(
+            *SigningKey::from_slice(&[8u8; 32]).unwrap().verifying_key(),
+        ));
+        assert!(read_list_or_token(old_path, Some(&other)).is_err());
+        fs::remove_file(old_path).unwrap();
+        fs::remove_file(new_path).unwrap();
+    }
+
+    #[test]
+    fn test_watch_step() {
+        let first = create("1,INVALID", 1).unwrap();
+        let second = create("1,INVALID\n3,INVALID", 1).unwrap();
+        let first = StatusListDecoder::new(&first).unwrap();
+        let second = StatusListDecoder::new(&second).unwrap();
+
+        let text = OutputFormat::Text;
+        assert_eq!(
+            watch_step(None, &first, 100, text).unwrap(),
+            "100 watching 8 statuses\n"
+        );
+        assert_eq!(
+            watch_step(Some(&first), &second, 160, text).unwrap(),
+            "160 3: VALID -> INVALID\n"
+        );
+        assert_eq!(watch_step(Some(&second), &second, 220, text).unwrap(), "");
+
+        let json = OutputFormat::Json;
+        assert_eq!(
+            serde_json::from_str::<Value>(&watch_step(None, &first, 100, json).unwrap()).unwrap(),
+            serde_json::json!({"time": 100, "statuses": 8})
+        );
+        let line = watch_step(Some(&first), &second, 160, json).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&line).unwrap(),
+            serde_json::json!({"time": 160, "index": 3, "old": "VALID", "new": "INVALID"})
+        );
+    }
+}
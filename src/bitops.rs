@@ -0,0 +1,146 @@
+//! Low-level packing of status values into a byte array.
+//!
+//! Statuses are laid out as described in the Token Status List
+//! specification: index 0 occupies the least significant bits of byte 0,
+//! and each following index moves towards the most significant bits
+//! before continuing in the next byte.
+
+use crate::error::StatusTypeError;
+use crate::types::BitsPerStatus;
+
+/// Number of statuses stored in a single byte.
+pub fn statuses_per_byte(bits: BitsPerStatus) -> usize {
+    8 / bits as usize
+}
+
+/// Number of bytes needed to hold `count` statuses.
+pub fn packed_len(count: usize, bits: BitsPerStatus) -> usize {
+    count.div_ceil(statuses_per_byte(bits))
+}
+
+/// Writes `value` at `index`, leaving the neighbouring statuses untouched.
+pub fn encode_status(
+    bytes: &mut [u8],
+    index: usize,
+    bits: BitsPerStatus,
+    value: u8,
+) -> Result<(), StatusTypeError> {
+    let (byte_index, shift, mask) = locate(index, bits);
+    if value > mask {
+        return Err(StatusTypeError::StatusNotRepresentable(value, bits as u8));
+    }
+    let byte = bytes
+        .get_mut(byte_index)
+        .ok_or(StatusTypeError::InvalidByteIndex(byte_index))?;
+
+    *byte = (*byte & !(mask << shift)) | (value << shift);
+    Ok(())
+}
+
+/// Reads the raw value stored at `index`.
+pub fn decode_status(
+    bytes: &[u8],
+    index: usize,
+    bits: BitsPerStatus,
+) -> Result<u8, StatusTypeError> {
+    let (byte_index, shift, mask) = locate(index, bits);
+    let byte = bytes
+        .get(byte_index)
+        .ok_or(StatusTypeError::InvalidByteIndex(byte_index))?;
+
+    Ok((byte >> shift) & mask)
+}
+
+fn locate(index: usize, bits: BitsPerStatus) -> (usize, u32, u8) {
+    let per_byte = statuses_per_byte(bits);
+    let shift = ((index % per_byte) * bits as usize) as u32;
+    let mask = (((1u16 << bits as u8) - 1) & 0xFF) as u8;
+
+    (index / per_byte, shift, mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_1bit_example() {
+        let values = [1, 0, 0, 1, 1, 1, 0, 1, 1, 1, 0, 0, 0, 1, 0, 1];
+        let mut bytes = vec![0u8; packed_len(values.len(), BitsPerStatus::OneBit)];
+
+        for (i, value) in values.iter().enumerate() {
+            encode_status(&mut bytes, i, BitsPerStatus::OneBit, *value).unwrap();
+        }
+
+        assert_eq!(bytes, vec![0xB9, 0xA3]);
+        for (i, value) in values.iter().enumerate() {
+            assert_eq!(
+                decode_status(&bytes, i, BitsPerStatus::OneBit).unwrap(),
+                *value
+            );
+        }
+    }
+
+    #[test]
+    fn test_spec_2bit_example() {
+        let values = [1, 2, 0, 3, 0, 1, 0, 1, 1, 2, 3, 3];
+        let mut bytes = vec![0u8; packed_len(values.len(), BitsPerStatus::TwoBit)];
+
+        for (i, value) in values.iter().enumerate() {
+            encode_status(&mut bytes, i, BitsPerStatus::TwoBit, *value).unwrap();
+        }
+
+        assert_eq!(bytes, vec![0xC9, 0x44, 0xF9]);
+    }
+
+    #[test]
+    fn test_4bit_and_8bit_layout() {
+        let mut bytes = vec![0u8; 1];
+        encode_status(&mut bytes, 0, BitsPerStatus::FourBit, 0x1).unwrap();
+        encode_status(&mut bytes, 1, BitsPerStatus::FourBit, 0xF).unwrap();
+        assert_eq!(bytes, vec![0xF1]);
+
+        let mut bytes = vec![0u8; 2];
+        encode_status(&mut bytes, 1, BitsPerStatus::EightBit, 0xAB).unwrap();
+        assert_eq!(bytes, vec![0x00, 0xAB]);
+        assert_eq!(
+            decode_status(&bytes, 1, BitsPerStatus::EightBit).unwrap(),
+            0xAB
+        );
+    }
+
+    #[test]
+    fn test_overwrite_keeps_neighbours() {
+        let mut bytes = vec![0xFF];
+        encode_status(&mut bytes, 1, BitsPerStatus::TwoBit, 0b01).unwrap();
+
+        assert_eq!(bytes, vec![0b1111_0111]);
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let mut bytes = vec![0u8; 1];
+
+        match encode_status(&mut bytes, 8, BitsPerStatus::OneBit, 1) {
+            Err(StatusTypeError::InvalidByteIndex(1)) => (),
+            _ => panic!("Expected InvalidByteIndex error"),
+        }
+        match decode_status(&bytes, 2, BitsPerStatus::FourBit) {
+            Err(StatusTypeError::InvalidByteIndex(1)) => (),
+            _ => panic!("Expected InvalidByteIndex error"),
+        }
+        match encode_status(&mut bytes, 0, BitsPerStatus::TwoBit, 4) {
+            Err(StatusTypeError::StatusNotRepresentable(4, 2)) => (),
+            _ => panic!("Expected StatusNotRepresentable error"),
+        }
+    }
+
+    #[test]
+    fn test_packed_len() {
+        assert_eq!(packed_len(0, BitsPerStatus::OneBit), 0);
+        assert_eq!(packed_len(9, BitsPerStatus::OneBit), 2);
+        assert_eq!(packed_len(5, BitsPerStatus::TwoBit), 2);
+        assert_eq!(packed_len(3, BitsPerStatus::FourBit), 2);
+        assert_eq!(packed_len(3, BitsPerStatus::EightBit), 3);
+    }
+}
@@ -0,0 +1,199 @@
+//! Conversion to and from the W3C Bitstring Status List format.
+//!
+//! A bitstring is GZIP compressed, multibase base64url encoded (`u` prefix),
+//! at least 16KB before compression, and stores index 0 in the most
+//! significant bits of byte 0, the reverse of the Token Status List layout.
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+
+use crate::compression::{Compressor, Decompressor, ZlibCompression};
+use crate::error::BitstringError;
+use crate::types::{BitsPerStatus, StatusList};
+
+/// Minimum uncompressed size of a published bitstring.
+pub const BITSTRING_MIN_BYTES: usize = 16 * 1024;
+
+const MULTIBASE_BASE64URL: char = 'u';
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitstringStatusList {
+    status_size: u8,
+    bitstring: Vec<u8>,
+}
+
+impl BitstringStatusList {
+    pub fn from_status_list(status_list: &StatusList) -> Result<Self, BitstringError> {
+        let mut bitstring = ZlibCompression::default()
+            .decompress(status_list.lst())
+            .map_err(BitstringError::DecodingError)?;
+        reorder(&mut bitstring, status_list.bits());
+        if bitstring.len() < BITSTRING_MIN_BYTES {
+            bitstring.resize(BITSTRING_MIN_BYTES, 0);
+        }
+
+        Ok(Self {
+            status_size: status_list.bits(),
+            bitstring,
+        })
+    }
+
+    pub fn to_status_list(&self) -> Result<StatusList, BitstringError> {
+        let mut bytes = self.bitstring.clone();
+        reorder(&mut bytes, self.status_size);
+        let lst = ZlibCompression::default()
+            .compress(&bytes)
+            .map_err(BitstringError::EncodingError)?;
+
+        StatusList::from_compressed(self.status_size, lst)
+            .map_err(|e| BitstringError::EncodingError(e.to_string()))
+    }
+
+    /// Decodes an `encodedList` value; `status_size` comes from the
+    /// credential's `statusSize`, which is 1 when absent.
+    pub fn decode(encoded_list: &str, status_size: u8) -> Result<Self, BitstringError> {
+        BitsPerStatus::try_from(status_size)
+            .map_err(|_| BitstringError::InvalidStatusSize(status_size))?;
+        let encoded = encoded_list
+            .strip_prefix(MULTIBASE_BASE64URL)
+            .ok_or_else(|| BitstringError::DecodingError("missing multibase prefix".into()))?;
+        let compressed =
+            base64url::decode(encoded).map_err(|e| BitstringError::DecodingError(e.to_string()))?;
+
+        let mut bitstring = Vec::new();
+        GzDecoder::new(&compressed[..])
+            .read_to_end(&mut bitstring)
+            .map_err(|e| BitstringError::DecodingError(e.to_string()))?;
+
+        Ok(Self {
+            status_size,
+            bitstring,
+        })
+    }
+
+    pub fn encode(&self) -> Result<String, BitstringError> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        encoder
+            .write_all(&self.bitstring)
+            .map_err(|e| BitstringError::EncodingError(e.to_string()))?;
+        let compressed = encoder
+            .finish()
+            .map_err(|e| BitstringError::EncodingError(e.to_string()))?;
+
+        Ok(format!(
+            "{}{}",
+            MULTIBASE_BASE64URL,
+            base64url::encode(&compressed)
+        ))
+    }
+
+    pub fn status_size(&self) -> u8 {
+        self.status_size
+    }
+
+    pub fn len(&self) -> usize {
+        self.bitstring.len() * 8 / self.status_size as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bitstring.is_empty()
+    }
+
+    pub fn get_status(&self, index: usize) -> Option<u8> {
+        let size = self.status_size as usize;
+        let byte = self.bitstring.get(index * size / 8)?;
+        let shift = 8 - (index * size) % 8 - size;
+
+        Some((byte >> shift) & ((1u16 << size) - 1) as u8)
+    }
+}
+
+// Reverses the order of the `bits` wide groups in every byte, which maps
+// between LSB-first and MSB-first layouts in either direction.
+fn reorder(bytes: &mut [u8], bits: u8) {
+    let mask = ((1u16 << bits) - 1) as u8;
+    let groups = 8 / bits;
+    for byte in bytes.iter_mut() {
+        let mut reordered = 0u8;
+        for group in 0..groups {
+            let value = (*byte >> (group * bits)) & mask;
+            reordered |= value << ((groups - 1 - group) * bits);
+        }
+        *byte = reordered;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_list(bits: u8, bytes: &[u8]) -> StatusList {
+        StatusList::from_compressed(bits, ZlibCompression::default().compress(bytes).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_bit_order_translation() {
+        let bitstring =
+            BitstringStatusList::from_status_list(&status_list(1, &[0xB9, 0xA3])).unwrap();
+        assert_eq!(&bitstring.bitstring[..2], &[0x9D, 0xC5]);
+        assert_eq!(bitstring.bitstring.len(), BITSTRING_MIN_BYTES);
+
+        let values = [1, 0, 0, 1, 1, 1, 0, 1, 1, 1, 0, 0, 0, 1, 0, 1];
+        for (i, value) in values.iter().enumerate() {
+            assert_eq!(bitstring.get_status(i), Some(*value));
+        }
+
+        let bitstring =
+            BitstringStatusList::from_status_list(&status_list(2, &[0xC9, 0x44, 0xF9])).unwrap();
+        let values = [1, 2, 0, 3, 0, 1, 0, 1, 1, 2, 3, 3];
+        for (i, value) in values.iter().enumerate() {
+            assert_eq!(bitstring.get_status(i), Some(*value));
+        }
+        assert_eq!(bitstring.len(), BITSTRING_MIN_BYTES * 4);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let original = status_list(4, &[0xF1, 0x23]);
+        let bitstring = BitstringStatusList::from_status_list(&original).unwrap();
+        let encoded = bitstring.encode().unwrap();
+        assert!(encoded.starts_with('u'));
+
+        let decoded = BitstringStatusList::decode(&encoded, 4).unwrap();
+        assert_eq!(decoded, bitstring);
+
+        let converted = ZlibCompression::default()
+            .decompress(decoded.to_status_list().unwrap().lst())
+            .unwrap();
+        assert_eq!(&converted[..2], &[0xF1, 0x23]);
+    }
+
+    #[test]
+    fn test_decode_spec_example() {
+        let bitstring = BitstringStatusList::decode(
+            "uH4sIAAAAAAAAA-3BMQEAAADCoPVPbQwfoAAAAAAAAAAAAAAAAAAAAIC3AYbSVKsAQAAA",
+            1,
+        )
+        .unwrap();
+
+        assert_eq!(bitstring.len(), BITSTRING_MIN_BYTES * 8);
+        assert_eq!(bitstring.get_status(94567), Some(0));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        match BitstringStatusList::decode("uH4sI", 3) {
+            Err(BitstringError::InvalidStatusSize(3)) => (),
+            _ => panic!("Expected InvalidStatusSize"),
+        }
+        match BitstringStatusList::decode("zH4sI", 1) {
+            Err(BitstringError::DecodingError(msg)) => {
+                assert_eq!(msg, "missing multibase prefix")
+            }
+            _ => panic!("Expected DecodingError"),
+        }
+    }
+}
@@ -1,84 +1,658 @@
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Mutex;
+use std::collections::BTreeMap;
+#[cfg(feature = "store")]
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 
+use crate::bitops;
+use crate::compression::{Compressor, DEFAULT_MAX_DECOMPRESSED_LEN};
 use crate::encoder::StatusListEncoder;
+#[cfg(feature = "store")]
+use crate::error::StoreError;
 use crate::error::{BuilderError, StatusTypeError};
+use crate::registry::StatusRegistry;
+use crate::rng::SplitMix64;
+#[cfg(feature = "store")]
+use crate::store::{StatusListStore, StoreMetadata};
 use crate::types::{BitsPerStatus, StatusList, StatusType};
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FillStrategy {
+    AllValid,
+    AllInvalid,
+    RandomDecoy(u64),
+}
+
+/// Compressed sizes of two consecutive builds that differ by more than the
+/// configured ratio.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizeAnomaly {
+    pub previous_len: usize,
+    pub current_len: usize,
+}
+
+impl SizeAnomaly {
+    pub fn ratio(&self) -> f64 {
+        self.current_len as f64 / self.previous_len as f64
+    }
+}
+
+type AnomalyCallback = Arc<dyn Fn(&SizeAnomaly) + Send + Sync>;
+
+struct AnomalyCheck {
+    max_ratio: f64,
+    callback: AnomalyCallback,
+    last_len: Mutex<Option<usize>>,
+}
+
+impl AnomalyCheck {
+    fn observe(&self, current_len: usize) {
+        let previous_len = self.last_len.lock().unwrap().replace(current_len);
+        if let Some(previous_len) = previous_len {
+            let (smaller, larger) = if previous_len < current_len {
+                (previous_len, current_len)
+            } else {
+                (current_len, previous_len)
+            };
+            if larger as f64 > smaller.max(1) as f64 * self.max_ratio {
+                (self.callback)(&SizeAnomaly {
+                    previous_len,
+                    current_len,
+                });
+            }
+        }
+    }
+}
+
+impl fmt::Debug for AnomalyCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnomalyCheck")
+            .field("max_ratio", &self.max_ratio)
+            .field("last_len", &self.last_len)
+            .finish_non_exhaustive()
+    }
+}
+
+// Bytes per shard. Shards start on byte boundaries, so a status never spans
+// two of them whatever the bit width.
+const SHARD_BYTES: usize = 4096;
+
+#[derive(Debug, Default)]
+struct Shard {
+    bytes: Vec<u8>,
+    // Keyed by list index, like `dirty`.
+    unrepresentable: BTreeMap<usize, StatusType>,
+    #[cfg(feature = "store")]
+    dirty: BTreeSet<usize>,
+}
+
+impl Shard {
+    fn set(&mut self, bits: BitsPerStatus, index: usize, offset: usize, status: StatusType) {
+        match bitops::encode_status(&mut self.bytes, offset, bits, status.value()) {
+            Ok(()) => {
+                self.unrepresentable.remove(&index);
+            }
+            Err(_) => {
+                bitops::encode_status(&mut self.bytes, offset, bits, 0)
+                    .expect("index is within the shard");
+                self.unrepresentable.insert(index, status);
+            }
+        }
+    }
+
+    fn get(&self, bits: BitsPerStatus, index: usize, offset: usize) -> Option<StatusType> {
+        if let Some(status) = self.unrepresentable.get(&index) {
+            return Some(*status);
+        }
+
+        bitops::decode_status(&self.bytes, offset, bits)
+            .ok()
+            .map(StatusType::from)
+    }
+}
+
+// Statuses packed exactly as they appear in the uncompressed list, so that
+// build() only has to copy and compress. The bytes are split into shards with
+// a lock each: writes within the current length take the table's read lock
+// and a single shard lock, so writers to different shards don't contend.
+// Growing the list and taking a snapshot need the write lock, which keeps
+// `len` consistent with the data. Values too wide for the bit width are kept
+// aside and reported by build(). Every write bumps the generation.
+#[derive(Debug)]
+struct StatusTable {
+    bits: BitsPerStatus,
+    shards: Vec<Mutex<Shard>>,
+    len: usize,
+    generation: AtomicU64,
+    // Whether shards record the indices written since the last flush to a
+    // store; off until the first flush or load, which both start from a full
+    // view of the list.
+    #[cfg(feature = "store")]
+    tracking: bool,
+}
+
+impl StatusTable {
+    fn new(bits: BitsPerStatus) -> Self {
+        Self {
+            bits,
+            shards: Vec::new(),
+            len: 0,
+            generation: AtomicU64::new(0),
+            #[cfg(feature = "store")]
+            tracking: false,
+        }
+    }
+
+    fn locate(&self, index: usize) -> (usize, usize) {
+        let per_shard = SHARD_BYTES * bitops::statuses_per_byte(self.bits);
+        (index / per_shard, index % per_shard)
+    }
+
+    fn resize(&mut self, len: usize) {
+        let packed_len = bitops::packed_len(len, self.bits);
+        let shard_count = packed_len.div_ceil(SHARD_BYTES);
+        let first = self.shards.len().saturating_sub(1);
+        self.shards.resize_with(shard_count, Default::default);
+        for (i, shard) in self.shards.iter_mut().enumerate().skip(first) {
+            let shard_len = (packed_len - i * SHARD_BYTES).min(SHARD_BYTES);
+            shard.get_mut().unwrap().bytes.resize(shard_len, 0);
+        }
+        self.len = len;
+    }
+
+    // Writes an index below `len` through the shard lock. Returns false when
+    // the index is past the end and the list has to grow first.
+    fn set_shared(&self, index: usize, status: StatusType) -> bool {
+        if index >= self.len {
+            return false;
+        }
+        let (shard, offset) = self.locate(index);
+        let mut shard = self.shards[shard].lock().unwrap();
+        #[cfg(feature = "store")]
+        if self.tracking {
+            shard.dirty.insert(index);
+        }
+        shard.set(self.bits, index, offset, status);
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        true
+    }
+
+    fn set(&mut self, index: usize, status: StatusType) {
+        #[cfg(feature = "store")]
+        // New Valid indices need no record, only the stored length.
+        let record = self.tracking && (index < self.len || status.value() != 0);
+        if index >= self.len {
+            self.resize(index + 1);
+        }
+        let (shard, offset) = self.locate(index);
+        let bits = self.bits;
+        let shard = self.shards[shard].get_mut().unwrap();
+        #[cfg(feature = "store")]
+        if record {
+            shard.dirty.insert(index);
+        }
+        shard.set(bits, index, offset, status);
+        *self.generation.get_mut() += 1;
+    }
+
+    fn extend(&mut self, len: usize, mut status: impl FnMut() -> StatusType) {
+        if len > self.len {
+            self.shards
+                .reserve(bitops::packed_len(len, self.bits).div_ceil(SHARD_BYTES));
+        }
+        while self.len < len {
+            self.set(self.len, status());
+        }
+    }
+
+    fn get(&self, index: usize) -> Option<StatusType> {
+        if index >= self.len {
+            return None;
+        }
+        let (shard, offset) = self.locate(index);
+        self.shards[shard]
+            .lock()
+            .unwrap()
+            .get(self.bits, index, offset)
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    // The packed bytes and the generation they reflect, or the first status
+    // the bit width cannot hold.
+    fn snapshot(&mut self) -> Result<(u64, Vec<u8>), StatusType> {
+        let mut bytes = Vec::with_capacity(bitops::packed_len(self.len, self.bits));
+        for shard in &mut self.shards {
+            let shard = shard.get_mut().unwrap();
+            if let Some(status) = shard.unrepresentable.values().next() {
+                return Err(*status);
+            }
+            bytes.extend_from_slice(&shard.bytes);
+        }
+        Ok((*self.generation.get_mut(), bytes))
+    }
+}
+
+const PAST_MAX_LEN: &str = "status list would grow past its maximum length";
+
 #[derive(Debug)]
 pub struct StatusListBuilder {
-    statuses: Mutex<Vec<StatusType>>,
+    statuses: RwLock<StatusTable>,
     bits_per_status: u8,
-    last_index: AtomicUsize,
     encoder: StatusListEncoder,
+    default_status: StatusType,
+    max_len: usize,
+    last_build: Mutex<Option<(u64, StatusList)>>,
+    anomaly_check: Option<AnomalyCheck>,
+    registry: StatusRegistry,
 }
 
 impl StatusListBuilder {
     pub fn new(bits_per_status: u8) -> Result<Self, StatusTypeError> {
-        BitsPerStatus::try_from(bits_per_status)?;
+        let bits = BitsPerStatus::try_from(bits_per_status)?;
 
         Ok(Self {
-            statuses: Mutex::new(Vec::new()),
+            statuses: RwLock::new(StatusTable::new(bits)),
             bits_per_status,
-            last_index: AtomicUsize::new(0),
             encoder: StatusListEncoder::new(bits_per_status),
+            default_status: StatusType::Valid,
+            max_len: DEFAULT_MAX_DECOMPRESSED_LEN * bitops::statuses_per_byte(bits),
+            last_build: Mutex::new(None),
+            anomaly_check: None,
+            registry: StatusRegistry::default(),
         })
     }
 
-    pub fn from_vec(
-        statuses: Vec<StatusType>,
-        bits_per_status: u8,
-    ) -> Result<Self, StatusTypeError> {
-        BitsPerStatus::try_from(bits_per_status)?;
+    /// Fails with `MaxLenExceeded` if `statuses` is longer than the default
+    /// `max_len()`.
+    pub fn from_vec(statuses: Vec<StatusType>, bits_per_status: u8) -> Result<Self, BuilderError> {
+        let mut builder = Self::new(bits_per_status).map_err(BuilderError::StatusTypeError)?;
+        let mut statuses = statuses.into_iter();
+        let len = statuses.len();
+        if len > builder.max_len {
+            return Err(BuilderError::MaxLenExceeded(builder.max_len));
+        }
+        builder
+            .statuses
+            .get_mut()
+            .unwrap()
+            .extend(len, || statuses.next().unwrap_or(StatusType::Valid));
+        Ok(builder)
+    }
 
-        let last_index = if !statuses.is_empty() {
-            statuses.len() - 1
-        } else {
-            0
-        };
+    pub fn with_default_status(mut self, status: StatusType) -> Result<Self, StatusTypeError> {
+        if (status.value() as u16) >> self.bits_per_status != 0 {
+            return Err(StatusTypeError::StatusNotRepresentable(
+                status.value(),
+                self.bits_per_status,
+            ));
+        }
 
-        Ok(Self {
-            statuses: Mutex::new(statuses),
-            bits_per_status,
-            last_index: AtomicUsize::new(last_index),
-            encoder: StatusListEncoder::new(bits_per_status),
-        })
+        self.default_status = status;
+        Ok(self)
     }
 
-    pub fn add_status(&self, status: StatusType) -> &Self {
-        let mut statuses = self.statuses.lock().unwrap();
-        let index = statuses.len();
+    /// # Panics
+    ///
+    /// If `size` is past `max_len()`; `try_with_capacity` returns an error
+    /// instead.
+    pub fn with_capacity(self, size: usize, fill_strategy: FillStrategy) -> Self {
+        self.try_with_capacity(size, fill_strategy)
+            .expect("status list capacity is past its maximum length")
+    }
 
-        statuses.push(status);
-        self.last_index.store(index, Ordering::SeqCst);
+    pub fn try_with_capacity(
+        mut self,
+        size: usize,
+        fill_strategy: FillStrategy,
+    ) -> Result<Self, BuilderError> {
+        if size > self.max_len {
+            return Err(BuilderError::MaxLenExceeded(self.max_len));
+        }
+        let statuses = self.statuses.get_mut().unwrap();
+        match fill_strategy {
+            FillStrategy::AllValid => statuses.extend(size, || StatusType::Valid),
+            FillStrategy::AllInvalid => statuses.extend(size, || StatusType::Invalid),
+            FillStrategy::RandomDecoy(seed) => {
+                let mut rng = SplitMix64::new(seed);
+                statuses.extend(size, || {
+                    if rng.next_u64() & 1 == 0 {
+                        StatusType::Valid
+                    } else {
+                        StatusType::Invalid
+                    }
+                });
+            }
+        }
+        Ok(self)
+    }
+
+    /// Caps how far the builder grows the list. Defaults to the longest list
+    /// a default decoder accepts. Past it the write methods panic and their
+    /// `try_` counterparts return `BuilderError::MaxLenExceeded`.
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
         self
     }
 
-    pub fn get_last_index(&self) -> Option<usize> {
-        let index = self.last_index.load(Ordering::SeqCst);
-        if index == 0 && self.statuses.lock().unwrap().is_empty() {
-            None
-        } else {
-            Some(index)
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    pub fn with_compressor(mut self, compressor: impl Compressor + 'static) -> Self {
+        self.encoder = self.encoder.with_compressor(compressor);
+        self
+    }
+
+    /// Calls `callback` whenever the compressed list grows or shrinks by more
+    /// than `max_ratio` between two builds, which usually points at a bit
+    /// width change or corrupted data rather than a real revocation spike.
+    pub fn with_size_anomaly_check(
+        mut self,
+        max_ratio: f64,
+        callback: impl Fn(&SizeAnomaly) + Send + Sync + 'static,
+    ) -> Self {
+        self.anomaly_check = Some(AnomalyCheck {
+            max_ratio,
+            callback: Arc::new(callback),
+            last_len: Mutex::new(None),
+        });
+        self
+    }
+
+    pub fn with_registry(mut self, registry: StatusRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    pub fn registry(&self) -> &StatusRegistry {
+        &self.registry
+    }
+
+    pub fn get_default_status(&self) -> StatusType {
+        self.default_status
+    }
+
+    // The write methods below panic rather than grow the list past
+    // `max_len()`, as `Vec` does past its capacity. Each has a `try_`
+    // counterpart that returns `BuilderError::MaxLenExceeded` instead.
+
+    pub fn pad_to(&self, len: usize) -> &Self {
+        self.try_pad_to(len).expect(PAST_MAX_LEN)
+    }
+
+    pub fn try_pad_to(&self, len: usize) -> Result<&Self, BuilderError> {
+        if len > self.max_len {
+            return Err(BuilderError::MaxLenExceeded(self.max_len));
+        }
+        self.statuses
+            .write()
+            .unwrap()
+            .extend(len, || self.default_status);
+        Ok(self)
+    }
+
+    pub fn add_status(&self, status: StatusType) -> &Self {
+        self.extend_statuses([status])
+    }
+
+    /// Appends `statuses` under a single lock acquisition.
+    pub fn add_statuses(&self, statuses: &[StatusType]) -> &Self {
+        self.extend_statuses(statuses.iter().copied())
+    }
+
+    pub fn extend_statuses(&self, statuses: impl IntoIterator<Item = StatusType>) -> &Self {
+        self.try_extend_statuses(statuses).expect(PAST_MAX_LEN)
+    }
+
+    /// Appends until the list reaches `max_len()`, then fails with the
+    /// remaining statuses unwritten.
+    pub fn try_extend_statuses(
+        &self,
+        statuses: impl IntoIterator<Item = StatusType>,
+    ) -> Result<&Self, BuilderError> {
+        let mut packed = self.statuses.write().unwrap();
+        for status in statuses {
+            let index = packed.len;
+            if index >= self.max_len {
+                return Err(BuilderError::MaxLenExceeded(self.max_len));
+            }
+            packed.set(index, status);
+        }
+        Ok(self)
+    }
+
+    /// Sets every `(index, status)` pair, taking the write lock only for
+    /// indices past the end of the list.
+    pub fn set_statuses(&self, updates: &[(usize, StatusType)]) -> &Self {
+        self.try_set_statuses(updates).expect(PAST_MAX_LEN)
+    }
+
+    /// Like `set_statuses`, but stops at the first index past `max_len()`.
+    pub fn try_set_statuses(&self, updates: &[(usize, StatusType)]) -> Result<&Self, BuilderError> {
+        self.set_status_batch(&mut updates.iter().copied(), updates.len())?;
+        Ok(self)
+    }
+
+    /// Writes to indices below `len()` only lock the shard holding `index`,
+    /// so concurrent writers rarely wait on each other. Writing past the end
+    /// pads the list with the default status under the write lock.
+    pub fn set_status(&self, index: usize, status: StatusType) -> &Self {
+        self.try_set_status(index, status).expect(PAST_MAX_LEN)
+    }
+
+    pub fn try_set_status(&self, index: usize, status: StatusType) -> Result<&Self, BuilderError> {
+        if !self.statuses.read().unwrap().set_shared(index, status) {
+            self.grow_to(index, status)?;
+        }
+        Ok(self)
+    }
+
+    fn grow_to(&self, index: usize, status: StatusType) -> Result<(), BuilderError> {
+        // `index < max_len` also keeps `index + 1` from overflowing.
+        if index >= self.max_len {
+            return Err(BuilderError::MaxLenExceeded(self.max_len));
+        }
+        let mut statuses = self.statuses.write().unwrap();
+        statuses.extend(index, || self.default_status);
+        statuses.set(index, status);
+        Ok(())
+    }
+
+    // Applies up to `limit` events, holding the read lock between appends,
+    // and returns how many were applied.
+    pub(crate) fn set_status_batch(
+        &self,
+        events: &mut impl Iterator<Item = (usize, StatusType)>,
+        limit: usize,
+    ) -> Result<usize, BuilderError> {
+        let mut statuses = self.statuses.read().unwrap();
+        let mut applied = 0;
+        for (index, status) in events.take(limit) {
+            if !statuses.set_shared(index, status) {
+                drop(statuses);
+                self.grow_to(index, status)?;
+                statuses = self.statuses.read().unwrap();
+            }
+            applied += 1;
         }
+        Ok(applied)
+    }
+
+    pub fn set_named_status(&self, index: usize, name: &str) -> Result<&Self, StatusTypeError> {
+        let status = self
+            .registry
+            .lookup(name)
+            .ok_or_else(|| StatusTypeError::UnknownStatusName(name.to_string()))?;
+        Ok(self.set_status(index, status))
+    }
+
+    pub fn get_status(&self, index: usize) -> Option<StatusType> {
+        self.statuses.read().unwrap().get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.statuses.read().unwrap().len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Statuses the list holds before its uncompressed size grows by a byte:
+    /// `len()` rounded up to a whole byte.
+    pub fn capacity(&self) -> usize {
+        let statuses = self.statuses.read().unwrap();
+        bitops::packed_len(statuses.len, statuses.bits) * bitops::statuses_per_byte(statuses.bits)
+    }
+
+    /// Derived from the length, so it never points past the statuses written.
+    pub fn get_last_index(&self) -> Option<usize> {
+        self.len().checked_sub(1)
     }
 
     pub fn get_bits_per_status(&self) -> u8 {
         self.bits_per_status
     }
 
+    pub fn generation(&self) -> u64 {
+        self.statuses.read().unwrap().generation()
+    }
+
+    pub fn changed_since(&self, generation: u64) -> bool {
+        self.generation() != generation
+    }
+
+    /// Copies the statuses under the write lock, then compresses them with
+    /// writers unblocked.
     pub fn build(&self) -> Result<StatusList, BuilderError> {
-        let statuses = self.statuses.lock().unwrap();
-        let bytes = self.encoder.encode_statuses(&statuses)?;
-        self.encoder.finalize(&bytes)
+        let (_, bytes) = self.snapshot()?;
+        self.encode(&bytes)
+    }
+
+    /// Like `build`, but returns the previous result unchanged when no status
+    /// has been written since, skipping compression.
+    pub fn build_incremental(&self) -> Result<StatusList, BuilderError> {
+        let generation = self.generation();
+        if let Some((built, status_list)) = self.last_build.lock().unwrap().as_ref() {
+            if *built == generation {
+                return Ok(status_list.clone());
+            }
+        }
+
+        let (generation, bytes) = self.snapshot()?;
+        let status_list = self.encode(&bytes)?;
+        let mut last_build = self.last_build.lock().unwrap();
+        if last_build
+            .as_ref()
+            .is_none_or(|(built, _)| *built < generation)
+        {
+            *last_build = Some((generation, status_list.clone()));
+        }
+        Ok(status_list)
+    }
+
+    /// Restores a builder from the statuses last flushed to `store`.
+    #[cfg(feature = "store")]
+    pub fn load(store: &impl StatusListStore) -> Result<Self, StoreError> {
+        let metadata = store.load_metadata()?.ok_or(StoreError::Empty)?;
+        let mut builder = Self::new(metadata.bits_per_status)
+            .map_err(|e| StoreError::Corrupted(e.to_string()))?;
+        {
+            let statuses = builder.statuses.get_mut().unwrap();
+            statuses.extend(metadata.len, || StatusType::Valid);
+            for (index, value) in store.load_statuses()? {
+                if index >= metadata.len {
+                    return Err(StoreError::Corrupted(format!(
+                        "status at index {} is past the list length {}",
+                        index, metadata.len
+                    )));
+                }
+                statuses.set(index, StatusType::from(value));
+            }
+            statuses.tracking = true;
+        }
+        Ok(builder)
+    }
+
+    /// Writes the statuses changed since the last `flush` or `load` to
+    /// `store`. The first flush of a fresh builder replaces whatever the store
+    /// held before.
+    #[cfg(feature = "store")]
+    pub fn flush(&self, store: &impl StatusListStore) -> Result<(), StoreError> {
+        let mut statuses = self.statuses.write().unwrap();
+        let changed: Vec<(usize, u8)> = if statuses.tracking {
+            let dirty: Vec<usize> = statuses
+                .shards
+                .iter_mut()
+                .flat_map(|shard| shard.get_mut().unwrap().dirty.iter().copied())
+                .collect();
+            dirty
+                .into_iter()
+                .map(|index| (index, statuses.get(index).map_or(0, |s| s.value())))
+                .collect()
+        } else {
+            store.clear_statuses()?;
+            (0..statuses.len)
+                .filter_map(|index| statuses.get(index).map(|s| (index, s.value())))
+                .filter(|(_, value)| *value != 0)
+                .collect()
+        };
+
+        store.save_statuses(&changed)?;
+        store.save_metadata(StoreMetadata {
+            bits_per_status: self.bits_per_status,
+            len: statuses.len,
+        })?;
+        store.flush()?;
+        for shard in &mut statuses.shards {
+            shard.get_mut().unwrap().dirty.clear();
+        }
+        statuses.tracking = true;
+        Ok(())
+    }
+
+    fn snapshot(&self) -> Result<(u64, Vec<u8>), BuilderError> {
+        self.statuses.write().unwrap().snapshot().map_err(|status| {
+            BuilderError::EncodingError(
+                StatusTypeError::StatusNotRepresentable(status.value(), self.bits_per_status)
+                    .to_string(),
+            )
+        })
+    }
+
+    fn encode(&self, bytes: &[u8]) -> Result<StatusList, BuilderError> {
+        let status_list = self.encoder.finalize(bytes)?;
+        if let Some(check) = &self.anomaly_check {
+            check.observe(status_list.lst().len());
+        }
+        Ok(status_list)
+    }
+}
+
+/// Panics like `extend_statuses` past `max_len()`; use
+/// `try_extend_statuses` to get an error instead.
+impl Extend<StatusType> for StatusListBuilder {
+    fn extend<I: IntoIterator<Item = StatusType>>(&mut self, statuses: I) {
+        self.extend_statuses(statuses);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::AtomicUsize;
     use std::thread;
 
+    fn snapshot(builder: &StatusListBuilder) -> Vec<StatusType> {
+        (0..builder.len())
+            .map(|i| builder.get_status(i).unwrap())
+            .collect()
+    }
+
     #[test]
     fn test_thread_safety() {
         let builder = StatusListBuilder::new(2).unwrap();
@@ -98,8 +672,60 @@ mod tests {
             handle.join().unwrap();
         }
 
-        let statuses = builder_arc.statuses.lock().unwrap();
-        assert_eq!(statuses.len(), 20); // 10 threads * 2 statuses each
+        assert_eq!(builder_arc.len(), 20); // 10 threads * 2 statuses each
+    }
+
+    #[test]
+    fn test_concurrent_writers() {
+        let builder = Arc::new(
+            StatusListBuilder::new(2)
+                .unwrap()
+                .with_capacity(200_000, FillStrategy::AllValid),
+        );
+        let mut handles = vec![];
+
+        for t in 0..4 {
+            let builder = builder.clone();
+            handles.push(thread::spawn(move || {
+                for i in (t..200_000).step_by(4) {
+                    builder.set_status(i, StatusType::Invalid);
+                }
+            }));
+        }
+        let appender = builder.clone();
+        handles.push(thread::spawn(move || {
+            for _ in 0..1000 {
+                appender.add_status(StatusType::Suspended);
+            }
+        }));
+        let reader = builder.clone();
+        handles.push(thread::spawn(move || {
+            for _ in 0..1000 {
+                let last = reader.get_last_index().unwrap();
+                assert!(reader.get_status(last).is_some());
+            }
+        }));
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(builder.len(), 201_000);
+        assert_eq!(builder.get_last_index(), Some(200_999));
+        let statuses = snapshot(&builder);
+        assert!(statuses[..200_000]
+            .iter()
+            .all(|s| *s == StatusType::Invalid));
+        assert!(statuses[200_000..]
+            .iter()
+            .all(|s| *s == StatusType::Suspended));
+        assert_eq!(
+            builder.build().unwrap(),
+            StatusListBuilder::from_vec(statuses, 2)
+                .unwrap()
+                .build()
+                .unwrap()
+        );
     }
 
     #[test]
@@ -123,8 +749,8 @@ mod tests {
         let builder = StatusListBuilder::from_vec(statuses.clone(), bits_per_status).unwrap();
 
         assert_eq!(builder.bits_per_status, bits_per_status);
-        assert_eq!(*builder.statuses.lock().unwrap(), statuses);
-        assert_eq!(builder.last_index.load(Ordering::SeqCst), 11);
+        assert_eq!(snapshot(&builder), statuses);
+        assert_eq!(builder.get_last_index(), Some(11));
     }
 
     #[test]
@@ -153,7 +779,7 @@ mod tests {
             StatusType::Invalid,
         ];
         let builder = StatusListBuilder::from_vec(one_bit_statuses.clone(), 1).unwrap();
-        assert_eq!(builder.last_index.load(Ordering::SeqCst), 7);
+        assert_eq!(builder.get_last_index(), Some(7));
 
         let two_bit_statuses = vec![
             StatusType::Valid,
@@ -162,15 +788,15 @@ mod tests {
             StatusType::ApplicationSpecific3,
         ];
         let builder = StatusListBuilder::from_vec(two_bit_statuses.clone(), 2).unwrap();
-        assert_eq!(builder.last_index.load(Ordering::SeqCst), 3);
+        assert_eq!(builder.get_last_index(), Some(3));
 
         let four_bit_statuses = vec![StatusType::Valid, StatusType::Invalid];
         let builder = StatusListBuilder::from_vec(four_bit_statuses.clone(), 4).unwrap();
-        assert_eq!(builder.last_index.load(Ordering::SeqCst), 1);
+        assert_eq!(builder.get_last_index(), Some(1));
 
         let eight_bit_statuses = vec![StatusType::Valid];
         let builder = StatusListBuilder::from_vec(eight_bit_statuses.clone(), 8).unwrap();
-        assert_eq!(builder.last_index.load(Ordering::SeqCst), 0);
+        assert_eq!(builder.get_last_index(), Some(0));
     }
 
     #[test]
@@ -182,8 +808,282 @@ mod tests {
         builder.add_status(StatusType::Suspended);
         builder.add_status(StatusType::ApplicationSpecific3);
 
-        assert_eq!(builder.last_index.load(Ordering::SeqCst), 3);
-        assert_eq!(builder.statuses.lock().unwrap().len(), 4);
+        assert_eq!(builder.get_last_index(), Some(3));
+        assert_eq!(builder.len(), 4);
+    }
+
+    #[test]
+    fn test_bulk_updates() {
+        let mut builder = StatusListBuilder::new(2).unwrap();
+        builder
+            .add_statuses(&[StatusType::Valid, StatusType::Invalid])
+            .add_statuses(&[])
+            .set_statuses(&[(5, StatusType::Suspended), (0, StatusType::Invalid)]);
+        assert_eq!(builder.get_last_index(), Some(5));
+
+        builder.extend(std::iter::repeat_n(StatusType::Invalid, 2));
+        assert_eq!(
+            snapshot(&builder),
+            vec![
+                StatusType::Invalid,
+                StatusType::Invalid,
+                StatusType::Valid,
+                StatusType::Valid,
+                StatusType::Valid,
+                StatusType::Suspended,
+                StatusType::Invalid,
+                StatusType::Invalid,
+            ]
+        );
+        assert_eq!(builder.get_last_index(), Some(7));
+    }
+
+    #[test]
+    fn test_set_status() {
+        let builder = StatusListBuilder::new(2).unwrap();
+        builder
+            .add_status(StatusType::Valid)
+            .add_status(StatusType::Valid)
+            .set_status(1, StatusType::Invalid);
+
+        assert_eq!(
+            snapshot(&builder),
+            vec![StatusType::Valid, StatusType::Invalid]
+        );
+        assert_eq!(builder.get_last_index(), Some(1));
+
+        builder.set_status(4, StatusType::Suspended);
+        assert_eq!(
+            snapshot(&builder),
+            vec![
+                StatusType::Valid,
+                StatusType::Invalid,
+                StatusType::Valid,
+                StatusType::Valid,
+                StatusType::Suspended,
+            ]
+        );
+        assert_eq!(builder.get_last_index(), Some(4));
+
+        builder.set_status(0, StatusType::Invalid);
+        assert_eq!(builder.get_last_index(), Some(4));
+        assert_eq!(builder.get_status(0).unwrap(), StatusType::Invalid);
+    }
+
+    #[test]
+    fn test_with_capacity() {
+        let builder = StatusListBuilder::new(1)
+            .unwrap()
+            .with_capacity(16, FillStrategy::AllInvalid);
+        assert_eq!(snapshot(&builder), vec![StatusType::Invalid; 16]);
+        assert_eq!(builder.get_last_index(), Some(15));
+
+        let builder = StatusListBuilder::new(2)
+            .unwrap()
+            .with_capacity(4, FillStrategy::AllValid);
+        builder.set_status(1, StatusType::Suspended);
+        assert_eq!(
+            snapshot(&builder),
+            vec![
+                StatusType::Valid,
+                StatusType::Suspended,
+                StatusType::Valid,
+                StatusType::Valid
+            ]
+        );
+
+        let decoys = |seed| {
+            snapshot(
+                &StatusListBuilder::new(1)
+                    .unwrap()
+                    .with_capacity(256, FillStrategy::RandomDecoy(seed)),
+            )
+        };
+        let first = decoys(9);
+        assert_eq!(first, decoys(9));
+        assert_ne!(first, decoys(10));
+        assert!(first.contains(&StatusType::Valid) && first.contains(&StatusType::Invalid));
+
+        let builder = StatusListBuilder::from_vec(vec![StatusType::Invalid; 3], 1)
+            .unwrap()
+            .with_capacity(2, FillStrategy::AllValid);
+        assert_eq!(builder.len(), 3);
+    }
+
+    #[test]
+    fn test_packed_storage() {
+        let builder = StatusListBuilder::new(1)
+            .unwrap()
+            .with_capacity(1_000_000, FillStrategy::AllValid);
+        builder.set_status(999_999, StatusType::Invalid);
+
+        let mut statuses = builder.statuses.write().unwrap();
+        assert_eq!(statuses.shards.len(), 31);
+        let (_, bytes) = statuses.snapshot().unwrap();
+        assert_eq!(bytes.len(), 125_000);
+        assert_eq!(bytes[124_999], 0b1000_0000);
+    }
+
+    #[test]
+    fn test_unrepresentable_status_fails_build() {
+        let builder = StatusListBuilder::new(1).unwrap();
+        builder
+            .add_status(StatusType::Invalid)
+            .add_status(StatusType::Suspended);
+        assert_eq!(builder.get_status(1), Some(StatusType::Suspended));
+
+        match builder.build() {
+            Err(BuilderError::EncodingError(msg)) => assert_eq!(
+                msg,
+                "Status type 2 cannot be represented with 1 bits per status"
+            ),
+            _ => panic!("Expected EncodingError"),
+        }
+
+        builder.set_status(1, StatusType::Valid);
+        assert!(builder.build().is_ok());
+    }
+
+    #[derive(Debug)]
+    struct CountingCompressor(std::sync::Arc<AtomicUsize>);
+
+    impl Compressor for CountingCompressor {
+        fn compress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            crate::compression::ZlibCompression::default().compress(data)
+        }
+    }
+
+    #[test]
+    fn test_build_incremental() {
+        let calls = std::sync::Arc::new(AtomicUsize::new(0));
+        let builder = StatusListBuilder::new(1)
+            .unwrap()
+            .with_compressor(CountingCompressor(calls.clone()))
+            .with_capacity(64, FillStrategy::AllValid);
+
+        let first = builder.build_incremental().unwrap();
+        let generation = builder.generation();
+        assert_eq!(builder.build_incremental().unwrap(), first);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(!builder.changed_since(generation));
+
+        builder.set_status(3, StatusType::Invalid);
+        assert!(builder.changed_since(generation));
+        let second = builder.build_incremental().unwrap();
+        assert_ne!(second, first);
+        assert_eq!(second, builder.build().unwrap());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_size_anomaly_check() {
+        let anomalies = Arc::new(Mutex::new(Vec::new()));
+        let seen = anomalies.clone();
+        let builder = StatusListBuilder::new(1)
+            .unwrap()
+            .with_capacity(4096, FillStrategy::AllValid)
+            .with_size_anomaly_check(4.0, move |anomaly| seen.lock().unwrap().push(*anomaly));
+
+        let first = builder.build().unwrap().lst().len();
+        builder.set_status(7, StatusType::Invalid);
+        builder.build().unwrap();
+        assert!(anomalies.lock().unwrap().is_empty());
+
+        let mut rng = SplitMix64::new(1);
+        for i in 0..4096 {
+            if rng.next_u64() & 1 == 1 {
+                builder.set_status(i, StatusType::Invalid);
+            }
+        }
+        let current = builder.build().unwrap().lst().len();
+
+        let anomalies = anomalies.lock().unwrap();
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].current_len, current);
+        assert!(anomalies[0].previous_len < first + 4);
+        assert!(anomalies[0].ratio() > 4.0);
+    }
+
+    #[test]
+    fn test_any_application_specific_value() {
+        let builder = StatusListBuilder::new(8).unwrap();
+        builder
+            .add_status(StatusType::from(0x42))
+            .add_status(StatusType::from(0xFF));
+        let (_, bytes) = builder.statuses.write().unwrap().snapshot().unwrap();
+        assert_eq!(bytes, vec![0x42, 0xFF]);
+        assert!(builder.build().is_ok());
+
+        let builder = StatusListBuilder::new(4).unwrap();
+        builder.add_status(StatusType::from(0x10));
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn test_set_named_status() {
+        let builder = StatusListBuilder::new(4).unwrap().with_registry(
+            StatusRegistry::default()
+                .with_status(StatusType::ApplicationSpecific11, "PendingReview"),
+        );
+
+        builder
+            .set_named_status(0, "PendingReview")
+            .unwrap()
+            .set_named_status(1, "SUSPENDED")
+            .unwrap();
+        assert_eq!(
+            snapshot(&builder),
+            vec![StatusType::ApplicationSpecific11, StatusType::Suspended]
+        );
+
+        match builder.set_named_status(2, "Archived") {
+            Err(StatusTypeError::UnknownStatusName(name)) => assert_eq!(name, "Archived"),
+            _ => panic!("Expected UnknownStatusName"),
+        }
+    }
+
+    #[test]
+    fn test_max_len() {
+        assert_eq!(
+            StatusListBuilder::new(1).unwrap().max_len(),
+            DEFAULT_MAX_DECOMPRESSED_LEN * 8
+        );
+
+        let builder = StatusListBuilder::new(1).unwrap().with_max_len(10);
+        builder.set_status(9, StatusType::Invalid);
+        for index in [10, usize::MAX] {
+            match builder.try_set_status(index, StatusType::Invalid) {
+                Err(BuilderError::MaxLenExceeded(10)) => (),
+                _ => panic!("Expected MaxLenExceeded"),
+            }
+        }
+        assert!(builder.try_pad_to(11).is_err());
+        assert!(builder.try_extend_statuses([StatusType::Valid]).is_err());
+        assert!(builder
+            .try_set_statuses(&[(2, StatusType::Invalid), (usize::MAX, StatusType::Invalid)])
+            .is_err());
+        assert_eq!(builder.get_status(2), Some(StatusType::Invalid));
+        assert_eq!(builder.len(), 10);
+
+        match StatusListBuilder::new(1)
+            .unwrap()
+            .with_max_len(3)
+            .try_with_capacity(usize::MAX, FillStrategy::AllValid)
+        {
+            Err(BuilderError::MaxLenExceeded(3)) => (),
+            _ => panic!("Expected MaxLenExceeded"),
+        }
+        assert!(StatusListBuilder::from_vec(vec![StatusType::Valid; 4], 1).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "past its maximum length")]
+    fn test_set_status_past_max_len_panics() {
+        StatusListBuilder::new(1)
+            .unwrap()
+            .with_max_len(4)
+            .set_status(usize::MAX, StatusType::Invalid);
     }
 
     #[test]
@@ -221,6 +1121,66 @@ mod tests {
         );
     }
     #[test]
+    fn test_default_status_padding() {
+        let builder = StatusListBuilder::new(2)
+            .unwrap()
+            .with_default_status(StatusType::ApplicationSpecific3)
+            .unwrap();
+        assert_eq!(
+            builder.get_default_status(),
+            StatusType::ApplicationSpecific3
+        );
+
+        builder.add_status(StatusType::Invalid).pad_to(4);
+
+        assert_eq!(
+            snapshot(&builder),
+            vec![
+                StatusType::Invalid,
+                StatusType::ApplicationSpecific3,
+                StatusType::ApplicationSpecific3,
+                StatusType::ApplicationSpecific3,
+            ]
+        );
+        assert_eq!(builder.get_last_index(), Some(3));
+
+        builder.pad_to(2);
+        assert_eq!(builder.len(), 4);
+    }
+
+    #[test]
+    fn test_pad_to_uses_valid_by_default() {
+        let builder = StatusListBuilder::new(1).unwrap();
+        builder.pad_to(3);
+
+        assert_eq!(builder.get_default_status(), StatusType::Valid);
+        assert_eq!(snapshot(&builder), vec![StatusType::Valid; 3]);
+        assert_eq!(builder.get_last_index(), Some(2));
+    }
+
+    #[test]
+    fn test_default_status_must_fit_bits() {
+        let error = StatusListBuilder::new(1)
+            .unwrap()
+            .with_default_status(StatusType::Suspended)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Status type 2 cannot be represented with 1 bits per status"
+        );
+
+        assert!(StatusListBuilder::new(4)
+            .unwrap()
+            .with_default_status(StatusType::ApplicationSpecific15)
+            .is_ok());
+        assert!(StatusListBuilder::new(8)
+            .unwrap()
+            .with_default_status(StatusType::ApplicationSpecific15)
+            .is_ok());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
     fn test_spec_example() {
         let statuses = vec![
             StatusType::Invalid,
@@ -0,0 +1,151 @@
+use crate::builder::StatusListBuilder;
+use crate::error::ApplyError;
+use crate::types::{StatusList, StatusType};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ApplyOptions {
+    batch_size: usize,
+    publish_every: Option<usize>,
+}
+
+impl Default for ApplyOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 10_000,
+            publish_every: None,
+        }
+    }
+}
+
+impl ApplyOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Events applied per lock acquisition.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Builds an intermediate list every `batches` batches. The final list is
+    /// always built.
+    pub fn with_publish_every(mut self, batches: usize) -> Self {
+        self.publish_every = Some(batches.max(1));
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ApplyProgress {
+    pub applied: usize,
+    pub batches: usize,
+    pub published: usize,
+}
+
+impl StatusListBuilder {
+    /// Replays `(index, status)` events in bounded batches. The builder lock
+    /// is released between batches, so readers and other writers interleave
+    /// with a long replay.
+    ///
+    /// `on_progress` runs after every batch, outside the lock, with the list
+    /// to publish on batches where one was built. It applies back-pressure by
+    /// blocking until the publish has gone through, and stops the replay by
+    /// returning an error.
+    pub fn apply_events(
+        &self,
+        events: impl IntoIterator<Item = (usize, StatusType)>,
+        options: ApplyOptions,
+        mut on_progress: impl FnMut(&ApplyProgress, Option<&StatusList>) -> Result<(), String>,
+    ) -> Result<ApplyProgress, ApplyError> {
+        let mut events = events.into_iter().peekable();
+        let mut progress = ApplyProgress::default();
+
+        while events.peek().is_some() {
+            progress.applied += self
+                .set_status_batch(&mut events, options.batch_size)
+                .map_err(ApplyError::BuilderError)?;
+            progress.batches += 1;
+
+            let checkpoint = events.peek().is_none()
+                || options
+                    .publish_every
+                    .is_some_and(|every| progress.batches.is_multiple_of(every));
+            let status_list = if checkpoint {
+                progress.published += 1;
+                Some(self.build().map_err(ApplyError::BuilderError)?)
+            } else {
+                None
+            };
+            on_progress(&progress, status_list.as_ref()).map_err(|reason| ApplyError::Aborted {
+                applied: progress.applied,
+                reason,
+            })?;
+        }
+        Ok(progress)
+    }
+}
+
+#[cfg(all(test, feature = "decoder"))]
+mod tests {
+    use super::*;
+    use crate::decoder::StatusListDecoder;
+
+    #[test]
+    fn test_apply_events_in_batches() {
+        let builder = StatusListBuilder::new(1).unwrap();
+        let events = (0..25).map(|index| (index * 2, StatusType::Invalid));
+
+        let mut checkpoints = Vec::new();
+        let progress = builder
+            .apply_events(
+                events,
+                ApplyOptions::new().with_batch_size(4).with_publish_every(3),
+                |progress, status_list| {
+                    if let Some(status_list) = status_list {
+                        let decoder = StatusListDecoder::new(status_list).unwrap();
+                        checkpoints.push((progress.applied, decoder.stats().invalid()));
+                    }
+                    Ok(())
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            progress,
+            ApplyProgress {
+                applied: 25,
+                batches: 7,
+                published: 3,
+            }
+        );
+        assert_eq!(checkpoints, vec![(12, 12), (24, 24), (25, 25)]);
+        assert_eq!(builder.len(), 49);
+        assert_eq!(builder.get_status(48), Some(StatusType::Invalid));
+    }
+
+    #[test]
+    fn test_apply_events_aborts() {
+        let builder = StatusListBuilder::new(2).unwrap();
+        let events = (0..10).map(|index| (index, StatusType::Suspended));
+
+        let result = builder.apply_events(
+            events,
+            ApplyOptions::new().with_batch_size(3),
+            |progress, _| {
+                if progress.batches == 2 {
+                    return Err("publisher unavailable".to_string());
+                }
+                Ok(())
+            },
+        );
+        match result {
+            Err(ApplyError::Aborted { applied, reason }) => {
+                assert_eq!(applied, 6);
+                assert_eq!(reason, "publisher unavailable");
+            }
+            _ => panic!("Expected Aborted error"),
+        }
+        assert_eq!(builder.len(), 6);
+    }
+}
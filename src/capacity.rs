@@ -0,0 +1,158 @@
+#[cfg(feature = "decoder")]
+use flate2::{Decompress, FlushDecompress, Status};
+
+#[cfg(feature = "encoder")]
+use crate::bitops;
+#[cfg(feature = "encoder")]
+use crate::compression::{Compressor, ZlibCompression};
+#[cfg(feature = "decoder")]
+use crate::error::DecoderError;
+#[cfg(feature = "encoder")]
+use crate::error::StatusTypeError;
+#[cfg(feature = "encoder")]
+use crate::rng::SplitMix64;
+#[cfg(feature = "decoder")]
+use crate::types::StatusList;
+#[cfg(feature = "encoder")]
+use crate::types::{BitsPerStatus, StatusType};
+
+// Larger lists are estimated from a sample of this many statuses.
+#[cfg(feature = "encoder")]
+const SAMPLE_ENTRIES: usize = 1 << 20;
+
+#[cfg(feature = "decoder")]
+impl StatusList {
+    /// Size in bytes of the decompressed list, found by inflating it into a
+    /// small scratch buffer rather than allocating the whole list.
+    pub fn uncompressed_len(&self) -> Result<usize, DecoderError> {
+        let mut inflater = Decompress::new(true);
+        let mut scratch = [0u8; 16 * 1024];
+        loop {
+            let (total_in, total_out) = (inflater.total_in(), inflater.total_out());
+            let status = inflater
+                .decompress(
+                    &self.lst[total_in as usize..],
+                    &mut scratch,
+                    FlushDecompress::None,
+                )
+                .map_err(|e| DecoderError::DecompressionError(e.to_string()))?;
+
+            if status == Status::StreamEnd {
+                return Ok(inflater.total_out() as usize);
+            }
+            if inflater.total_in() == total_in && inflater.total_out() == total_out {
+                return Err(DecoderError::DecompressionError(
+                    "truncated ZLIB stream".to_string(),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "encoder")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizeEstimate {
+    pub entries: usize,
+    pub bits: u8,
+    /// Exact size of the packed statuses.
+    pub uncompressed_bytes: usize,
+    /// Expected ZLIB size when revocations are spread uniformly at random,
+    /// the worst case for compression at a given rate.
+    pub compressed_bytes: usize,
+    /// Expected length of the base64url `lst` claim in the JSON form.
+    pub base64url_len: usize,
+}
+
+/// Estimates list size for `num_entries` statuses of `bits` each, with a
+/// share `revocation_rate` (0.0 to 1.0) of them Invalid.
+#[cfg(feature = "encoder")]
+pub fn size_estimate(
+    num_entries: usize,
+    bits: u8,
+    revocation_rate: f64,
+) -> Result<SizeEstimate, StatusTypeError> {
+    let bits_per_status = BitsPerStatus::try_from(bits)?;
+    let rate = revocation_rate.clamp(0.0, 1.0);
+    let threshold = (rate * u64::MAX as f64) as u64;
+
+    let sample_entries = num_entries.min(SAMPLE_ENTRIES);
+    let mut sample = vec![0u8; bitops::packed_len(sample_entries, bits_per_status)];
+    let mut rng = SplitMix64::new(num_entries as u64);
+    for index in 0..sample_entries {
+        if rate > 0.0 && rng.next_u64() <= threshold {
+            bitops::encode_status(
+                &mut sample,
+                index,
+                bits_per_status,
+                StatusType::Invalid.value(),
+            )
+            .expect("index is within the sample");
+        }
+    }
+    let sample_compressed = ZlibCompression::default()
+        .compress(&sample)
+        .expect("compressing into memory does not fail")
+        .len();
+
+    let compressed_bytes = if sample_entries == num_entries {
+        sample_compressed
+    } else {
+        (sample_compressed as f64 * num_entries as f64 / sample_entries as f64).ceil() as usize
+    };
+    Ok(SizeEstimate {
+        entries: num_entries,
+        bits,
+        uncompressed_bytes: bitops::packed_len(num_entries, bits_per_status),
+        compressed_bytes,
+        base64url_len: (compressed_bytes * 4).div_ceil(3),
+    })
+}
+
+#[cfg(all(test, feature = "encoder", feature = "decoder"))]
+mod tests {
+    use super::*;
+    use crate::builder::StatusListBuilder;
+
+    #[test]
+    fn test_uncompressed_len() {
+        let builder = StatusListBuilder::new(2).unwrap();
+        builder.set_status(40_001, StatusType::Invalid);
+        let status_list = builder.build().unwrap();
+        assert_eq!(status_list.uncompressed_len().unwrap(), 10_001);
+        assert_eq!(builder.len(), 40_002);
+        assert_eq!(builder.capacity(), 40_004);
+
+        let truncated = StatusList::from_compressed(2, status_list.lst()[..6].to_vec()).unwrap();
+        assert!(matches!(
+            truncated.uncompressed_len(),
+            Err(DecoderError::DecompressionError(_))
+        ));
+    }
+
+    #[test]
+    fn test_size_estimate() {
+        let empty = size_estimate(100_000, 1, 0.0).unwrap();
+        assert_eq!(empty.uncompressed_bytes, 12_500);
+        assert!(empty.compressed_bytes < 100);
+
+        let revoked = size_estimate(100_000, 1, 0.05).unwrap();
+        assert!(revoked.compressed_bytes > 10 * empty.compressed_bytes);
+        assert!(revoked.compressed_bytes < revoked.uncompressed_bytes);
+        assert_eq!(
+            revoked.base64url_len,
+            (revoked.compressed_bytes * 4).div_ceil(3)
+        );
+
+        // Beyond the sample size the compressed size is extrapolated.
+        let large = size_estimate(4 * SAMPLE_ENTRIES, 8, 0.01).unwrap();
+        assert_eq!(large.uncompressed_bytes, 4 * SAMPLE_ENTRIES);
+        let sampled = size_estimate(SAMPLE_ENTRIES, 8, 0.01).unwrap();
+        let ratio = large.compressed_bytes as f64 / sampled.compressed_bytes as f64;
+        assert!((3.9..4.1).contains(&ratio));
+
+        assert!(matches!(
+            size_estimate(10, 3, 0.1),
+            Err(StatusTypeError::InvalidBitsPerStatus(3))
+        ));
+    }
+}
@@ -0,0 +1,225 @@
+use serde::{Deserialize, Serialize};
+
+use crate::bitops;
+use crate::error::ChangelogError;
+use crate::signing::{self, PayloadType, SignatureVerifier, Signer};
+use crate::types::{BitsPerStatus, StatusType};
+use crate::uri::StatusListUri;
+
+pub const CHANGELOG_CONTENT_TYPE: &str = "application/statuslist-changelog+cbor";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ChangelogEntry {
+    pub idx: usize,
+    pub status: u8,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StatusListChangelog {
+    pub uri: StatusListUri,
+    pub bits: u8,
+    pub from_version: u64,
+    pub to_version: u64,
+    pub changes: Vec<ChangelogEntry>,
+}
+
+impl StatusListChangelog {
+    pub fn new(
+        uri: StatusListUri,
+        bits: BitsPerStatus,
+        from_version: u64,
+        to_version: u64,
+    ) -> Self {
+        Self {
+            uri,
+            bits: bits as u8,
+            from_version,
+            to_version,
+            changes: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, idx: usize, status: StatusType) -> &mut Self {
+        self.changes.push(ChangelogEntry {
+            idx,
+            status: status.value(),
+        });
+        self
+    }
+
+    pub fn sign(&self, signer: &impl Signer) -> Result<Vec<u8>, ChangelogError> {
+        self.check_versions()?;
+
+        let mut payload = Vec::new();
+        ciborium::ser::into_writer(self, &mut payload)
+            .map_err(|e| ChangelogError::EncodingError(e.to_string()))?;
+
+        Ok(signing::sign_cose(
+            PayloadType::ContentType(CHANGELOG_CONTENT_TYPE),
+            payload,
+            signer,
+        )?)
+    }
+
+    /// Verifies a signed changelog for the list at `expected_uri`.
+    pub fn verify(
+        bytes: &[u8],
+        expected_uri: &StatusListUri,
+        verifier: &impl SignatureVerifier,
+    ) -> Result<Self, ChangelogError> {
+        let payload = signing::verify_cose(
+            bytes,
+            PayloadType::ContentType(CHANGELOG_CONTENT_TYPE),
+            verifier,
+        )?;
+        let changelog: Self = ciborium::de::from_reader(&payload[..])
+            .map_err(|e| ChangelogError::EncodingError(e.to_string()))?;
+
+        expected_uri
+            .ensure_matches(&changelog.uri)
+            .map_err(|e| ChangelogError::UriMismatch(e.to_string()))?;
+        BitsPerStatus::try_from(changelog.bits)
+            .map_err(|e| ChangelogError::EncodingError(e.to_string()))?;
+        changelog.check_versions()?;
+
+        Ok(changelog)
+    }
+
+    /// Applies the changes to the packed bytes of a list, which must use the
+    /// changelog's bit width.
+    pub fn apply_to(&self, bytes: &mut [u8], bits: BitsPerStatus) -> Result<(), ChangelogError> {
+        if bits as u8 != self.bits {
+            return Err(ChangelogError::UnexpectedBits(bits as u8, self.bits));
+        }
+        for entry in &self.changes {
+            bitops::encode_status(bytes, entry.idx, bits, entry.status)
+                .map_err(|_| ChangelogError::InvalidEntry(entry.idx))?;
+        }
+        Ok(())
+    }
+
+    fn check_versions(&self) -> Result<(), ChangelogError> {
+        if self.to_version <= self.from_version {
+            return Err(ChangelogError::InvalidVersionRange(
+                self.from_version,
+                self.to_version,
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing::tests::TestKey;
+
+    fn uri() -> StatusListUri {
+        StatusListUri::parse("https://example.com/statuslists/1").unwrap()
+    }
+
+    fn sample_changelog() -> StatusListChangelog {
+        let mut changelog = StatusListChangelog::new(uri(), BitsPerStatus::TwoBit, 4, 5);
+        changelog
+            .record(1, StatusType::Invalid)
+            .record(6, StatusType::Suspended);
+        changelog
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let changelog = sample_changelog();
+        let signed = changelog.sign(&TestKey(7)).unwrap();
+
+        let verified = StatusListChangelog::verify(&signed, &uri(), &TestKey(7)).unwrap();
+        assert_eq!(verified, changelog);
+    }
+
+    #[test]
+    fn test_verify_with_wrong_key() {
+        let signed = sample_changelog().sign(&TestKey(7)).unwrap();
+
+        match StatusListChangelog::verify(&signed, &uri(), &TestKey(8)) {
+            Err(ChangelogError::SignatureError(msg)) => assert_eq!(msg, "bad signature"),
+            _ => panic!("Expected SignatureError"),
+        }
+    }
+
+    #[test]
+    fn test_verify_checks_uri() {
+        let signed = sample_changelog().sign(&TestKey(7)).unwrap();
+        let other = StatusListUri::parse("https://example.com/statuslists/2").unwrap();
+
+        match StatusListChangelog::verify(&signed, &other, &TestKey(7)) {
+            Err(ChangelogError::UriMismatch(msg)) => assert!(msg.contains("path")),
+            _ => panic!("Expected UriMismatch"),
+        }
+    }
+
+    #[test]
+    fn test_invalid_version_range() {
+        let changelog = StatusListChangelog::new(uri(), BitsPerStatus::OneBit, 5, 5);
+
+        match changelog.sign(&TestKey(7)) {
+            Err(ChangelogError::InvalidVersionRange(5, 5)) => (),
+            _ => panic!("Expected InvalidVersionRange error"),
+        }
+    }
+
+    #[test]
+    fn test_apply_to() {
+        let mut bytes = vec![0u8; 2];
+        sample_changelog()
+            .apply_to(&mut bytes, BitsPerStatus::TwoBit)
+            .unwrap();
+        assert_eq!(bytes, vec![0b0000_0100, 0b0010_0000]);
+
+        match sample_changelog().apply_to(&mut bytes, BitsPerStatus::OneBit) {
+            Err(ChangelogError::UnexpectedBits(1, 2)) => (),
+            _ => panic!("Expected UnexpectedBits error"),
+        }
+
+        let mut changelog = sample_changelog();
+        changelog.record(8, StatusType::Invalid);
+        match changelog.apply_to(&mut bytes, BitsPerStatus::TwoBit) {
+            Err(ChangelogError::InvalidEntry(8)) => (),
+            _ => panic!("Expected InvalidEntry error"),
+        }
+    }
+
+    #[test]
+    fn test_changelog_error_display() {
+        let errors = [
+            ChangelogError::EncodingError("bad cbor".to_string()),
+            ChangelogError::SignatureError("bad signature".to_string()),
+            ChangelogError::InvalidVersionRange(3, 2),
+            ChangelogError::InvalidEntry(9),
+            ChangelogError::UriMismatch("path differs".to_string()),
+            ChangelogError::UnexpectedBits(1, 2),
+        ];
+
+        for error in errors {
+            let error_string = error.to_string();
+            match error {
+                ChangelogError::EncodingError(_) => {
+                    assert!(error_string.contains("Changelog encoding error"));
+                }
+                ChangelogError::SignatureError(_) => {
+                    assert!(error_string.contains("Changelog signature error"));
+                }
+                ChangelogError::InvalidVersionRange(_, _) => {
+                    assert!(error_string.contains("Invalid changelog version range"));
+                }
+                ChangelogError::InvalidEntry(_) => {
+                    assert!(error_string.contains("Invalid changelog entry"));
+                }
+                ChangelogError::UriMismatch(_) => {
+                    assert!(error_string.contains("Changelog is for another list"));
+                }
+                ChangelogError::UnexpectedBits(_, _) => {
+                    assert!(error_string.contains("bits per status"));
+                }
+            }
+        }
+    }
+}
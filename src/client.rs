@@ -0,0 +1,1132 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use futures_util::future::join_all;
+
+#[cfg(feature = "changelog")]
+use crate::changelog::{StatusListChangelog, CHANGELOG_CONTENT_TYPE};
+use crate::decoder::StatusListDecoder;
+use crate::error::{ClientError, TokenError};
+use crate::jwt::JWT_MEDIA_TYPE;
+use crate::signing::SignatureVerifier;
+use crate::token::{Freshness, ReplayWindow, StatusListTokenVerifier, CWT_TYPE};
+#[cfg(feature = "http")]
+use crate::transport::ReqwestTransport;
+use crate::transport::{DefaultTransport, Transport};
+use crate::types::{BitsPerStatus, StatusListReference, StatusType};
+use crate::uri::StatusListUri;
+
+// Both token forms are accepted; the response's Content-Type says which
+// one the server picked.
+const ACCEPT: &str = "application/statuslist+jwt, application/statuslist+cwt";
+
+// Served for a list its issuer has retired; unlike other failures it will
+// not go away on retry.
+const GONE: u16 = 410;
+
+/// A Status List Token as served, in the form named by its media type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FetchedToken {
+    Cwt(Vec<u8>),
+    Jwt(String),
+}
+
+impl FetchedToken {
+    fn from_response(content_type: Option<&str>, body: Vec<u8>) -> Result<Self, ClientError> {
+        let media_type = content_type
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_ascii_lowercase())
+            .unwrap_or_default();
+        match media_type.as_str() {
+            CWT_TYPE => Ok(Self::Cwt(body)),
+            JWT_MEDIA_TYPE => String::from_utf8(body).map(Self::Jwt).map_err(|_| {
+                ClientError::TokenError(TokenError::EncodingError(
+                    "JWT is not valid UTF-8".to_string(),
+                ))
+            }),
+            _ => Err(ClientError::UnexpectedContentType(media_type)),
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Cwt(bytes) => bytes,
+            Self::Jwt(token) => token.as_bytes(),
+        }
+    }
+
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            Self::Cwt(_) => CWT_TYPE,
+            Self::Jwt(_) => JWT_MEDIA_TYPE,
+        }
+    }
+
+    // Returns the list and the token's time claims.
+    fn verify(
+        &self,
+        token_verifier: &StatusListTokenVerifier,
+        verifier: &impl SignatureVerifier,
+        now: u64,
+    ) -> Result<(StatusListDecoder, Freshness), ClientError> {
+        match self {
+            Self::Cwt(bytes) => token_verifier.verify_cwt_until(bytes, verifier, now),
+            Self::Jwt(token) => token_verifier.verify_jwt_until(token, verifier, now),
+        }
+        .map_err(ClientError::TokenError)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StatusListClient<T = DefaultTransport> {
+    transport: T,
+    leeway: u64,
+}
+
+#[cfg(feature = "http")]
+impl StatusListClient<ReqwestTransport> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.transport = ReqwestTransport::new(http);
+        self
+    }
+}
+
+impl<T: Transport> StatusListClient<T> {
+    pub fn with_transport(transport: T) -> Self {
+        Self {
+            transport,
+            leeway: 0,
+        }
+    }
+
+    pub fn with_leeway(mut self, leeway: u64) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    pub async fn fetch_token(&self, uri: &StatusListUri) -> Result<FetchedToken, ClientError> {
+        Ok(self.fetch_token_with_max_age(uri).await?.0)
+    }
+
+    async fn fetch_token_with_max_age(
+        &self,
+        uri: &StatusListUri,
+    ) -> Result<(FetchedToken, Option<u64>), ClientError> {
+        let response = self.transport.get(uri.as_str(), ACCEPT).await?;
+        if response.status == GONE {
+            return Err(ClientError::Retired(uri.to_string()));
+        }
+        if !(200..300).contains(&response.status) {
+            return Err(ClientError::HttpStatus(response.status));
+        }
+
+        let max_age = response.cache_control.as_deref().and_then(parse_max_age);
+        let token = FetchedToken::from_response(response.content_type.as_deref(), response.body)?;
+        Ok((token, max_age))
+    }
+
+    // Asks for the signed changes since `version` at `<uri>?since=<version>`.
+    #[cfg(feature = "changelog")]
+    async fn fetch_changelog(
+        &self,
+        uri: &StatusListUri,
+        version: u64,
+    ) -> Result<(Vec<u8>, Option<u64>), ClientError> {
+        let separator = if uri.as_str().contains('?') { '&' } else { '?' };
+        let url = format!("{}{}since={}", uri, separator, version);
+        let response = self.transport.get(&url, CHANGELOG_CONTENT_TYPE).await?;
+        if !(200..300).contains(&response.status) {
+            return Err(ClientError::HttpStatus(response.status));
+        }
+
+        let media_type = response
+            .content_type
+            .as_deref()
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_ascii_lowercase())
+            .unwrap_or_default();
+        if media_type != CHANGELOG_CONTENT_TYPE {
+            return Err(ClientError::UnexpectedContentType(media_type));
+        }
+        let max_age = response.cache_control.as_deref().and_then(parse_max_age);
+        Ok((response.body, max_age))
+    }
+
+    pub async fn fetch(
+        &self,
+        uri: &StatusListUri,
+        verifier: &impl SignatureVerifier,
+        now: u64,
+    ) -> Result<StatusListDecoder, ClientError> {
+        let token = self.fetch_token(uri).await?;
+        let token_verifier = StatusListTokenVerifier::new(uri.clone()).with_leeway(self.leeway);
+        Ok(token.verify(&token_verifier, verifier, now)?.0)
+    }
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    decoder: Arc<StatusListDecoder>,
+    freshness: Freshness,
+    expires_at: u64,
+}
+
+// Fetched tokens go through `StatusListTokenVerifier`, so a cache never
+// serves a list older than one it has already seen.
+#[derive(Debug, Default)]
+pub struct StatusListCache<T = DefaultTransport> {
+    client: StatusListClient<T>,
+    entries: Mutex<HashMap<String, CacheEntry>>,
+    retired: Mutex<HashSet<String>>,
+    replay_window: ReplayWindow,
+    expected_bits: Option<BitsPerStatus>,
+    known_lens: HashMap<String, usize>,
+    results: Option<Mutex<StatusLru>>,
+    #[cfg(feature = "changelog")]
+    delta_sync: bool,
+}
+
+impl<T: Transport> StatusListCache<T> {
+    pub fn new(client: StatusListClient<T>) -> Self {
+        Self {
+            client,
+            entries: Mutex::new(HashMap::new()),
+            retired: Mutex::new(HashSet::new()),
+            replay_window: ReplayWindow::new(),
+            expected_bits: None,
+            known_lens: HashMap::new(),
+            results: None,
+            #[cfg(feature = "changelog")]
+            delta_sync: false,
+        }
+    }
+
+    /// Also keeps the last `capacity` individual (uri, idx) results, each
+    /// valid as long as the list it came from, for credentials checked far
+    /// more often than their lists change.
+    pub fn with_status_cache(mut self, capacity: usize) -> Self {
+        self.results = Some(Mutex::new(StatusLru::new(capacity)));
+        self
+    }
+
+    /// Shares the highest `iat` seen per list with other verifiers.
+    pub fn with_replay_window(mut self, replay_window: ReplayWindow) -> Self {
+        self.replay_window = replay_window;
+        self
+    }
+
+    pub fn with_expected_bits(mut self, bits: BitsPerStatus) -> Self {
+        self.expected_bits = Some(bits);
+        self
+    }
+
+    /// The number of statuses allocated in the list at `uri`. Lookups at or
+    /// past it fail with `DecoderError::IndexNotPresent` rather than reading
+    /// padding bits as valid.
+    pub fn with_known_len(mut self, uri: &StatusListUri, len: usize) -> Self {
+        self.known_lens.insert(uri.as_str().to_string(), len);
+        self
+    }
+
+    /// Refreshes a stale list from the signed changelog since it was issued
+    /// instead of refetching the whole token, when the server offers one.
+    /// Changelog versions are the `iat` of the lists they lead from and to.
+    /// The updated list stays fresh for the original token's `ttl` from the
+    /// new version, never past its `exp`. Any failure to fetch, verify or
+    /// apply the changelog falls back to a full fetch.
+    #[cfg(feature = "changelog")]
+    pub fn with_delta_sync(mut self) -> Self {
+        self.delta_sync = true;
+        self
+    }
+
+    pub async fn get(
+        &self,
+        uri: &StatusListUri,
+        verifier: &impl SignatureVerifier,
+        now: u64,
+    ) -> Result<Arc<StatusListDecoder>, ClientError> {
+        Ok(self.entry(uri, verifier, now).await?.0)
+    }
+
+    // Returns the list and when it expires. Retired lists are remembered
+    // and never refetched.
+    async fn entry(
+        &self,
+        uri: &StatusListUri,
+        verifier: &impl SignatureVerifier,
+        now: u64,
+    ) -> Result<(Arc<StatusListDecoder>, u64), ClientError> {
+        if let Some(entry) = self.entries.lock().unwrap().get(uri.as_str()) {
+            if now < entry.expires_at {
+                return Ok((entry.decoder.clone(), entry.expires_at));
+            }
+        }
+
+        if self.retired.lock().unwrap().contains(uri.as_str()) {
+            return Err(ClientError::Retired(uri.to_string()));
+        }
+        #[cfg(feature = "changelog")]
+        if self.delta_sync {
+            if let Some((decoder, freshness, max_age)) = self.sync(uri, verifier, now).await {
+                return Ok(self.insert(uri, decoder, freshness, max_age, now));
+            }
+        }
+
+        let (token, max_age) = match self.client.fetch_token_with_max_age(uri).await {
+            Err(ClientError::Retired(retired)) => {
+                self.retired
+                    .lock()
+                    .unwrap()
+                    .insert(uri.as_str().to_string());
+                self.entries.lock().unwrap().remove(uri.as_str());
+                if let Some(results) = &self.results {
+                    results.lock().unwrap().remove_list(uri.as_str());
+                }
+                return Err(ClientError::Retired(retired));
+            }
+            result => result?,
+        };
+        let (decoder, freshness) = token.verify(&self.token_verifier(uri), verifier, now)?;
+        Ok(self.insert(uri, decoder, freshness, max_age, now))
+    }
+
+    fn insert(
+        &self,
+        uri: &StatusListUri,
+        decoder: StatusListDecoder,
+        freshness: Freshness,
+        max_age: Option<u64>,
+        now: u64,
+    ) -> (Arc<StatusListDecoder>, u64) {
+        let decoder = Arc::new(decoder);
+        let expires_at = expires_at(freshness, max_age, now);
+        self.entries.lock().unwrap().insert(
+            uri.as_str().to_string(),
+            CacheEntry {
+                decoder: decoder.clone(),
+                freshness,
+                expires_at,
+            },
+        );
+        (decoder, expires_at)
+    }
+
+    // Applies the changelog since the cached list's version, if it brings
+    // the list back to freshness.
+    #[cfg(feature = "changelog")]
+    async fn sync(
+        &self,
+        uri: &StatusListUri,
+        verifier: &impl SignatureVerifier,
+        now: u64,
+    ) -> Option<(StatusListDecoder, Freshness, Option<u64>)> {
+        let (decoder, freshness) = {
+            let entries = self.entries.lock().unwrap();
+            let entry = entries.get(uri.as_str())?;
+            (entry.decoder.clone(), entry.freshness)
+        };
+        let (bytes, max_age) = self.client.fetch_changelog(uri, freshness.iat).await.ok()?;
+        let changelog = StatusListChangelog::verify(&bytes, uri, verifier).ok()?;
+        if changelog.from_version != freshness.iat
+            || changelog.to_version > now.saturating_add(self.client.leeway)
+        {
+            return None;
+        }
+
+        let freshness = Freshness {
+            iat: changelog.to_version,
+            ..freshness
+        };
+        if expires_at(freshness, max_age, now) <= now {
+            return None;
+        }
+        let decoder = decoder.apply_changelog(&changelog).ok()?;
+        Some((decoder, freshness, max_age))
+    }
+
+    pub async fn get_status(
+        &self,
+        uri: &StatusListUri,
+        index: usize,
+        verifier: &impl SignatureVerifier,
+        now: u64,
+    ) -> Result<StatusType, ClientError> {
+        if let Some(status) = self.cached_status(uri, index, now) {
+            return Ok(status);
+        }
+        let (decoder, expires_at) = self.entry(uri, verifier, now).await?;
+        self.lookup(uri, index, &decoder, expires_at)
+    }
+
+    /// Checks many references at once. Each distinct list is resolved once,
+    /// concurrently with the others, and results come back in input order; a
+    /// list that cannot be fetched or verified only fails its own references.
+    pub async fn check_many(
+        &self,
+        references: &[StatusListReference],
+        verifier: &impl SignatureVerifier,
+        now: u64,
+    ) -> Vec<Result<StatusType, ClientError>> {
+        let cached: Vec<_> = references
+            .iter()
+            .map(|reference| self.cached_status(&reference.uri, reference.idx, now))
+            .collect();
+        let mut uris: Vec<&StatusListUri> = references
+            .iter()
+            .zip(&cached)
+            .filter(|(_, status)| status.is_none())
+            .map(|(reference, _)| &reference.uri)
+            .collect();
+        uris.sort();
+        uris.dedup();
+
+        let resolved = join_all(uris.iter().map(|uri| self.entry(uri, verifier, now))).await;
+        let lists: HashMap<_, _> = uris.into_iter().zip(resolved).collect();
+        references
+            .iter()
+            .zip(cached)
+            .map(
+                |(reference, cached)| match (cached, lists.get(&reference.uri)) {
+                    (Some(status), _) => Ok(status),
+                    (None, Some(Ok((decoder, expires_at)))) => {
+                        self.lookup(&reference.uri, reference.idx, decoder, *expires_at)
+                    }
+                    (None, Some(Err(e))) => Err(e.clone()),
+                    (None, None) => unreachable!("every uncached reference was resolved"),
+                },
+            )
+            .collect()
+    }
+
+    pub fn invalidate(&self, uri: &StatusListUri) {
+        self.entries.lock().unwrap().remove(uri.as_str());
+        self.retired.lock().unwrap().remove(uri.as_str());
+        if let Some(results) = &self.results {
+            results.lock().unwrap().remove_list(uri.as_str());
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // The cached list's time claims and when the cache stops serving it.
+    pub(crate) fn cached_freshness(&self, uri: &StatusListUri) -> Option<(Freshness, u64)> {
+        let entries = self.entries.lock().unwrap();
+        let entry = entries.get(uri.as_str())?;
+        Some((entry.freshness, entry.expires_at))
+    }
+
+    fn cached_status(&self, uri: &StatusListUri, index: usize, now: u64) -> Option<StatusType> {
+        self.results
+            .as_ref()?
+            .lock()
+            .unwrap()
+            .get(uri.as_str(), index, now)
+    }
+
+    fn lookup(
+        &self,
+        uri: &StatusListUri,
+        index: usize,
+        decoder: &StatusListDecoder,
+        expires_at: u64,
+    ) -> Result<StatusType, ClientError> {
+        let status = decoder
+            .get_status(index)
+            .map_err(ClientError::DecoderError)?;
+        if let Some(results) = &self.results {
+            results
+                .lock()
+                .unwrap()
+                .insert(uri.as_str(), index, status, expires_at);
+        }
+        Ok(status)
+    }
+
+    fn token_verifier(&self, uri: &StatusListUri) -> StatusListTokenVerifier {
+        let mut verifier = StatusListTokenVerifier::new(uri.clone())
+            .with_leeway(self.client.leeway)
+            .with_replay_window(self.replay_window.clone());
+        if let Some(bits) = self.expected_bits {
+            verifier = verifier.with_expected_bits(bits);
+        }
+        match self.known_lens.get(uri.as_str()) {
+            Some(&len) => verifier.with_known_len(len),
+            None => verifier,
+        }
+    }
+}
+
+type ResultKey = (String, usize);
+
+#[derive(Debug)]
+struct StatusLru {
+    capacity: usize,
+    clock: u64,
+    // (status, valid_until, last_used) per key, and keys by last use.
+    entries: HashMap<ResultKey, (StatusType, u64, u64)>,
+    recency: BTreeMap<u64, ResultKey>,
+}
+
+impl StatusLru {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            clock: 0,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+        }
+    }
+
+    fn get(&mut self, uri: &str, idx: usize, now: u64) -> Option<StatusType> {
+        let key = (uri.to_string(), idx);
+        let (status, valid_until, last_used) = *self.entries.get(&key)?;
+        self.recency.remove(&last_used);
+        if now >= valid_until {
+            self.entries.remove(&key);
+            return None;
+        }
+
+        self.clock += 1;
+        self.recency.insert(self.clock, key.clone());
+        self.entries.insert(key, (status, valid_until, self.clock));
+        Some(status)
+    }
+
+    fn insert(&mut self, uri: &str, idx: usize, status: StatusType, valid_until: u64) {
+        if self.capacity == 0 {
+            return;
+        }
+        let key = (uri.to_string(), idx);
+        self.clock += 1;
+        if let Some((_, _, last_used)) = self
+            .entries
+            .insert(key.clone(), (status, valid_until, self.clock))
+        {
+            self.recency.remove(&last_used);
+        }
+        self.recency.insert(self.clock, key);
+
+        while self.entries.len() > self.capacity {
+            let (_, oldest) = self.recency.pop_first().expect("an entry per key");
+            self.entries.remove(&oldest);
+        }
+    }
+
+    fn remove_list(&mut self, uri: &str) {
+        self.entries.retain(|(entry_uri, _), (_, _, last_used)| {
+            entry_uri != uri || self.recency.remove(last_used).is_none()
+        });
+    }
+}
+
+// The earliest of exp, iat + ttl and the HTTP max-age bounds how long a list
+// may be served from cache.
+fn expires_at(freshness: Freshness, max_age: Option<u64>, now: u64) -> u64 {
+    [
+        freshness.stale_at(),
+        max_age.map(|max_age| now.saturating_add(max_age)),
+    ]
+    .into_iter()
+    .flatten()
+    .min()
+    .unwrap_or(now)
+}
+
+fn parse_max_age(cache_control: &str) -> Option<u64> {
+    let mut max_age = None;
+    for directive in cache_control.split(',').map(str::trim) {
+        let directive = directive.to_ascii_lowercase();
+        if directive == "no-store" || directive == "no-cache" {
+            return Some(0);
+        }
+        if let Some(value) = directive.strip_prefix("max-age=") {
+            max_age = value.trim_matches('"').parse().ok();
+        }
+    }
+    max_age
+}
+
+#[cfg(all(test, feature = "http", feature = "encoder"))]
+mod tests {
+    use super::*;
+    use crate::builder::StatusListBuilder;
+    use crate::error::DecoderError;
+    use crate::jwt::JwtStatusListToken;
+    use crate::signing::tests::TestKey;
+    use crate::token::CwtStatusListToken;
+    use crate::transport::TransportResponse;
+    use crate::types::StatusType;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn listen() -> (TcpListener, StatusListUri) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let uri = format!("http://{}/statuslists/1", listener.local_addr().unwrap());
+
+        (
+            listener,
+            StatusListUri::parse_with_schemes(&uri, &["http"]).unwrap(),
+        )
+    }
+
+    // Serves a single request and returns the request head it received.
+    fn serve_once(
+        listener: TcpListener,
+        status: &'static str,
+        body: Vec<u8>,
+        gzip: bool,
+    ) -> thread::JoinHandle<String> {
+        serve(listener, status, body, gzip, "")
+    }
+
+    fn serve(
+        listener: TcpListener,
+        status: &'static str,
+        body: Vec<u8>,
+        gzip: bool,
+        extra_headers: &'static str,
+    ) -> thread::JoinHandle<String> {
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![0u8; 4096];
+            let read = stream.read(&mut request).unwrap();
+
+            let body = if gzip {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(&body).unwrap();
+                encoder.finish().unwrap()
+            } else {
+                body
+            };
+            let mut response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n",
+                status,
+                CWT_TYPE,
+                body.len()
+            );
+            if gzip {
+                response.push_str("Content-Encoding: gzip\r\n");
+            }
+            response.push_str(extra_headers);
+            response.push_str("Connection: close\r\n\r\n");
+            stream.write_all(response.as_bytes()).unwrap();
+            stream.write_all(&body).unwrap();
+
+            String::from_utf8_lossy(&request[..read]).to_lowercase()
+        })
+    }
+
+    fn signed_token(uri: &StatusListUri) -> Vec<u8> {
+        signed_token_at(uri, 1000, &TestKey(1))
+    }
+
+    fn signed_token_at(uri: &StatusListUri, iat: u64, key: &TestKey) -> Vec<u8> {
+        let status_list =
+            StatusListBuilder::from_vec(vec![StatusType::Valid, StatusType::Invalid], 1)
+                .unwrap()
+                .build()
+                .unwrap();
+        CwtStatusListToken::new(uri.clone(), iat, status_list)
+            .with_exp(2000)
+            .sign(key)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_fetch_gzip_token() {
+        let (listener, uri) = listen();
+        let handle = serve_once(listener, "200 OK", signed_token(&uri), true);
+
+        let decoder = StatusListClient::new()
+            .fetch(&uri, &TestKey(1), 1500)
+            .await
+            .unwrap();
+        assert_eq!(decoder.get_status(1).unwrap(), StatusType::Invalid);
+
+        let request = handle.join().unwrap();
+        assert!(request.starts_with("get /statuslists/1 "));
+        assert!(request.contains("accept: application/statuslist+jwt, application/statuslist+cwt"));
+        assert!(request.contains("accept-encoding: gzip"));
+    }
+
+    #[test]
+    fn test_parse_max_age() {
+        assert_eq!(parse_max_age("public, max-age=300"), Some(300));
+        assert_eq!(parse_max_age("Max-Age=\"60\""), Some(60));
+        assert_eq!(parse_max_age("no-cache, max-age=300"), Some(0));
+        assert_eq!(parse_max_age("public"), None);
+    }
+
+    #[tokio::test]
+    async fn test_cache_honors_max_age() {
+        let (listener, uri) = listen();
+        let handle = serve(
+            listener,
+            "200 OK",
+            signed_token(&uri),
+            false,
+            "Cache-Control: max-age=60\r\n",
+        );
+        let cache = StatusListCache::new(StatusListClient::new());
+
+        assert_eq!(
+            cache.get_status(&uri, 1, &TestKey(1), 1000).await.unwrap(),
+            StatusType::Invalid
+        );
+        handle.join().unwrap();
+
+        // Served from cache: nothing is listening for a second request.
+        assert_eq!(
+            cache.get_status(&uri, 0, &TestKey(1), 1059).await.unwrap(),
+            StatusType::Valid
+        );
+        assert_eq!(cache.len(), 1);
+
+        match cache.get_status(&uri, 0, &TestKey(1), 1060).await {
+            Err(ClientError::RequestError(_)) => (),
+            _ => panic!("Expected a refresh attempt after max-age"),
+        }
+
+        cache.invalidate(&uri);
+        assert!(cache.is_empty());
+    }
+
+    struct StaticTransport(TransportResponse);
+
+    impl Transport for StaticTransport {
+        async fn get(&self, _url: &str, accept: &str) -> Result<TransportResponse, ClientError> {
+            assert_eq!(accept, format!("{}, {}", JWT_MEDIA_TYPE, CWT_TYPE));
+            Ok(self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_over_custom_transport() {
+        let uri = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        let cache = StatusListCache::new(StatusListClient::with_transport(StaticTransport(
+            TransportResponse {
+                status: 200,
+                content_type: Some(CWT_TYPE.to_string()),
+                cache_control: Some("max-age=60".to_string()),
+                body: signed_token(&uri),
+            },
+        )));
+
+        assert_eq!(
+            cache.get_status(&uri, 1, &TestKey(1), 1000).await.unwrap(),
+            StatusType::Invalid
+        );
+        assert_eq!(cache.len(), 1);
+
+        let client = StatusListClient::with_transport(StaticTransport(TransportResponse {
+            status: 503,
+            content_type: None,
+            cache_control: None,
+            body: Vec::new(),
+        }));
+        assert!(matches!(
+            client.fetch_token(&uri).await,
+            Err(ClientError::HttpStatus(503))
+        ));
+    }
+
+    // Serves fixed responses per URL, 404 otherwise, and logs requests.
+    #[derive(Default)]
+    struct RoutingTransport {
+        responses: HashMap<String, TransportResponse>,
+        requests: Mutex<Vec<String>>,
+    }
+
+    impl RoutingTransport {
+        fn with(self, uri: &StatusListUri, body: Vec<u8>) -> Self {
+            self.with_type(uri, CWT_TYPE, body)
+        }
+
+        fn with_type(mut self, uri: &StatusListUri, content_type: &str, body: Vec<u8>) -> Self {
+            let response = TransportResponse {
+                status: 200,
+                content_type: Some(content_type.to_string()),
+                cache_control: None,
+                body,
+            };
+            self.responses.insert(uri.to_string(), response);
+            self
+        }
+    }
+
+    impl Transport for &RoutingTransport {
+        async fn get(&self, url: &str, _accept: &str) -> Result<TransportResponse, ClientError> {
+            self.requests.lock().unwrap().push(url.to_string());
+            Ok(self
+                .responses
+                .get(url)
+                .cloned()
+                .unwrap_or(TransportResponse {
+                    status: 404,
+                    content_type: None,
+                    cache_control: None,
+                    body: Vec::new(),
+                }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_many() {
+        let uri = |n: u8| {
+            StatusListUri::parse(&format!("https://example.com/statuslists/{}", n)).unwrap()
+        };
+        let transport = RoutingTransport::default()
+            .with(&uri(1), signed_token(&uri(1)))
+            .with(&uri(2), signed_token_at(&uri(2), 1000, &TestKey(2)));
+        let cache = StatusListCache::new(StatusListClient::with_transport(&transport));
+
+        let references = [
+            StatusListReference::new(1, uri(1)),
+            StatusListReference::new(0, uri(3)),
+            StatusListReference::new(0, uri(1)),
+            StatusListReference::new(1, uri(2)),
+            StatusListReference::new(99, uri(1)),
+            StatusListReference::new(1, uri(3)),
+        ];
+        let results = cache.check_many(&references, &TestKey(1), 1500).await;
+
+        assert!(matches!(results[0], Ok(StatusType::Invalid)));
+        assert!(matches!(results[1], Err(ClientError::HttpStatus(404))));
+        assert!(matches!(results[2], Ok(StatusType::Valid)));
+        assert!(matches!(
+            results[3],
+            Err(ClientError::TokenError(TokenError::SignatureError(_)))
+        ));
+        assert!(matches!(results[4], Err(ClientError::DecoderError(_))));
+        assert!(matches!(results[5], Err(ClientError::HttpStatus(404))));
+
+        // One request per distinct list.
+        let mut requests = transport.requests.lock().unwrap().clone();
+        requests.sort();
+        assert_eq!(
+            requests,
+            [uri(1), uri(2), uri(3)].map(|uri| uri.to_string()).to_vec()
+        );
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_known_len() {
+        let uri = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        let transport = RoutingTransport::default().with(&uri, signed_token(&uri));
+        let cache = StatusListCache::new(StatusListClient::with_transport(&transport))
+            .with_known_len(&uri, 2)
+            .with_status_cache(8);
+
+        assert_eq!(
+            cache.get_status(&uri, 1, &TestKey(1), 1500).await.unwrap(),
+            StatusType::Invalid
+        );
+        // Index 5 is padding in the list's only byte.
+        assert!(matches!(
+            cache.get_status(&uri, 5, &TestKey(1), 1500).await,
+            Err(ClientError::DecoderError(DecoderError::IndexNotPresent(5)))
+        ));
+        let references = [
+            StatusListReference::new(0, uri.clone()),
+            StatusListReference::new(2, uri.clone()),
+        ];
+        assert!(matches!(
+            cache.check_many(&references, &TestKey(1), 1500).await[..],
+            [
+                Ok(StatusType::Valid),
+                Err(ClientError::DecoderError(DecoderError::IndexNotPresent(2)))
+            ]
+        ));
+        assert_eq!(transport.requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_verifies_replay_and_bits() {
+        let uri = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        let newer =
+            RoutingTransport::default().with(&uri, signed_token_at(&uri, 1000, &TestKey(1)));
+        let older = RoutingTransport::default().with(&uri, signed_token_at(&uri, 900, &TestKey(1)));
+        let replay_window = ReplayWindow::new();
+
+        let cache = StatusListCache::new(StatusListClient::with_transport(&newer))
+            .with_replay_window(replay_window.clone());
+        assert!(cache.get(&uri, &TestKey(1), 1500).await.is_ok());
+        assert_eq!(replay_window.highest_iat(&uri), Some(1000));
+
+        let cache = StatusListCache::new(StatusListClient::with_transport(&older))
+            .with_replay_window(replay_window);
+        assert!(matches!(
+            cache.get(&uri, &TestKey(1), 1500).await,
+            Err(ClientError::TokenError(TokenError::Rollback(900)))
+        ));
+
+        let cache = StatusListCache::new(StatusListClient::with_transport(&newer))
+            .with_expected_bits(BitsPerStatus::TwoBit);
+        assert!(matches!(
+            cache.get(&uri, &TestKey(1), 1500).await,
+            Err(ClientError::TokenError(TokenError::UnexpectedBits(2, 1)))
+        ));
+        assert!(cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_content_negotiation() {
+        let uri = |n: u8| {
+            StatusListUri::parse(&format!("https://example.com/statuslists/{}", n)).unwrap()
+        };
+        let status_list =
+            StatusListBuilder::from_vec(vec![StatusType::Valid, StatusType::Invalid], 1)
+                .unwrap()
+                .build()
+                .unwrap();
+        let jwt = JwtStatusListToken::new(uri(1), 1000, status_list)
+            .with_exp(2000)
+            .sign(&TestKey(1))
+            .unwrap();
+        let transport = RoutingTransport::default()
+            .with_type(&uri(1), JWT_MEDIA_TYPE, jwt.clone().into_bytes())
+            .with_type(
+                &uri(2),
+                "Application/StatusList+CWT; charset=binary",
+                signed_token(&uri(2)),
+            )
+            .with_type(&uri(3), "application/json", b"{}".to_vec())
+            .with_type(&uri(4), CWT_TYPE, jwt.clone().into_bytes());
+        let client = StatusListClient::with_transport(&transport);
+
+        let token = client.fetch_token(&uri(1)).await.unwrap();
+        assert_eq!(token, FetchedToken::Jwt(jwt));
+        assert_eq!(token.media_type(), JWT_MEDIA_TYPE);
+        let decoder = client.fetch(&uri(1), &TestKey(1), 1500).await.unwrap();
+        assert_eq!(decoder.get_status(1).unwrap(), StatusType::Invalid);
+
+        assert!(matches!(
+            client.fetch_token(&uri(2)).await,
+            Ok(FetchedToken::Cwt(_))
+        ));
+        match client.fetch(&uri(3), &TestKey(1), 1500).await {
+            Err(ClientError::UnexpectedContentType(content_type)) => {
+                assert_eq!(content_type, "application/json")
+            }
+            _ => panic!("Expected UnexpectedContentType"),
+        }
+        // A JWT labelled as a CWT is parsed as the CWT it claims to be.
+        assert!(matches!(
+            client.fetch(&uri(4), &TestKey(1), 1500).await,
+            Err(ClientError::TokenError(_))
+        ));
+
+        let cache = StatusListCache::new(client);
+        let references = [
+            StatusListReference::new(1, uri(1)),
+            StatusListReference::new(1, uri(2)),
+        ];
+        assert!(matches!(
+            cache.check_many(&references, &TestKey(1), 1500).await[..],
+            [Ok(StatusType::Invalid), Ok(StatusType::Invalid)]
+        ));
+    }
+
+    #[test]
+    fn test_status_lru() {
+        let mut lru = StatusLru::new(2);
+        lru.insert("a", 0, StatusType::Valid, 100);
+        lru.insert("a", 1, StatusType::Invalid, 100);
+        assert_eq!(lru.get("a", 0, 50), Some(StatusType::Valid));
+
+        // ("a", 1) is now the least recently used.
+        lru.insert("b", 0, StatusType::Suspended, 60);
+        assert_eq!(lru.get("a", 1, 50), None);
+        assert_eq!(lru.get("b", 0, 59), Some(StatusType::Suspended));
+        assert_eq!(lru.get("b", 0, 60), None);
+        assert_eq!(lru.entries.len(), 1);
+
+        lru.insert("b", 1, StatusType::Valid, 100);
+        lru.remove_list("a");
+        assert_eq!(lru.get("a", 0, 50), None);
+        assert_eq!(lru.get("b", 1, 50), Some(StatusType::Valid));
+        assert_eq!(lru.entries.len(), lru.recency.len());
+
+        let mut disabled = StatusLru::new(0);
+        disabled.insert("a", 0, StatusType::Valid, 100);
+        assert_eq!(disabled.get("a", 0, 50), None);
+    }
+
+    #[tokio::test]
+    async fn test_status_cache() {
+        let uri = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        let transport = RoutingTransport::default().with(&uri, signed_token(&uri));
+        let cache =
+            StatusListCache::new(StatusListClient::with_transport(&transport)).with_status_cache(8);
+
+        assert_eq!(
+            cache.get_status(&uri, 1, &TestKey(1), 1500).await.unwrap(),
+            StatusType::Invalid
+        );
+        // Results outlive the list entry, but not the list's expiry.
+        cache.entries.lock().unwrap().clear();
+        assert_eq!(
+            cache.get_status(&uri, 1, &TestKey(1), 1999).await.unwrap(),
+            StatusType::Invalid
+        );
+        let references = [StatusListReference::new(1, uri.clone())];
+        assert!(matches!(
+            cache.check_many(&references, &TestKey(1), 1999).await[..],
+            [Ok(StatusType::Invalid)]
+        ));
+        assert_eq!(transport.requests.lock().unwrap().len(), 1);
+
+        assert!(matches!(
+            cache.get_status(&uri, 1, &TestKey(1), 2000).await,
+            Err(ClientError::TokenError(TokenError::Expired(2000)))
+        ));
+        assert_eq!(transport.requests.lock().unwrap().len(), 2);
+
+        cache.get_status(&uri, 0, &TestKey(1), 1500).await.unwrap();
+        cache.invalidate(&uri);
+        assert!(cache
+            .results
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .entries
+            .is_empty());
+    }
+
+    #[cfg(feature = "changelog")]
+    #[tokio::test]
+    async fn test_delta_sync() {
+        let uri = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        let status_list =
+            StatusListBuilder::from_vec(vec![StatusType::Valid, StatusType::Invalid], 1)
+                .unwrap()
+                .build()
+                .unwrap();
+        let token = CwtStatusListToken::new(uri.clone(), 1000, status_list)
+            .with_exp(2000)
+            .with_ttl(100)
+            .sign(&TestKey(1))
+            .unwrap();
+        let changelog = |from, to| {
+            let mut changelog =
+                StatusListChangelog::new(uri.clone(), BitsPerStatus::OneBit, from, to);
+            changelog.record(0, StatusType::Invalid);
+            TransportResponse {
+                status: 200,
+                content_type: Some(CHANGELOG_CONTENT_TYPE.to_string()),
+                cache_control: None,
+                body: changelog.sign(&TestKey(1)).unwrap(),
+            }
+        };
+        let mut transport = RoutingTransport::default().with(&uri, token);
+        transport
+            .responses
+            .insert(format!("{}?since=1000", uri), changelog(1000, 1100));
+        transport
+            .responses
+            .insert(format!("{}?since=1100", uri), changelog(900, 1200));
+        let cache =
+            StatusListCache::new(StatusListClient::with_transport(&transport)).with_delta_sync();
+
+        assert_eq!(
+            cache.get_status(&uri, 0, &TestKey(1), 1050).await.unwrap(),
+            StatusType::Valid
+        );
+        // Stale at 1100; the changelog moves the list to version 1100, fresh
+        // until 1200.
+        assert_eq!(
+            cache.get_status(&uri, 0, &TestKey(1), 1150).await.unwrap(),
+            StatusType::Invalid
+        );
+        assert_eq!(
+            cache.get_status(&uri, 1, &TestKey(1), 1199).await.unwrap(),
+            StatusType::Invalid
+        );
+        // A changelog that does not start at the cached version is ignored
+        // in favor of a full fetch.
+        assert_eq!(
+            cache.get_status(&uri, 0, &TestKey(1), 1250).await.unwrap(),
+            StatusType::Valid
+        );
+        assert_eq!(
+            *transport.requests.lock().unwrap(),
+            [
+                uri.to_string(),
+                format!("{}?since=1000", uri),
+                format!("{}?since=1100", uri),
+                uri.to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retired_list() {
+        let uri = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        let mut transport = RoutingTransport::default();
+        transport.responses.insert(
+            uri.to_string(),
+            TransportResponse {
+                status: 410,
+                content_type: None,
+                cache_control: None,
+                body: Vec::new(),
+            },
+        );
+        let cache = StatusListCache::new(StatusListClient::with_transport(&transport));
+
+        for _ in 0..2 {
+            match cache.get_status(&uri, 1, &TestKey(1), 1500).await {
+                Err(ClientError::Retired(retired)) => assert_eq!(retired, uri.to_string()),
+                other => panic!("Expected Retired, got {:?}", other),
+            }
+        }
+        assert_eq!(transport.requests.lock().unwrap().len(), 1);
+        assert!(cache.is_empty());
+
+        cache.invalidate(&uri);
+        assert!(matches!(
+            cache.get(&uri, &TestKey(1), 1500).await,
+            Err(ClientError::Retired(_))
+        ));
+        assert_eq!(transport.requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_http_error() {
+        let (listener, uri) = listen();
+        let handle = serve_once(listener, "404 Not Found", Vec::new(), false);
+
+        match StatusListClient::new().fetch_token(&uri).await {
+            Err(ClientError::HttpStatus(404)) => (),
+            other => panic!("Expected HttpStatus error, got {:?}", other),
+        }
+        handle.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_rejects_expired_token() {
+        let (listener, uri) = listen();
+        let handle = serve_once(listener, "200 OK", signed_token(&uri), false);
+
+        match StatusListClient::new().fetch(&uri, &TestKey(1), 2500).await {
+            Err(ClientError::TokenError(TokenError::Expired(2000))) => (),
+            _ => panic!("Expected expired token error"),
+        }
+        handle.join().unwrap();
+    }
+}
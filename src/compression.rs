@@ -0,0 +1,266 @@
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use std::fmt;
+use std::io::{Read, Write};
+
+/// The largest decompressed list accepted by default: 128 MiB, a billion
+/// one-bit statuses.
+pub const DEFAULT_MAX_DECOMPRESSED_LEN: usize = 128 * 1024 * 1024;
+
+pub trait Compressor: fmt::Debug + Send + Sync {
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>, String>;
+}
+
+pub trait Decompressor: fmt::Debug + Send + Sync {
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, String>;
+
+    /// Decompresses into `out`, replacing its contents but keeping its allocation.
+    fn decompress_into(&self, data: &[u8], out: &mut Vec<u8>) -> Result<(), String> {
+        out.clear();
+        out.extend_from_slice(&self.decompress(data)?);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ZlibCompression {
+    level: u32,
+    max_decompressed_len: usize,
+}
+
+impl ZlibCompression {
+    pub fn new(level: u32) -> Self {
+        Self {
+            level: level.min(9),
+            max_decompressed_len: DEFAULT_MAX_DECOMPRESSED_LEN,
+        }
+    }
+
+    /// Fails decompression of streams that inflate past `len` bytes.
+    pub fn with_max_decompressed_len(mut self, len: usize) -> Self {
+        self.max_decompressed_len = len;
+        self
+    }
+}
+
+impl Default for ZlibCompression {
+    fn default() -> Self {
+        Self::new(Compression::best().level())
+    }
+}
+
+impl Compressor for ZlibCompression {
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::new(self.level));
+        encoder.write_all(data).map_err(|e| e.to_string())?;
+        encoder.finish().map_err(|e| e.to_string())
+    }
+}
+
+impl Decompressor for ZlibCompression {
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let mut raw_bytes = Vec::new();
+        self.decompress_into(data, &mut raw_bytes)?;
+        Ok(raw_bytes)
+    }
+
+    fn decompress_into(&self, data: &[u8], out: &mut Vec<u8>) -> Result<(), String> {
+        let max = self.max_decompressed_len;
+        out.clear();
+        // One byte past the limit is enough to tell that the stream is too long.
+        ZlibDecoder::new(data)
+            .take((max as u64).saturating_add(1))
+            .read_to_end(out)
+            .map_err(|e| e.to_string())?;
+        if out.len() > max {
+            out.clear();
+            return Err(format!("decompressed list exceeds {} bytes", max));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "libdeflater")]
+#[derive(Debug, Clone, Copy)]
+pub struct LibdeflateCompression {
+    level: i32,
+    max_decompressed_len: usize,
+}
+
+#[cfg(feature = "libdeflater")]
+impl LibdeflateCompression {
+    pub fn new(level: i32) -> Self {
+        Self {
+            level: level.clamp(0, 12),
+            max_decompressed_len: DEFAULT_MAX_DECOMPRESSED_LEN,
+        }
+    }
+
+    /// Fails decompression of streams that inflate past `len` bytes.
+    pub fn with_max_decompressed_len(mut self, len: usize) -> Self {
+        self.max_decompressed_len = len;
+        self
+    }
+}
+
+#[cfg(feature = "libdeflater")]
+impl Default for LibdeflateCompression {
+    fn default() -> Self {
+        Self::new(libdeflater::CompressionLvl::best().into())
+    }
+}
+
+#[cfg(feature = "libdeflater")]
+impl Compressor for LibdeflateCompression {
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let level = libdeflater::CompressionLvl::new(self.level).map_err(|e| format!("{:?}", e))?;
+        let mut compressor = libdeflater::Compressor::new(level);
+        let mut compressed = vec![0u8; compressor.zlib_compress_bound(data.len())];
+        let len = compressor
+            .zlib_compress(data, &mut compressed)
+            .map_err(|e| e.to_string())?;
+        compressed.truncate(len);
+        Ok(compressed)
+    }
+}
+
+#[cfg(feature = "libdeflater")]
+impl Decompressor for LibdeflateCompression {
+    // libdeflate needs the whole output buffer up front, so grow it until the
+    // stream fits or the limit is reached.
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let mut raw_bytes = Vec::new();
+        self.decompress_into(data, &mut raw_bytes)?;
+        Ok(raw_bytes)
+    }
+
+    fn decompress_into(&self, data: &[u8], out: &mut Vec<u8>) -> Result<(), String> {
+        let mut decompressor = libdeflater::Decompressor::new();
+        let max = self.max_decompressed_len;
+        let initial = data.len().saturating_mul(8).max(1024).max(out.capacity());
+        out.clear();
+        out.resize(initial.min(max), 0);
+        loop {
+            match decompressor.zlib_decompress(data, out) {
+                Ok(len) => {
+                    out.truncate(len);
+                    return Ok(());
+                }
+                Err(libdeflater::DecompressionError::InsufficientSpace) if out.len() < max => {
+                    let len = out.len().saturating_mul(2).clamp(1, max);
+                    out.resize(len, 0);
+                }
+                Err(libdeflater::DecompressionError::InsufficientSpace) => {
+                    out.clear();
+                    return Err(format!("decompressed list exceeds {} bytes", max));
+                }
+                Err(e) => {
+                    out.clear();
+                    return Err(e.to_string());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zlib_round_trip() {
+        let zlib = ZlibCompression::default();
+        let compressed = zlib.compress(&[0xB9, 0xA3]).unwrap();
+
+        assert_eq!(
+            compressed,
+            vec![0x78, 0xda, 0xdb, 0xb9, 0x18, 0x00, 0x02, 0x17, 0x01, 0x5d]
+        );
+        assert_eq!(zlib.decompress(&compressed).unwrap(), vec![0xB9, 0xA3]);
+    }
+
+    #[test]
+    fn test_decompress_into_reuses_buffer() {
+        let zlib = ZlibCompression::default();
+        let compressed = zlib.compress(&[0xB9, 0xA3]).unwrap();
+        let mut buffer = Vec::with_capacity(4096);
+        buffer.extend_from_slice(b"stale");
+
+        zlib.decompress_into(&compressed, &mut buffer).unwrap();
+        assert_eq!(buffer, vec![0xB9, 0xA3]);
+        assert!(buffer.capacity() >= 4096);
+    }
+
+    #[test]
+    fn test_zlib_levels() {
+        let data = vec![0u8; 1024];
+        let fast = ZlibCompression::new(1).compress(&data).unwrap();
+        let clamped = ZlibCompression::new(42).compress(&data).unwrap();
+
+        assert_eq!(ZlibCompression::default().decompress(&fast).unwrap(), data);
+        assert_eq!(clamped, ZlibCompression::new(9).compress(&data).unwrap());
+    }
+
+    #[test]
+    fn test_zlib_max_decompressed_len() {
+        let data = vec![0u8; 100_000];
+        let compressed = ZlibCompression::default().compress(&data).unwrap();
+
+        let exact = ZlibCompression::default().with_max_decompressed_len(data.len());
+        assert_eq!(exact.decompress(&compressed).unwrap(), data);
+
+        let mut buffer = Vec::new();
+        let capped = ZlibCompression::default().with_max_decompressed_len(data.len() - 1);
+        assert_eq!(
+            capped
+                .decompress_into(&compressed, &mut buffer)
+                .unwrap_err(),
+            "decompressed list exceeds 99999 bytes"
+        );
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_zlib_decompress_error() {
+        assert!(ZlibCompression::default()
+            .decompress(b"not zlib data")
+            .is_err());
+    }
+
+    #[cfg(feature = "libdeflater")]
+    #[test]
+    fn test_libdeflate_matches_zlib() {
+        let data: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        let libdeflate = LibdeflateCompression::default();
+        let zlib = ZlibCompression::default();
+
+        let compressed = zlib.compress(&data).unwrap();
+        assert_eq!(libdeflate.decompress(&compressed).unwrap(), data);
+
+        let compressed = libdeflate.compress(&data).unwrap();
+        assert_eq!(zlib.decompress(&compressed).unwrap(), data);
+
+        assert!(libdeflate.decompress(b"not zlib data").is_err());
+    }
+
+    #[cfg(feature = "libdeflater")]
+    #[test]
+    fn test_libdeflate_max_decompressed_len() {
+        let data = vec![0u8; 100_000];
+        let compressed = ZlibCompression::default().compress(&data).unwrap();
+
+        let exact = LibdeflateCompression::default().with_max_decompressed_len(data.len());
+        assert_eq!(exact.decompress(&compressed).unwrap(), data);
+
+        let mut buffer = Vec::new();
+        let capped = LibdeflateCompression::default().with_max_decompressed_len(data.len() - 1);
+        assert_eq!(
+            capped
+                .decompress_into(&compressed, &mut buffer)
+                .unwrap_err(),
+            "decompressed list exceeds 99999 bytes"
+        );
+        assert!(buffer.is_empty());
+    }
+}
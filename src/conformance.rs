@@ -0,0 +1,365 @@
+use std::fmt;
+
+#[cfg(feature = "cwt")]
+use coset::cbor::value::Value;
+#[cfg(feature = "cwt")]
+use coset::{CoseSign1, Label, TaggedCborSerializable};
+
+use crate::bitops;
+use crate::compression::{Decompressor, ZlibCompression};
+#[cfg(feature = "cwt")]
+use crate::signing::TYP_HEADER;
+#[cfg(feature = "cwt")]
+use crate::token::{CwtStatusListToken, CWT_TYPE, IAT_CLAIM, STATUS_LIST_CLAIM, SUB_CLAIM};
+use crate::types::{BitsPerStatus, StatusList};
+
+/// A requirement of the specification that an artifact does not meet.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    Malformed(String),
+    InvalidBits(i128),
+    NotZlib,
+    DecompressionFailed(String),
+    NonZeroPadding(usize),
+    Base64Padding,
+    InvalidBase64(String),
+    MissingClaim(String),
+    InvalidClaim(String),
+    InvalidTyp(Option<String>),
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::Malformed(msg) => write!(f, "Artifact cannot be parsed: {}", msg),
+            Violation::InvalidBits(bits) => {
+                write!(f, "bits is {}, must be 1, 2, 4, or 8", bits)
+            }
+            Violation::NotZlib => write!(f, "lst is not a ZLIB (RFC 1950) stream"),
+            Violation::DecompressionFailed(msg) => {
+                write!(f, "lst does not decompress: {}", msg)
+            }
+            Violation::NonZeroPadding(len) => {
+                write!(f, "bits after the last of {} statuses are not zero", len)
+            }
+            Violation::Base64Padding => write!(f, "lst must be base64url without padding"),
+            Violation::InvalidBase64(msg) => write!(f, "lst is not valid base64url: {}", msg),
+            Violation::MissingClaim(claim) => write!(f, "Required claim {} is missing", claim),
+            Violation::InvalidClaim(claim) => write!(f, "Claim {} has an invalid value", claim),
+            Violation::InvalidTyp(typ) => match typ {
+                Some(typ) => write!(f, "typ header is {}", typ),
+                None => write!(f, "typ header is missing"),
+            },
+        }
+    }
+}
+
+// Checks a bit width and compressed list. With a known `len`, the bits after
+// the last status must be zero.
+fn check_list(bits: i128, lst: &[u8], len: Option<usize>, violations: &mut Vec<Violation>) {
+    let bits = match u8::try_from(bits).ok().map(BitsPerStatus::try_from) {
+        Some(Ok(bits)) => Some(bits),
+        _ => {
+            violations.push(Violation::InvalidBits(bits));
+            None
+        }
+    };
+
+    // RFC 1950 header: deflate method, and CMF/FLG must be a multiple of 31
+    if !matches!(lst, [cmf, flg, ..]
+        if cmf & 0x0F == 8 && (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0)
+    {
+        violations.push(Violation::NotZlib);
+        return;
+    }
+
+    let raw = match ZlibCompression::default().decompress(lst) {
+        Ok(raw) => raw,
+        Err(e) => {
+            violations.push(Violation::DecompressionFailed(e));
+            return;
+        }
+    };
+
+    if let (Some(bits), Some(len)) = (bits, len) {
+        let capacity = raw.len() * bitops::statuses_per_byte(bits);
+        if len > capacity {
+            violations.push(Violation::DecompressionFailed(format!(
+                "{} statuses expected, list holds {}",
+                len, capacity
+            )));
+            return;
+        }
+        let padding = (len..capacity)
+            .find(|&index| bitops::decode_status(&raw, index, bits).is_ok_and(|value| value != 0));
+        if padding.is_some() {
+            violations.push(Violation::NonZeroPadding(len));
+        }
+    }
+}
+
+fn into_result(violations: Vec<Violation>) -> Result<(), Vec<Violation>> {
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+impl StatusList {
+    /// Checks the list against the requirements of the specification,
+    /// returning every violation found.
+    pub fn is_conformant(&self) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        check_list(self.bits().into(), self.lst(), None, &mut violations);
+        into_result(violations)
+    }
+
+    /// Like `is_conformant`, additionally requiring every bit after the first
+    /// `len` statuses to be zero.
+    pub fn is_conformant_with_len(&self, len: usize) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        check_list(self.bits().into(), self.lst(), Some(len), &mut violations);
+        into_result(violations)
+    }
+
+    /// Checks a `status_list` JSON object as received, before any parsing
+    /// that would reject it on the first problem.
+    #[cfg(feature = "serde")]
+    pub fn check_json(json: &str) -> Result<(), Vec<Violation>> {
+        let object = match serde_json::from_str::<serde_json::Value>(json) {
+            Ok(serde_json::Value::Object(object)) => object,
+            Ok(_) => return Err(vec![Violation::Malformed("not a JSON object".to_string())]),
+            Err(e) => return Err(vec![Violation::Malformed(e.to_string())]),
+        };
+
+        let mut violations = Vec::new();
+        let bits = match object.get("bits") {
+            None => {
+                violations.push(Violation::MissingClaim("bits".to_string()));
+                None
+            }
+            Some(bits) => match bits.as_i64() {
+                Some(bits) => Some(i128::from(bits)),
+                None => {
+                    violations.push(Violation::InvalidClaim("bits".to_string()));
+                    None
+                }
+            },
+        };
+        let lst = match object.get("lst") {
+            None => {
+                violations.push(Violation::MissingClaim("lst".to_string()));
+                None
+            }
+            Some(serde_json::Value::String(lst)) => {
+                if lst.contains('=') {
+                    violations.push(Violation::Base64Padding);
+                }
+                match base64url::decode(lst.trim_end_matches('=')) {
+                    Ok(lst) => Some(lst),
+                    Err(e) => {
+                        violations.push(Violation::InvalidBase64(e.to_string()));
+                        None
+                    }
+                }
+            }
+            Some(_) => {
+                violations.push(Violation::InvalidClaim("lst".to_string()));
+                None
+            }
+        };
+
+        if let (Some(bits), Some(lst)) = (bits, lst) {
+            check_list(bits, &lst, None, &mut violations);
+        }
+        into_result(violations)
+    }
+}
+
+#[cfg(feature = "cwt")]
+impl CwtStatusListToken {
+    /// Checks a signed Status List Token's header and claims without verifying
+    /// its signature.
+    pub fn check_conformance(bytes: &[u8]) -> Result<(), Vec<Violation>> {
+        let sign1 = CoseSign1::from_tagged_slice(bytes)
+            .map_err(|e| vec![Violation::Malformed(e.to_string())])?;
+
+        let mut violations = Vec::new();
+        let typ = sign1
+            .protected
+            .header
+            .rest
+            .iter()
+            .find(|(label, _)| *label == Label::Int(TYP_HEADER))
+            .map(|(_, value)| value);
+        match typ {
+            Some(Value::Text(typ)) if typ == CWT_TYPE => (),
+            Some(Value::Text(typ)) => violations.push(Violation::InvalidTyp(Some(typ.clone()))),
+            Some(_) => violations.push(Violation::InvalidTyp(Some("non-text value".to_string()))),
+            None => violations.push(Violation::InvalidTyp(None)),
+        }
+
+        let claims = match sign1.payload.as_deref().map(ciborium::de::from_reader) {
+            Some(Ok(Value::Map(claims))) => claims,
+            Some(Ok(_)) => {
+                violations.push(Violation::Malformed("claims set is not a map".to_string()));
+                return Err(violations);
+            }
+            Some(Err(e)) => {
+                violations.push(Violation::Malformed(e.to_string()));
+                return Err(violations);
+            }
+            None => {
+                violations.push(Violation::Malformed("missing payload".to_string()));
+                return Err(violations);
+            }
+        };
+        let claim = |key: i64| {
+            claims
+                .iter()
+                .find(|(k, _)| k.as_integer() == Some(key.into()))
+                .map(|(_, value)| value)
+        };
+
+        match claim(SUB_CLAIM) {
+            Some(Value::Text(_)) => (),
+            Some(_) => violations.push(Violation::InvalidClaim("sub".to_string())),
+            None => violations.push(Violation::MissingClaim("sub".to_string())),
+        }
+        match claim(IAT_CLAIM) {
+            Some(Value::Integer(iat)) if u64::try_from(*iat).is_ok() => (),
+            Some(_) => violations.push(Violation::InvalidClaim("iat".to_string())),
+            None => violations.push(Violation::MissingClaim("iat".to_string())),
+        }
+        match claim(STATUS_LIST_CLAIM) {
+            Some(Value::Map(status_list)) => {
+                let field = |name: &str| {
+                    status_list
+                        .iter()
+                        .find(|(k, _)| k.as_text() == Some(name))
+                        .map(|(_, value)| value)
+                };
+                match (field("bits"), field("lst")) {
+                    (Some(Value::Integer(bits)), Some(Value::Bytes(lst))) => {
+                        check_list(i128::from(*bits), lst, None, &mut violations)
+                    }
+                    (None, _) => violations.push(Violation::MissingClaim("bits".to_string())),
+                    (_, None) => violations.push(Violation::MissingClaim("lst".to_string())),
+                    _ => violations.push(Violation::InvalidClaim("status_list".to_string())),
+                }
+            }
+            Some(_) => violations.push(Violation::InvalidClaim("status_list".to_string())),
+            None => violations.push(Violation::MissingClaim("status_list".to_string())),
+        }
+        into_result(violations)
+    }
+}
+
+#[cfg(all(test, feature = "encoder"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conformant_list() {
+        let status_list = StatusList::from_packed_bytes(1, &[0b0000_0101]).unwrap();
+        assert!(status_list.is_conformant().is_ok());
+        assert!(status_list.is_conformant_with_len(3).is_ok());
+        assert_eq!(
+            status_list.is_conformant_with_len(2),
+            Err(vec![Violation::NonZeroPadding(2)])
+        );
+    }
+
+    #[test]
+    fn test_truncated_stream() {
+        let (bits, lst, _) = StatusList::from_packed_bytes(2, &[0xAB; 64])
+            .unwrap()
+            .into_parts();
+        let truncated = StatusList::from_compressed(bits, lst[..lst.len() / 2].to_vec()).unwrap();
+        match truncated.is_conformant() {
+            Err(violations) => assert!(matches!(
+                violations[..],
+                [Violation::DecompressionFailed(_)]
+            )),
+            Ok(()) => panic!("Expected DecompressionFailed"),
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_check_json() {
+        assert!(StatusList::check_json(r#"{"bits":1,"lst":"eNrbuRgAAhcBXQ"}"#).is_ok());
+
+        assert_eq!(
+            StatusList::check_json(r#"{"bits":3,"lst":"AAAA="}"#),
+            Err(vec![
+                Violation::Base64Padding,
+                Violation::InvalidBits(3),
+                Violation::NotZlib,
+            ])
+        );
+        assert_eq!(
+            StatusList::check_json(r#"{"lst":7}"#),
+            Err(vec![
+                Violation::MissingClaim("bits".to_string()),
+                Violation::InvalidClaim("lst".to_string()),
+            ])
+        );
+        assert_eq!(
+            Violation::InvalidBits(3).to_string(),
+            "bits is 3, must be 1, 2, 4, or 8"
+        );
+    }
+
+    #[cfg(feature = "cwt")]
+    #[test]
+    fn test_check_cwt() {
+        use crate::signing::Signer;
+        use crate::uri::StatusListUri;
+        use coset::{iana, CoseSign1Builder, HeaderBuilder};
+
+        struct NoopSigner;
+
+        impl Signer for NoopSigner {
+            fn algorithm(&self) -> iana::Algorithm {
+                iana::Algorithm::ES256
+            }
+
+            fn sign(&self, _data: &[u8]) -> Result<Vec<u8>, String> {
+                Ok(vec![0; 64])
+            }
+        }
+
+        let token = CwtStatusListToken::new(
+            StatusListUri::parse("https://example.com/statuslists/1").unwrap(),
+            1686920170,
+            StatusList::from_packed_bytes(1, &[0x00]).unwrap(),
+        )
+        .sign(&NoopSigner)
+        .unwrap();
+        assert!(CwtStatusListToken::check_conformance(&token).is_ok());
+
+        let mut payload = Vec::new();
+        ciborium::ser::into_writer(
+            &Value::Map(vec![(Value::Integer(6.into()), Value::Text("now".into()))]),
+            &mut payload,
+        )
+        .unwrap();
+        let bare = CoseSign1Builder::new()
+            .protected(HeaderBuilder::new().build())
+            .payload(payload)
+            .build()
+            .to_tagged_vec()
+            .unwrap();
+        assert_eq!(
+            CwtStatusListToken::check_conformance(&bare),
+            Err(vec![
+                Violation::InvalidTyp(None),
+                Violation::MissingClaim("sub".to_string()),
+                Violation::InvalidClaim("iat".to_string()),
+                Violation::MissingClaim("status_list".to_string()),
+            ])
+        );
+    }
+}
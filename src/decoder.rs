@@ -1,97 +1,335 @@
+use crate::bitops;
+#[cfg(feature = "changelog")]
+use crate::changelog::StatusListChangelog;
+use crate::compression::Decompressor;
+#[cfg(feature = "libdeflater")]
+use crate::compression::LibdeflateCompression as DefaultDecompressor;
+#[cfg(not(feature = "libdeflater"))]
+use crate::compression::ZlibCompression as DefaultDecompressor;
+#[cfg(feature = "encoder")]
+use crate::encoder::StatusListEncoder;
+#[cfg(feature = "changelog")]
+use crate::error::ChangelogError;
 use crate::error::DecoderError;
-use crate::types::{StatusList, StatusType};
-use flate2::read::ZlibDecoder;
-use std::io::Read;
+use crate::registry::{NamedStatus, StatusRegistry};
+use crate::types::{BitsPerStatus, StatusList, StatusType};
+use std::ops::{Bound, Deref, RangeBounds};
+use std::sync::Arc;
 
+#[derive(Debug)]
 pub struct StatusListDecoder {
     raw_bytes: Vec<u8>,
-    bits_per_status: u8,
+    bits_per_status: BitsPerStatus,
+    known_len: Option<usize>,
+    registry: StatusRegistry,
 }
 
 impl StatusListDecoder {
     pub fn new(status_list: &StatusList) -> Result<Self, DecoderError> {
-        let mut decoder = ZlibDecoder::new(&status_list.lst[..]);
-        let mut raw_bytes = Vec::new();
-        decoder
-            .read_to_end(&mut raw_bytes)
-            .map_err(|e| DecoderError::DecompressionError(e.to_string()))?;
+        Self::with_decompressor(status_list, &DefaultDecompressor::default())
+    }
+
+    pub fn with_decompressor(
+        status_list: &StatusList,
+        decompressor: &impl Decompressor,
+    ) -> Result<Self, DecoderError> {
+        Self::with_buffer(status_list, decompressor, Vec::new())
+    }
+
+    /// Like `new`, but fails once the list inflates past `max_len` bytes
+    /// instead of at `DEFAULT_MAX_DECOMPRESSED_LEN`.
+    pub fn with_max_decompressed_len(
+        status_list: &StatusList,
+        max_len: usize,
+    ) -> Result<Self, DecoderError> {
+        let decompressor = DefaultDecompressor::default().with_max_decompressed_len(max_len);
+        Self::with_decompressor(status_list, &decompressor)
+    }
+
+    pub fn from_buffer(status_list: &StatusList, buffer: Vec<u8>) -> Result<Self, DecoderError> {
+        Self::with_buffer(status_list, &DefaultDecompressor::default(), buffer)
+    }
+
+    fn with_buffer(
+        status_list: &StatusList,
+        decompressor: &impl Decompressor,
+        mut buffer: Vec<u8>,
+    ) -> Result<Self, DecoderError> {
+        let bits_per_status = BitsPerStatus::try_from(status_list.bits)
+            .map_err(|_| DecoderError::InvalidBitsPerStatus(status_list.bits))?;
+
+        decompressor
+            .decompress_into(&status_list.lst, &mut buffer)
+            .map_err(DecoderError::DecompressionError)?;
 
         Ok(Self {
-            raw_bytes,
-            bits_per_status: status_list.bits,
+            raw_bytes: buffer,
+            bits_per_status,
+            known_len: None,
+            registry: StatusRegistry::default(),
         })
     }
 
-    pub fn get_status(&self, index: usize) -> Result<StatusType, DecoderError> {
-        let statuses_per_byte = 8 / self.bits_per_status as usize;
-        let byte_index = index / statuses_per_byte;
-        let position_in_byte = index % statuses_per_byte;
+    pub fn decode_into(status_list: &StatusList, buffer: &mut Vec<u8>) -> Result<(), DecoderError> {
+        BitsPerStatus::try_from(status_list.bits)
+            .map_err(|_| DecoderError::InvalidBitsPerStatus(status_list.bits))?;
+
+        DefaultDecompressor::default()
+            .decompress_into(&status_list.lst, buffer)
+            .map_err(DecoderError::DecompressionError)
+    }
+
+    pub fn into_raw_bytes(self) -> Vec<u8> {
+        self.raw_bytes
+    }
 
-        if byte_index >= self.raw_bytes.len() {
-            return Err(DecoderError::InvalidByteIndex(byte_index));
+    pub fn expect_bits(self, expected: u8) -> Result<Self, DecoderError> {
+        if self.bits_per_status as u8 != expected {
+            return Err(DecoderError::BitsMismatch(
+                expected,
+                self.bits_per_status as u8,
+            ));
         }
+        Ok(self)
+    }
 
-        let byte = self.raw_bytes[byte_index];
-
-        if self.bits_per_status == 8 {
-            StatusType::try_from(byte).map_err(|_| DecoderError::InvalidStatusType(byte))
-        } else {
-            let bit_shift = match self.bits_per_status {
-                1 => position_in_byte,
-                2 => match position_in_byte {
-                    0 => 0,
-                    1 => 2,
-                    2 => 4,
-                    3 => 6,
-                    _ => unreachable!(),
-                },
-                4 => {
-                    if position_in_byte == 0 {
-                        4
-                    } else {
-                        0
-                    }
-                }
-                _ => unreachable!(),
-            };
+    pub fn get_bits_per_status(&self) -> u8 {
+        self.bits_per_status as u8
+    }
+
+    pub(crate) fn bits(&self) -> BitsPerStatus {
+        self.bits_per_status
+    }
+
+    pub fn with_len(mut self, len: usize) -> Result<Self, DecoderError> {
+        let capacity = self.raw_bytes.len() * bitops::statuses_per_byte(self.bits_per_status);
+        if len > capacity {
+            return Err(DecoderError::StatusListCreationError(format!(
+                "length {} exceeds the {} statuses held by the list",
+                len, capacity
+            )));
+        }
+
+        self.known_len = Some(len);
+        Ok(self)
+    }
+
+    pub fn get_status(&self, index: usize) -> Result<StatusType, DecoderError> {
+        if let Some(len) = self.known_len {
+            if index >= len {
+                return Err(DecoderError::IndexNotPresent(index));
+            }
+        }
+
+        let bits = self.bits_per_status;
+        let value = bitops::decode_status(&self.raw_bytes, index, bits)
+            .map_err(|_| DecoderError::InvalidByteIndex(index / bitops::statuses_per_byte(bits)))?;
 
-            let mask = (1u8 << self.bits_per_status) - 1;
-            let value = (byte >> bit_shift) & mask;
+        Ok(StatusType::from(value))
+    }
+
+    pub fn with_registry(mut self, registry: StatusRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    pub fn get_named_status(&self, index: usize) -> Result<NamedStatus<'_>, DecoderError> {
+        Ok(self.registry.describe(self.get_status(index)?))
+    }
+
+    pub fn dump(&self, range: impl RangeBounds<usize>) -> String {
+        let statuses_per_byte = bitops::statuses_per_byte(self.bits_per_status);
+        let start = match range.start_bound() {
+            Bound::Included(&i) => i,
+            Bound::Excluded(&i) => i.saturating_add(1),
+            Bound::Unbounded => 0,
+        }
+        .min(self.len());
+        let end = match range.end_bound() {
+            Bound::Included(&i) => i.saturating_add(1),
+            Bound::Excluded(&i) => i,
+            Bound::Unbounded => self.len(),
+        }
+        .min(self.len());
 
-            StatusType::try_from(value).map_err(|_| DecoderError::InvalidStatusType(value))
+        let mut output = String::new();
+        if start >= end {
+            return output;
         }
+
+        for byte_index in start / statuses_per_byte..=(end - 1) / statuses_per_byte {
+            let byte = self.raw_bytes[byte_index];
+            let first_index = byte_index * statuses_per_byte;
+            let statuses: Vec<String> = (first_index..first_index + statuses_per_byte)
+                .filter(|index| (start..end).contains(index))
+                .map(|index| match self.get_status(index) {
+                    Ok(status) => format!("{}={:?}", index, status),
+                    Err(e) => format!("{}={}", index, e),
+                })
+                .collect();
+
+            output.push_str(&format!(
+                "{:08x}  {:02x}  {:08b}  {}\n",
+                byte_index,
+                byte,
+                byte,
+                statuses.join(" ")
+            ));
+        }
+        output
+    }
+
+    /// Returns a copy of this list with a verified changelog's entries
+    /// written over it.
+    #[cfg(feature = "changelog")]
+    pub fn apply_changelog(&self, changelog: &StatusListChangelog) -> Result<Self, ChangelogError> {
+        let mut raw_bytes = self.raw_bytes.clone();
+        changelog.apply_to(&mut raw_bytes, self.bits_per_status)?;
+        Ok(Self {
+            raw_bytes,
+            bits_per_status: self.bits_per_status,
+            known_len: self.known_len,
+            registry: self.registry.clone(),
+        })
     }
 
     pub fn get_raw_bytes(&self) -> &[u8] {
         &self.raw_bytes
     }
 
+    pub fn raw_byte_len(&self) -> usize {
+        self.raw_bytes.len()
+    }
+
+    /// Copies all raw bytes into the front of `out`, returning how many were written.
+    pub fn copy_raw_bytes_into(&self, out: &mut [u8]) -> Result<usize, DecoderError> {
+        let len = self.raw_bytes.len();
+        if out.len() < len {
+            return Err(DecoderError::BufferTooSmall(len, out.len()));
+        }
+
+        out[..len].copy_from_slice(&self.raw_bytes);
+        Ok(len)
+    }
+
+    /// Copies the raw bytes starting at `offset` into `out`, as many as fit,
+    /// and returns how many were written (0 once `offset` is past the end).
+    pub fn copy_raw_chunk_into(&self, offset: usize, out: &mut [u8]) -> usize {
+        let rest = self.raw_bytes.get(offset..).unwrap_or_default();
+        let len = rest.len().min(out.len());
+
+        out[..len].copy_from_slice(&rest[..len]);
+        len
+    }
+
     pub fn len(&self) -> usize {
-        self.raw_bytes.len() * (8 / self.bits_per_status as usize)
+        self.known_len
+            .unwrap_or(self.raw_bytes.len() * bitops::statuses_per_byte(self.bits_per_status))
     }
 
     pub fn is_empty(&self) -> bool {
-        self.raw_bytes.is_empty()
+        self.len() == 0
+    }
+
+    /// Recompresses the first `len()` statuses at `bits` per status,
+    /// repacking them when `bits` differs from the decoder's own width.
+    /// Fails if a status does not fit in the new width.
+    #[cfg(feature = "encoder")]
+    pub fn to_status_list(&self, bits: u8) -> Result<StatusList, DecoderError> {
+        let target = BitsPerStatus::try_from(bits)
+            .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?;
+
+        let encoder = StatusListEncoder::new(bits);
+        if target == self.bits_per_status && self.known_len.is_none() {
+            return encoder
+                .finalize(&self.raw_bytes)
+                .map_err(|e| DecoderError::StatusListCreationError(e.to_string()));
+        }
+
+        let len = self.len();
+        let mut bytes = vec![0u8; bitops::packed_len(len, target)];
+        for index in 0..len {
+            let value = bitops::decode_status(&self.raw_bytes, index, self.bits_per_status)
+                .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?;
+            bitops::encode_status(&mut bytes, index, target, value)
+                .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?;
+        }
+        encoder
+            .finalize(&bytes)
+            .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))
     }
 
-    pub fn new_from_base64(base64_str: &str) -> Result<Self, DecoderError> {
+    pub fn new_from_base64(base64_str: &str, bits: u8) -> Result<Self, DecoderError> {
         let compressed =
             base64url::decode(base64_str).map_err(|e| DecoderError::Base64Error(e.to_string()))?;
 
-        let mut decoder = ZlibDecoder::new(&compressed[..]);
-        let mut raw_bytes = Vec::new();
-        decoder
-            .read_to_end(&mut raw_bytes)
-            .map_err(|e| DecoderError::DecompressionError(e.to_string()))?;
+        Self::new_from_parts(bits, &compressed)
+    }
+
+    pub fn new_from_parts(bits: u8, compressed: &[u8]) -> Result<Self, DecoderError> {
+        let bits_per_status =
+            BitsPerStatus::try_from(bits).map_err(|_| DecoderError::InvalidBitsPerStatus(bits))?;
+
+        let raw_bytes = DefaultDecompressor::default()
+            .decompress(compressed)
+            .map_err(DecoderError::DecompressionError)?;
 
         Ok(Self {
             raw_bytes,
-            bits_per_status: 8,
+            bits_per_status,
+            known_len: None,
+            registry: StatusRegistry::default(),
         })
     }
+
+    #[cfg(feature = "serde")]
+    pub fn new_from_json(json: &str) -> Result<Self, DecoderError> {
+        let status_list = StatusList::from_json(json)
+            .map_err(|e| DecoderError::SerializationError(e.to_string()))?;
+
+        Self::new(&status_list)
+    }
+}
+
+// Decoders are shared across threads by SharedStatusListDecoder; keep them
+// Send + Sync.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<StatusListDecoder>();
+};
+
+/// A cheaply cloneable, read-only handle to a decoder, meant to be kept in
+/// server state and queried concurrently.
+#[derive(Debug, Clone)]
+pub struct SharedStatusListDecoder(Arc<StatusListDecoder>);
+
+impl SharedStatusListDecoder {
+    pub fn new(decoder: StatusListDecoder) -> Self {
+        Self(Arc::new(decoder))
+    }
+}
+
+impl From<StatusListDecoder> for SharedStatusListDecoder {
+    fn from(decoder: StatusListDecoder) -> Self {
+        Self::new(decoder)
+    }
+}
+
+impl From<Arc<StatusListDecoder>> for SharedStatusListDecoder {
+    fn from(decoder: Arc<StatusListDecoder>) -> Self {
+        Self(decoder)
+    }
+}
+
+impl Deref for SharedStatusListDecoder {
+    type Target = StatusListDecoder;
+
+    fn deref(&self) -> &StatusListDecoder {
+        &self.0
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "encoder", feature = "serde"))]
 mod tests {
     use super::*;
     use crate::builder::StatusListBuilder;
@@ -204,7 +442,7 @@ mod tests {
 
         let base64_str = decoded["lst"].as_str().unwrap();
 
-        let decoder = StatusListDecoder::new_from_base64(base64_str)?;
+        let decoder = StatusListDecoder::new_from_base64(base64_str, 8)?;
 
         assert_eq!(decoder.get_status(0)?, StatusType::Valid);
         assert_eq!(decoder.get_status(1)?, StatusType::Invalid);
@@ -230,12 +468,49 @@ mod tests {
 
     #[test]
     fn test_decoder_base64_from_string() {
-        match StatusListDecoder::new_from_base64("invalid base64!@#$") {
+        match StatusListDecoder::new_from_base64("invalid base64!@#$", 1) {
             Err(e) => assert!(e.to_string().contains("Base64 decoding error")),
             _ => panic!("Expected Base64 decoding error"),
         }
     }
 
+    #[test]
+    fn test_new_from_base64_respects_bits() -> Result<(), DecoderError> {
+        let decoder = StatusListDecoder::new_from_base64("eNrbuRgAAhcBXQ", 1)?;
+        assert_eq!(decoder.get_bits_per_status(), 1);
+        assert_eq!(decoder.len(), 16);
+        assert_eq!(decoder.get_status(0)?, StatusType::Invalid);
+        assert_eq!(decoder.get_status(1)?, StatusType::Valid);
+        assert_eq!(decoder.get_status(15)?, StatusType::Invalid);
+
+        match StatusListDecoder::new_from_base64("eNrbuRgAAhcBXQ", 3) {
+            Err(DecoderError::InvalidBitsPerStatus(3)) => (),
+            _ => panic!("Expected InvalidBitsPerStatus error"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_from_parts_and_json() -> Result<(), DecoderError> {
+        let compressed = [0x78, 0xda, 0xdb, 0xb9, 0x18, 0x00, 0x02, 0x17, 0x01, 0x5d];
+        let decoder = StatusListDecoder::new_from_parts(2, &compressed)?;
+        assert_eq!(decoder.get_bits_per_status(), 2);
+        assert_eq!(decoder.get_status(0)?, StatusType::Invalid);
+        assert_eq!(decoder.get_status(1)?, StatusType::Suspended);
+
+        let decoder = StatusListDecoder::new_from_json(r#"{"bits":1,"lst":"eNrbuRgAAhcBXQ"}"#)?;
+        assert_eq!(decoder.get_raw_bytes(), &[0xB9, 0xA3]);
+        assert_eq!(decoder.get_bits_per_status(), 1);
+
+        match StatusListDecoder::new_from_json(r#"{"bits":1}"#) {
+            Err(DecoderError::SerializationError(_)) => (),
+            _ => panic!("Expected SerializationError"),
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_decoder_decompression_error() {
         let status_list = StatusList {
@@ -250,6 +525,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_decoder_max_decompressed_len() {
+        let builder = StatusListBuilder::new(1).unwrap();
+        builder.set_status(8 * 4096 - 1, StatusType::Invalid);
+        let status_list = builder.build().unwrap();
+
+        let decoder = StatusListDecoder::with_max_decompressed_len(&status_list, 4096).unwrap();
+        assert_eq!(
+            decoder.get_status(8 * 4096 - 1).unwrap(),
+            StatusType::Invalid
+        );
+
+        match StatusListDecoder::with_max_decompressed_len(&status_list, 4095) {
+            Err(DecoderError::DecompressionError(msg)) => {
+                assert_eq!(msg, "decompressed list exceeds 4095 bytes")
+            }
+            _ => panic!("Expected DecompressionError"),
+        }
+    }
+
     #[test]
     fn test_decoder_invalid_byte_index() {
         let builder = StatusListBuilder::new(2).unwrap();
@@ -264,19 +559,359 @@ mod tests {
     }
 
     #[test]
-    fn test_decoder_invalid_status_type() {
-        let status_list = StatusList {
-            bits: 8,
-            lst: "eJzLBQAAdgB2".as_bytes().to_vec(),
-            aggregation_uri: None,
-        };
+    fn test_shared_decoder_across_threads() {
+        let status_list = StatusList::from_packed_bytes(1, &[0b0000_0100]).unwrap();
+        let shared = SharedStatusListDecoder::from(StatusListDecoder::new(&status_list).unwrap());
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let shared = shared.clone();
+                std::thread::spawn(move || shared.get_status(2).unwrap())
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), StatusType::Invalid);
+        }
+        assert_eq!(shared.len(), 8);
+    }
+
+    #[test]
+    fn test_copy_raw_bytes() {
+        let status_list = StatusList::from_packed_bytes(8, &[1, 2, 3, 4, 5]).unwrap();
+        let decoder = StatusListDecoder::new(&status_list).unwrap();
+        assert_eq!(decoder.raw_byte_len(), 5);
+
+        let mut out = [0u8; 8];
+        assert_eq!(decoder.copy_raw_bytes_into(&mut out).unwrap(), 5);
+        assert_eq!(out, [1, 2, 3, 4, 5, 0, 0, 0]);
+
+        match decoder.copy_raw_bytes_into(&mut [0u8; 4]) {
+            Err(DecoderError::BufferTooSmall(5, 4)) => (),
+            _ => panic!("Expected BufferTooSmall"),
+        }
 
-        if let Ok(decoder) = StatusListDecoder::new(&status_list) {
-            match decoder.get_status(0) {
-                Err(DecoderError::InvalidStatusType(_)) => (),
-                _ => panic!("Expected InvalidStatusType error"),
+        let mut chunk = [0u8; 2];
+        let mut copied = Vec::new();
+        let mut offset = 0;
+        loop {
+            let n = decoder.copy_raw_chunk_into(offset, &mut chunk);
+            if n == 0 {
+                break;
             }
+            copied.extend_from_slice(&chunk[..n]);
+            offset += n;
         }
+        assert_eq!(copied, vec![1, 2, 3, 4, 5]);
+        assert_eq!(decoder.copy_raw_chunk_into(99, &mut chunk), 0);
+    }
+
+    #[test]
+    fn test_get_named_status() {
+        let status_list = StatusList::from_packed_bytes(8, &[0x01, 0x0B, 0x42]).unwrap();
+        let decoder = StatusListDecoder::new(&status_list).unwrap().with_registry(
+            StatusRegistry::default()
+                .with_status(StatusType::ApplicationSpecific11, "PendingReview"),
+        );
+
+        assert_eq!(decoder.get_named_status(0).unwrap().to_string(), "INVALID");
+        assert_eq!(
+            decoder.get_named_status(1).unwrap().name,
+            Some("PendingReview")
+        );
+        assert_eq!(decoder.get_named_status(2).unwrap().to_string(), "0x42");
+    }
+
+    #[test]
+    fn test_decoder_any_8bit_status() {
+        let status_list = StatusList::from_packed_bytes(8, &[0x00, 0x42, 0xFF, 0x0B]).unwrap();
+        let decoder = StatusListDecoder::new(&status_list).unwrap();
+
+        assert_eq!(decoder.get_status(0).unwrap(), StatusType::Valid);
+        assert_eq!(decoder.get_status(1).unwrap(), StatusType::from(0x42));
+        assert_eq!(decoder.get_status(2).unwrap(), StatusType::from(0xFF));
+        assert_eq!(
+            decoder.get_status(3).unwrap(),
+            StatusType::ApplicationSpecific11
+        );
+    }
+
+    #[test]
+    fn test_decoder_rejects_invalid_bits() {
+        let status_list = StatusListBuilder::new(1).unwrap().build().unwrap();
+
+        for bits in [0, 3, 5, 16] {
+            let status_list = StatusList {
+                bits,
+                ..status_list.clone()
+            };
+            match StatusListDecoder::new(&status_list) {
+                Err(DecoderError::InvalidBitsPerStatus(value)) => assert_eq!(value, bits),
+                _ => panic!("Expected InvalidBitsPerStatus error for {}", bits),
+            }
+        }
+    }
+
+    #[test]
+    fn test_decoder_expect_bits() -> Result<(), DecoderError> {
+        let status_list = StatusListBuilder::from_vec(vec![StatusType::Suspended], 2)
+            .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?
+            .build()
+            .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?;
+
+        let decoder = StatusListDecoder::new(&status_list)?.expect_bits(2)?;
+        assert_eq!(decoder.get_bits_per_status(), 2);
+        assert_eq!(decoder.get_status(0)?, StatusType::Suspended);
+
+        match StatusListDecoder::new(&status_list)?.expect_bits(1) {
+            Err(DecoderError::BitsMismatch(expected, found)) => {
+                assert_eq!((expected, found), (1, 2));
+            }
+            _ => panic!("Expected BitsMismatch error"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_beyond_known_len() -> Result<(), DecoderError> {
+        let builder = StatusListBuilder::new(1)
+            .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?;
+        builder
+            .add_status(StatusType::Invalid)
+            .add_status(StatusType::Valid)
+            .add_status(StatusType::Invalid);
+        let status_list = builder
+            .build()
+            .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?;
+
+        // Without a known length the padding bits decode as Valid
+        let decoder = StatusListDecoder::new(&status_list)?;
+        assert_eq!(decoder.len(), 8);
+        assert_eq!(decoder.get_status(5)?, StatusType::Valid);
+
+        let decoder = StatusListDecoder::new(&status_list)?.with_len(3)?;
+        assert_eq!(decoder.len(), 3);
+        assert_eq!(decoder.get_status(2)?, StatusType::Invalid);
+        match decoder.get_status(5) {
+            Err(DecoderError::IndexNotPresent(5)) => (),
+            _ => panic!("Expected IndexNotPresent error"),
+        }
+
+        assert!(StatusListDecoder::new(&status_list)?.with_len(9).is_err());
+        assert!(StatusListDecoder::new(&status_list)?
+            .with_len(0)?
+            .is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_status_list_round_trip() -> Result<(), DecoderError> {
+        let statuses = vec![
+            StatusType::Invalid,
+            StatusType::Suspended,
+            StatusType::Valid,
+            StatusType::ApplicationSpecific3,
+            StatusType::Invalid,
+        ];
+        let status_list = StatusListBuilder::from_vec(statuses.clone(), 2)
+            .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?
+            .build()
+            .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?;
+
+        let forwarded = StatusListDecoder::new(&status_list)?.to_status_list(2)?;
+        assert_eq!(forwarded.bits, 2);
+        assert_eq!(forwarded.lst, status_list.lst);
+
+        let decoder = StatusListDecoder::new(&forwarded)?;
+        for (i, status) in statuses.iter().enumerate() {
+            assert_eq!(decoder.get_status(i)?, *status);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_status_list_from_base64() -> Result<(), DecoderError> {
+        let status_list = StatusListBuilder::from_vec(
+            vec![StatusType::Invalid, StatusType::Valid, StatusType::Invalid],
+            1,
+        )
+        .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?
+        .build()
+        .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?;
+        let lst = base64url::encode(&status_list.lst);
+
+        let forwarded = StatusListDecoder::new_from_base64(&lst, 1)?.to_status_list(1)?;
+        let decoder = StatusListDecoder::new(&forwarded)?;
+        assert_eq!(decoder.get_status(0)?, StatusType::Invalid);
+        assert_eq!(decoder.get_status(1)?, StatusType::Valid);
+        assert_eq!(decoder.get_status(2)?, StatusType::Invalid);
+
+        match StatusListDecoder::new_from_base64(&lst, 1)?.to_status_list(3) {
+            Err(DecoderError::StatusListCreationError(msg)) => {
+                assert!(msg.contains("Invalid bits per status value: 3"))
+            }
+            _ => panic!("Expected StatusListCreationError"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_status_list_repacks_to_bits() -> Result<(), DecoderError> {
+        let statuses = vec![
+            StatusType::Invalid,
+            StatusType::Suspended,
+            StatusType::Valid,
+            StatusType::Invalid,
+            StatusType::Suspended,
+        ];
+        let status_list = StatusListBuilder::from_vec(statuses.clone(), 2)
+            .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?
+            .build()
+            .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?;
+        let decoder = StatusListDecoder::new(&status_list)?.with_len(statuses.len())?;
+
+        let widened = decoder.to_status_list(8)?;
+        assert_eq!(widened.bits, 8);
+        let widened = StatusListDecoder::new(&widened)?;
+        assert_eq!(widened.len(), statuses.len());
+        for (i, status) in statuses.iter().enumerate() {
+            assert_eq!(widened.get_status(i)?, *status);
+        }
+
+        let same = StatusListDecoder::new(&decoder.to_status_list(2)?)?;
+        assert_eq!(same.len(), 8);
+        assert_eq!(same.get_status(5)?, StatusType::Valid);
+
+        match decoder.to_status_list(1) {
+            Err(DecoderError::StatusListCreationError(msg)) => {
+                assert!(msg.contains("cannot be represented with 1 bits"))
+            }
+            _ => panic!("Expected StatusListCreationError"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_decompressor() -> Result<(), DecoderError> {
+        use crate::compression::ZlibCompression;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Debug, Default)]
+        struct Counting(AtomicUsize);
+
+        impl Decompressor for Counting {
+            fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                ZlibCompression::default().decompress(data)
+            }
+        }
+
+        let status_list = StatusListBuilder::from_vec(vec![StatusType::Invalid], 1)
+            .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?
+            .build()
+            .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?;
+
+        let counting = Counting::default();
+        let decoder = StatusListDecoder::with_decompressor(&status_list, &counting)?;
+        assert_eq!(decoder.get_status(0)?, StatusType::Invalid);
+        assert_eq!(counting.0.load(Ordering::Relaxed), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_buffer_reuse() -> Result<(), DecoderError> {
+        let first = StatusListBuilder::from_vec(vec![StatusType::Invalid; 9], 1)
+            .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?
+            .build()
+            .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?;
+        let second = StatusListBuilder::from_vec(vec![StatusType::Suspended], 2)
+            .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?
+            .build()
+            .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?;
+
+        let mut buffer = Vec::with_capacity(1024);
+        StatusListDecoder::decode_into(&first, &mut buffer)?;
+        assert_eq!(buffer, vec![0xFF, 0x01]);
+
+        let decoder = StatusListDecoder::from_buffer(&second, buffer)?;
+        assert_eq!(decoder.get_status(0)?, StatusType::Suspended);
+        assert_eq!(decoder.get_raw_bytes(), &[0x02]);
+
+        let buffer = decoder.into_raw_bytes();
+        assert!(buffer.capacity() >= 1024);
+
+        let invalid = StatusList {
+            bits: 3,
+            lst: first.lst.clone(),
+            aggregation_uri: None,
+        };
+        let mut buffer = buffer;
+        match StatusListDecoder::decode_into(&invalid, &mut buffer) {
+            Err(DecoderError::InvalidBitsPerStatus(3)) => (),
+            _ => panic!("Expected InvalidBitsPerStatus error"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dump() -> Result<(), DecoderError> {
+        let builder = StatusListBuilder::from_vec(
+            vec![
+                StatusType::Invalid,
+                StatusType::Suspended,
+                StatusType::Valid,
+                StatusType::ApplicationSpecific3,
+                StatusType::Valid,
+                StatusType::Invalid,
+            ],
+            2,
+        )
+        .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?;
+        let status_list = builder
+            .build()
+            .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?;
+        let decoder = StatusListDecoder::new(&status_list)?;
+
+        assert_eq!(
+            decoder.dump(..),
+            "00000000  c9  11001001  0=Invalid 1=Suspended 2=Valid 3=ApplicationSpecific3\n\
+             00000001  04  00000100  4=Valid 5=Invalid 6=Valid 7=Valid\n"
+        );
+        assert_eq!(
+            decoder.dump(3..5),
+            "00000000  c9  11001001  3=ApplicationSpecific3\n\
+             00000001  04  00000100  4=Valid\n"
+        );
+        assert_eq!(decoder.dump(100..200), "");
+        assert_eq!(decoder.dump(7..=usize::MAX), decoder.dump(7..));
+        assert_eq!(
+            decoder.dump((Bound::Excluded(usize::MAX), Bound::Unbounded)),
+            ""
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dump_undefined_status() -> Result<(), DecoderError> {
+        let encoder = crate::encoder::StatusListEncoder::new(8);
+        let status_list = encoder
+            .finalize(&[0x00, 0x05])
+            .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?;
+        let decoder = StatusListDecoder::new(&status_list)?;
+
+        assert_eq!(
+            decoder.dump(..),
+            "00000000  00  00000000  0=Valid\n00000001  05  00000101  1=ApplicationSpecific(5)\n"
+        );
+
+        Ok(())
     }
 
     #[test]
@@ -285,9 +920,13 @@ mod tests {
             DecoderError::Base64Error("invalid input".to_string()),
             DecoderError::DecompressionError("invalid data".to_string()),
             DecoderError::InvalidByteIndex(100),
+            DecoderError::IndexNotPresent(7),
             DecoderError::InvalidStatusType(255),
+            DecoderError::InvalidBitsPerStatus(3),
+            DecoderError::BitsMismatch(1, 2),
             DecoderError::StatusListCreationError("invalid status list".to_string()),
             DecoderError::SerializationError("invalid serialization".to_string()),
+            DecoderError::BufferTooSmall(8, 4),
         ];
 
         for error in errors {
@@ -302,15 +941,27 @@ mod tests {
                 DecoderError::InvalidByteIndex(_) => {
                     assert!(error_string.contains("Invalid byte index"));
                 }
+                DecoderError::IndexNotPresent(_) => {
+                    assert!(error_string.contains("not present in the status list"));
+                }
                 DecoderError::InvalidStatusType(_) => {
                     assert!(error_string.contains("Invalid status type value"));
                 }
+                DecoderError::InvalidBitsPerStatus(_) => {
+                    assert!(error_string.contains("Invalid bits per status value"));
+                }
+                DecoderError::BitsMismatch(_, _) => {
+                    assert!(error_string.contains("Bits per status mismatch"));
+                }
                 DecoderError::StatusListCreationError(_) => {
                     assert!(error_string.contains("Status list creation error"));
                 }
                 DecoderError::SerializationError(_) => {
                     assert!(error_string.contains("Serialization error"));
                 }
+                DecoderError::BufferTooSmall(_, _) => {
+                    assert!(error_string.contains("too small"));
+                }
             }
         }
     }
@@ -0,0 +1,376 @@
+use std::collections::BTreeMap;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::bitops;
+use crate::decoder::StatusListDecoder;
+use crate::error::DecoderError;
+use crate::types::{StatusList, StatusType};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusChange {
+    /// The index exists only in the newer list.
+    Added { index: usize, status: StatusType },
+    /// The index exists only in the older list.
+    Removed { index: usize, status: StatusType },
+    Modified {
+        index: usize,
+        old: StatusType,
+        new: StatusType,
+    },
+}
+
+impl StatusChange {
+    pub fn index(&self) -> usize {
+        match self {
+            StatusChange::Added { index, .. }
+            | StatusChange::Removed { index, .. }
+            | StatusChange::Modified { index, .. } => *index,
+        }
+    }
+}
+
+/// Changes between two lists, in index order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatusListDiff {
+    pub changes: Vec<StatusChange>,
+}
+
+impl StatusListDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.changes.iter().map(StatusChange::index)
+    }
+
+    pub fn added(&self) -> impl Iterator<Item = &StatusChange> {
+        self.changes
+            .iter()
+            .filter(|c| matches!(c, StatusChange::Added { .. }))
+    }
+
+    pub fn removed(&self) -> impl Iterator<Item = &StatusChange> {
+        self.changes
+            .iter()
+            .filter(|c| matches!(c, StatusChange::Removed { .. }))
+    }
+
+    pub fn modified(&self) -> impl Iterator<Item = &StatusChange> {
+        self.changes
+            .iter()
+            .filter(|c| matches!(c, StatusChange::Modified { .. }))
+    }
+}
+
+/// Bucket width used by dashboards and the CLI.
+pub const DEFAULT_BUCKET_SIZE: usize = 10_000;
+
+/// Change counts for the indices `start..end`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct DiffBucket {
+    pub start: usize,
+    pub end: usize,
+    pub added: usize,
+    pub removed: usize,
+    pub modified: usize,
+}
+
+impl DiffBucket {
+    pub fn total(&self) -> usize {
+        self.added + self.removed + self.modified
+    }
+}
+
+// A diff reduced to per-bucket counts, small enough to chart or print for
+// lists with millions of changes. Buckets without changes are left out.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct DiffSummary {
+    pub bucket_size: usize,
+    pub added: usize,
+    pub removed: usize,
+    pub modified: usize,
+    pub buckets: Vec<DiffBucket>,
+}
+
+impl DiffSummary {
+    pub fn total(&self) -> usize {
+        self.added + self.removed + self.modified
+    }
+}
+
+/// The summary of the diff between two consecutive versions, labelled with
+/// the caller's version identifiers (e.g. token `iat`s).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct DiffTimelineStep {
+    pub from: u64,
+    pub to: u64,
+    pub summary: DiffSummary,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct DiffTimeline {
+    pub bucket_size: usize,
+    pub steps: Vec<DiffTimelineStep>,
+}
+
+impl DiffTimeline {
+    /// Summarizes the diffs between consecutive `(version, list)` pairs, which
+    /// must be given oldest first.
+    pub fn new(versions: &[(u64, &StatusListDecoder)], bucket_size: usize) -> Self {
+        let steps = versions
+            .windows(2)
+            .map(|pair| {
+                let ((from, older), (to, newer)) = (pair[0], pair[1]);
+                DiffTimelineStep {
+                    from,
+                    to,
+                    summary: older.diff(newer).summarize(bucket_size),
+                }
+            })
+            .collect();
+        Self {
+            bucket_size: bucket_size.max(1),
+            steps,
+        }
+    }
+}
+
+impl StatusListDiff {
+    /// Counts changes per `bucket_size` indices, e.g. `DEFAULT_BUCKET_SIZE`.
+    pub fn summarize(&self, bucket_size: usize) -> DiffSummary {
+        let bucket_size = bucket_size.max(1);
+        let mut summary = DiffSummary {
+            bucket_size,
+            ..DiffSummary::default()
+        };
+        let mut buckets = BTreeMap::new();
+
+        for change in &self.changes {
+            let start = change.index() / bucket_size * bucket_size;
+            let bucket = buckets.entry(start).or_insert_with(|| DiffBucket {
+                start,
+                end: start.saturating_add(bucket_size),
+                ..DiffBucket::default()
+            });
+            match change {
+                StatusChange::Added { .. } => {
+                    bucket.added += 1;
+                    summary.added += 1;
+                }
+                StatusChange::Removed { .. } => {
+                    bucket.removed += 1;
+                    summary.removed += 1;
+                }
+                StatusChange::Modified { .. } => {
+                    bucket.modified += 1;
+                    summary.modified += 1;
+                }
+            }
+        }
+
+        summary.buckets = buckets.into_values().collect();
+        summary
+    }
+}
+
+impl StatusListDecoder {
+    /// Compares this (older) list against `newer`.
+    pub fn diff(&self, newer: &StatusListDecoder) -> StatusListDiff {
+        let common = self.len().min(newer.len());
+        let mut changes = Vec::new();
+
+        let same_width = self.bits() == newer.bits();
+        let per_byte = bitops::statuses_per_byte(self.bits());
+
+        let mut index = 0;
+        while index < common {
+            // With equal bit widths, identical bytes hold identical statuses
+            // and can be skipped without decoding.
+            let byte = index / per_byte;
+            if same_width
+                && index.is_multiple_of(per_byte)
+                && index + per_byte <= common
+                && self.get_raw_bytes()[byte] == newer.get_raw_bytes()[byte]
+            {
+                index += per_byte;
+                continue;
+            }
+            let old = self.status_at(index);
+            let new = newer.status_at(index);
+            if old != new {
+                changes.push(StatusChange::Modified { index, old, new });
+            }
+            index += 1;
+        }
+
+        changes.extend((common..newer.len()).map(|index| StatusChange::Added {
+            index,
+            status: newer.status_at(index),
+        }));
+        changes.extend((common..self.len()).map(|index| StatusChange::Removed {
+            index,
+            status: self.status_at(index),
+        }));
+        StatusListDiff { changes }
+    }
+
+    fn status_at(&self, index: usize) -> StatusType {
+        self.get_status(index)
+            .expect("index is within the decoded list")
+    }
+}
+
+impl StatusList {
+    /// Decodes both lists and compares this (older) list against `newer`.
+    pub fn diff(&self, newer: &StatusList) -> Result<StatusListDiff, DecoderError> {
+        Ok(StatusListDecoder::new(self)?.diff(&StatusListDecoder::new(newer)?))
+    }
+}
+
+#[cfg(all(test, feature = "encoder"))]
+mod tests {
+    use super::*;
+    use crate::builder::StatusListBuilder;
+
+    fn list(statuses: Vec<StatusType>, bits: u8) -> StatusList {
+        StatusListBuilder::from_vec(statuses, bits)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = list(vec![StatusType::Valid; 20], 2);
+        let mut statuses = vec![StatusType::Valid; 24];
+        statuses[5] = StatusType::Invalid;
+        statuses[13] = StatusType::Suspended;
+        statuses[21] = StatusType::Invalid;
+        let new = list(statuses, 2);
+
+        let diff = old.diff(&new).unwrap();
+        assert_eq!(
+            diff.modified().copied().collect::<Vec<_>>(),
+            vec![
+                StatusChange::Modified {
+                    index: 5,
+                    old: StatusType::Valid,
+                    new: StatusType::Invalid,
+                },
+                StatusChange::Modified {
+                    index: 13,
+                    old: StatusType::Valid,
+                    new: StatusType::Suspended,
+                },
+            ]
+        );
+        assert_eq!(diff.added().count(), 4);
+        assert_eq!(
+            diff.added().nth(1),
+            Some(&StatusChange::Added {
+                index: 21,
+                status: StatusType::Invalid,
+            })
+        );
+        assert_eq!(diff.removed().count(), 0);
+        assert_eq!(
+            diff.indices().collect::<Vec<_>>(),
+            vec![5, 13, 20, 21, 22, 23]
+        );
+
+        let reverse = new.diff(&old).unwrap();
+        assert_eq!(reverse.removed().count(), 4);
+        assert_eq!(reverse.modified().count(), 2);
+
+        assert!(new.diff(&new).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_summarize() {
+        let old = list(vec![StatusType::Valid; 40], 1);
+        let mut statuses = vec![StatusType::Valid; 48];
+        for index in [3, 7, 25, 26] {
+            statuses[index] = StatusType::Invalid;
+        }
+        let new = list(statuses, 1);
+
+        let summary = old.diff(&new).unwrap().summarize(10);
+        assert_eq!(
+            (summary.added, summary.modified, summary.total()),
+            (8, 4, 12)
+        );
+        assert_eq!(
+            summary.buckets,
+            vec![
+                DiffBucket {
+                    start: 0,
+                    end: 10,
+                    modified: 2,
+                    ..DiffBucket::default()
+                },
+                DiffBucket {
+                    start: 20,
+                    end: 30,
+                    modified: 2,
+                    ..DiffBucket::default()
+                },
+                DiffBucket {
+                    start: 40,
+                    end: 50,
+                    added: 8,
+                    ..DiffBucket::default()
+                },
+            ]
+        );
+        assert!(new.diff(&new).unwrap().summarize(0).buckets.is_empty());
+    }
+
+    #[test]
+    fn test_timeline() {
+        let v1 = StatusListDecoder::new(&list(vec![StatusType::Valid; 8], 1)).unwrap();
+        let mut statuses = vec![StatusType::Valid; 8];
+        statuses[2] = StatusType::Invalid;
+        let v2 = StatusListDecoder::new(&list(statuses, 1)).unwrap();
+
+        let timeline = DiffTimeline::new(&[(100, &v1), (200, &v2), (300, &v2)], 4);
+        assert_eq!(timeline.steps.len(), 2);
+        assert_eq!((timeline.steps[0].from, timeline.steps[0].to), (100, 200));
+        assert_eq!(timeline.steps[0].summary.buckets[0].start, 0);
+        assert_eq!(timeline.steps[0].summary.modified, 1);
+        assert_eq!(timeline.steps[1].summary.total(), 0);
+
+        #[cfg(feature = "serde")]
+        assert_eq!(
+            serde_json::to_string(&timeline.steps[0].summary).unwrap(),
+            r#"{"bucket_size":4,"added":0,"removed":0,"modified":1,"buckets":[{"start":0,"end":4,"added":0,"removed":0,"modified":1}]}"#
+        );
+    }
+
+    #[test]
+    fn test_diff_across_bit_widths() {
+        let old = list(vec![StatusType::Valid, StatusType::Invalid], 1);
+        let new = list(vec![StatusType::Valid, StatusType::Valid], 8);
+
+        let old = StatusListDecoder::new(&old).unwrap().with_len(2).unwrap();
+        let new = StatusListDecoder::new(&new).unwrap();
+        assert_eq!(
+            old.diff(&new).changes,
+            vec![StatusChange::Modified {
+                index: 1,
+                old: StatusType::Invalid,
+                new: StatusType::Valid,
+            }]
+        );
+    }
+}
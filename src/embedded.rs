@@ -0,0 +1,146 @@
+use serde_json::{Map, Value};
+
+use crate::error::TokenError;
+use crate::jwt::decode_json;
+use crate::signing::SignatureVerifier;
+use crate::token::{StatusCheckReport, StatusListTokenVerifier};
+
+/// The protected header parameter of a referenced JWT or SD-JWT that
+/// carries a Status List Token inline (crate extension, not part of the
+/// specification).
+pub const EMBEDDED_STATUS_LIST_HEADER: &str = "status_list";
+
+/// Adds a signed Status List Token in JWT form to the protected header of a
+/// referenced token before the issuer signs it, so that verifiers can check
+/// its status without fetching the list. Meant for small lists.
+pub fn embed_status_list(header: &mut Map<String, Value>, status_list_token: &str) {
+    header.insert(
+        EMBEDDED_STATUS_LIST_HEADER.to_string(),
+        Value::String(status_list_token.to_string()),
+    );
+}
+
+/// The Status List Token embedded in a referenced JWT or SD-JWT, if any.
+/// The referenced token's own signature is not checked; the embedded token
+/// carries the list issuer's signature and is verified like a fetched one.
+pub fn embedded_status_list(referenced_token: &str) -> Result<Option<String>, TokenError> {
+    let jws = referenced_token
+        .trim()
+        .split('~')
+        .next()
+        .unwrap_or_default();
+    let header = jws.split('.').next().unwrap_or_default();
+    let header: Map<String, Value> = decode_json(header)?;
+    match header.get(EMBEDDED_STATUS_LIST_HEADER) {
+        None => Ok(None),
+        Some(Value::String(token)) => Ok(Some(token.clone())),
+        Some(_) => Err(TokenError::InvalidClaim(
+            EMBEDDED_STATUS_LIST_HEADER.to_string(),
+        )),
+    }
+}
+
+impl StatusListTokenVerifier {
+    /// Checks `idx` against the list embedded in `referenced_token`, with
+    /// the same checks as `check_jwt`. `None` means the token embeds no
+    /// list and the caller fetches it as usual.
+    pub fn check_embedded(
+        &self,
+        referenced_token: &str,
+        verifier: &impl SignatureVerifier,
+        idx: usize,
+        now: u64,
+    ) -> Result<Option<StatusCheckReport>, TokenError> {
+        embedded_status_list(referenced_token)?
+            .map(|token| self.check_jwt(&token, verifier, idx, now))
+            .transpose()
+    }
+}
+
+#[cfg(all(test, feature = "encoder"))]
+mod tests {
+    use super::*;
+    use crate::builder::StatusListBuilder;
+    use crate::jwt::JwtStatusListToken;
+    use crate::signing::tests::TestKey;
+    use crate::types::StatusType;
+    use crate::uri::StatusListUri;
+
+    fn uri() -> StatusListUri {
+        StatusListUri::parse("https://example.com/statuslists/1").unwrap()
+    }
+
+    fn status_list_token(sub: StatusListUri) -> String {
+        let status_list =
+            StatusListBuilder::from_vec(vec![StatusType::Valid, StatusType::Invalid], 1)
+                .unwrap()
+                .build()
+                .unwrap();
+        JwtStatusListToken::new(sub, 1000, status_list)
+            .with_exp(2000)
+            .sign(&TestKey(1))
+            .unwrap()
+    }
+
+    // An unsigned SD-JWT whose header is `header`; only the header is read.
+    fn referenced_token(header: &Map<String, Value>) -> String {
+        let encode = |value: &Value| base64url::encode(serde_json::to_vec(value).unwrap());
+        let claims = serde_json::json!({
+            "status": {"status_list": {"idx": 1, "uri": uri().as_str()}},
+        });
+        format!(
+            "{}.{}.c2ln~WyJkaXNjbG9zdXJlIl0~",
+            encode(&Value::Object(header.clone())),
+            encode(&claims)
+        )
+    }
+
+    #[test]
+    fn test_check_embedded() {
+        let mut header = Map::new();
+        header.insert("alg".to_string(), Value::from("ES256"));
+        let checker = StatusListTokenVerifier::new(uri());
+        assert_eq!(
+            checker
+                .check_embedded(&referenced_token(&header), &TestKey(1), 1, 1500)
+                .unwrap(),
+            None
+        );
+
+        embed_status_list(&mut header, &status_list_token(uri()));
+        let referenced = referenced_token(&header);
+        assert_eq!(
+            embedded_status_list(&referenced).unwrap(),
+            Some(status_list_token(uri()))
+        );
+        let report = checker
+            .check_embedded(&referenced, &TestKey(1), 1, 1500)
+            .unwrap()
+            .unwrap();
+        assert_eq!(report.status, StatusType::Invalid.value());
+        assert_eq!(report.token_iat, 1000);
+
+        assert!(matches!(
+            checker.check_embedded(&referenced, &TestKey(2), 1, 1500),
+            Err(TokenError::SignatureError(_))
+        ));
+        assert!(matches!(
+            checker.check_embedded(&referenced, &TestKey(1), 1, 2000),
+            Err(TokenError::Expired(2000))
+        ));
+
+        // A list embedded for another URI does not stand in for this one.
+        let other = StatusListUri::parse("https://example.com/statuslists/2").unwrap();
+        embed_status_list(&mut header, &status_list_token(other));
+        assert!(matches!(
+            checker.check_embedded(&referenced_token(&header), &TestKey(1), 1, 1500),
+            Err(TokenError::SubjectMismatch(_))
+        ));
+
+        header.insert(EMBEDDED_STATUS_LIST_HEADER.to_string(), Value::from(1));
+        assert!(matches!(
+            embedded_status_list(&referenced_token(&header)),
+            Err(TokenError::InvalidClaim(_))
+        ));
+    }
+}
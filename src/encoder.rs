@@ -1,132 +1,120 @@
-use flate2::{write::ZlibEncoder, Compression};
 use std::io::Write;
+use std::sync::Arc;
+
+use flate2::{write::ZlibEncoder, Compression};
 
+use crate::bitops;
+use crate::compression::{Compressor, ZlibCompression};
 use crate::error::BuilderError;
-use crate::types::{StatusList, StatusType};
+use crate::types::{BitsPerStatus, StatusList, StatusType};
 
 #[derive(Debug)]
 pub struct StatusListEncoder {
     bits_per_status: u8,
+    compressor: Arc<dyn Compressor>,
 }
 
 impl StatusListEncoder {
     pub fn new(bits_per_status: u8) -> Self {
-        Self { bits_per_status }
+        Self {
+            bits_per_status,
+            compressor: Arc::new(ZlibCompression::default()),
+        }
     }
 
-    pub fn encode_status1(&self, bytes: &mut [u8], index: usize, status: StatusType) {
-        let statuses_per_byte = (8 / self.bits_per_status) as usize;
-        let byte_index = index / statuses_per_byte;
-        let position_in_byte = index % statuses_per_byte;
-
-        let status_value = status as u8;
-        let bit_shift = match self.bits_per_status {
-            1 => position_in_byte,
-            2 => position_in_byte * 2,
-            4 => position_in_byte * 4,
-            8 => 0,
-            _ => unreachable!(),
-        };
-
-        let mask = !(((1u8 << self.bits_per_status) - 1) << bit_shift);
-        bytes[byte_index] &= mask;
-        bytes[byte_index] |= status_value << bit_shift;
-
-        #[cfg(debug_assertions)]
-        println!(
-            "Encoding: index={}, byte={:08b}, shift={}, status={:?}",
-            index, bytes[byte_index], bit_shift, status
-        );
+    pub fn with_compressor(mut self, compressor: impl Compressor + 'static) -> Self {
+        self.compressor = Arc::new(compressor);
+        self
     }
 
-    pub fn encode_status(&self, bytes: &mut [u8], index: usize, status: StatusType) {
-        let statuses_per_byte = 8 / self.bits_per_status as usize;
-        let byte_index = index / statuses_per_byte;
-        let position_in_byte = index % statuses_per_byte;
-
-        let status_value = status as u8;
-
-        let bit_shift = match self.bits_per_status {
-            1 => {
-                // 8 values per byte, right to left
-                position_in_byte
-            }
-            2 => {
-                // 4 values per byte, right to left in pairs
-                match position_in_byte {
-                    0 => 0, // First value in bits 1-0
-                    1 => 2, // Second value in bits 3-2
-                    2 => 4, // Third value in bits 5-4
-                    3 => 6, // Fourth value in bits 7-6
-                    _ => unreachable!(),
-                }
-            }
-            4 => {
-                // 2 values per byte, left to right in nibbles
-                match position_in_byte {
-                    0 => 4, // First value in high nibble (bits 7-4)
-                    1 => 0, // Second value in low nibble (bits 3-0)
-                    _ => unreachable!(),
-                }
-            }
-            8 => 0, // 1 value per byte
-            _ => unreachable!(),
-        };
-
-        let mask = !(((1u8 << self.bits_per_status) - 1) << bit_shift);
-        bytes[byte_index] &= mask;
+    pub fn encode_status(
+        &self,
+        bytes: &mut [u8],
+        index: usize,
+        status: StatusType,
+    ) -> Result<(), BuilderError> {
+        let bits = BitsPerStatus::try_from(self.bits_per_status)
+            .map_err(|_| BuilderError::InvalidBitsPerStatus(self.bits_per_status))?;
+
+        bitops::encode_status(bytes, index, bits, status.value())
+            .map_err(|e| BuilderError::EncodingError(e.to_string()))
+    }
 
-        bytes[byte_index] |= status_value << bit_shift;
+    pub fn encode_statuses(&self, statuses: &[StatusType]) -> Result<Vec<u8>, BuilderError> {
+        let bits = BitsPerStatus::try_from(self.bits_per_status)
+            .map_err(|_| BuilderError::InvalidBitsPerStatus(self.bits_per_status))?;
+        let mut bytes = vec![0u8; bitops::packed_len(statuses.len(), bits)];
 
-        #[cfg(debug_assertions)]
-        println!(
-            "Encoding: index={}, byte={:08b}, shift={}, status={:?}, value={:08b}",
-            index, bytes[byte_index], bit_shift, status, status_value
-        );
+        for (i, status) in statuses.iter().enumerate() {
+            bitops::encode_status(&mut bytes, i, bits, status.value())
+                .map_err(|e| BuilderError::EncodingError(e.to_string()))?;
+        }
+        Ok(bytes)
     }
 
-    pub fn encode_statuses(&self, statuses: &[StatusType]) -> Result<Vec<u8>, BuilderError> {
-        match self.bits_per_status {
-            8 => Ok(statuses.iter().map(|status| *status as u8).collect()),
-            1 | 2 | 4 => {
-                let statuses_per_byte = (8 / self.bits_per_status) as usize;
-                let num_bytes = statuses.len().div_ceil(statuses_per_byte);
-                let mut bytes = vec![0u8; num_bytes];
-
-                for (i, status) in statuses.iter().enumerate() {
-                    self.encode_status(&mut bytes, i, *status);
-                }
-                Ok(bytes)
+    // Always compresses with flate2's zlib, since the Compressor trait is one-shot.
+    pub fn encode_iter<W: Write>(
+        &self,
+        statuses: impl IntoIterator<Item = StatusType>,
+        writer: W,
+    ) -> Result<(usize, W), BuilderError> {
+        let bits = BitsPerStatus::try_from(self.bits_per_status)
+            .map_err(|_| BuilderError::InvalidBitsPerStatus(self.bits_per_status))?;
+        let per_byte = bitops::statuses_per_byte(bits);
+
+        let mut encoder = ZlibEncoder::new(writer, Compression::best());
+        let mut chunk = [0u8; 4096];
+        let mut count = 0;
+        for status in statuses {
+            let offset = count % (chunk.len() * per_byte);
+            bitops::encode_status(&mut chunk, offset, bits, status.value())
+                .map_err(|e| BuilderError::EncodingError(e.to_string()))?;
+            count += 1;
+
+            if offset + 1 == chunk.len() * per_byte {
+                encoder
+                    .write_all(&chunk)
+                    .map_err(|e| BuilderError::CompressionError(e.to_string()))?;
+                chunk = [0u8; 4096];
             }
-            _ => Err(BuilderError::InvalidBitsPerStatus(self.bits_per_status)),
         }
+
+        let pending = count % (chunk.len() * per_byte);
+        if pending > 0 {
+            encoder
+                .write_all(&chunk[..pending.div_ceil(per_byte)])
+                .map_err(|e| BuilderError::CompressionError(e.to_string()))?;
+        }
+        let writer = encoder
+            .finish()
+            .map_err(|e| BuilderError::CompressionError(e.to_string()))?;
+        Ok((count, writer))
     }
 
     pub fn finalize(&self, bytes: &[u8]) -> Result<StatusList, BuilderError> {
-        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
-        encoder
-            .write_all(bytes)
-            .map_err(|e| BuilderError::CompressionError(e.to_string()))?;
+        BitsPerStatus::try_from(self.bits_per_status)
+            .map_err(|_| BuilderError::InvalidBitsPerStatus(self.bits_per_status))?;
 
-        let compressed = encoder
-            .finish()
-            .map_err(|e| BuilderError::CompressionError(e.to_string()))?;
+        let compressed = self
+            .compressor
+            .compress(bytes)
+            .map_err(BuilderError::CompressionError)?;
 
-        Ok(StatusList {
-            bits: self.bits_per_status,
-            lst: compressed,
-            aggregation_uri: None,
-        })
+        // Custom compressors must still produce ZLIB, as the specification requires.
+        StatusList::from_compressed(self.bits_per_status, compressed)
+            .map_err(|e| BuilderError::CompressionError(e.to_string()))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "decoder")]
     use crate::decoder::StatusListDecoder;
-    use crate::error::BuilderError;
+    use crate::error::{BuilderError, StatusTypeError};
     use crate::types::StatusType;
 
+    #[cfg(feature = "decoder")]
     #[test]
     fn test_direct_encoding() -> Result<(), BuilderError> {
         let encoder = StatusListEncoder::new(2);
@@ -160,7 +148,7 @@ mod tests {
         let test_cases = [
             (1, vec![StatusType::Valid, StatusType::Invalid], 0b00000010),
             (2, vec![StatusType::Valid, StatusType::Invalid], 0b00000100),
-            (4, vec![StatusType::Valid, StatusType::Invalid], 0b00000001),
+            (4, vec![StatusType::Valid, StatusType::Invalid], 0b00010000),
             (8, vec![StatusType::Valid, StatusType::Invalid], 0b00000000),
         ];
 
@@ -177,6 +165,7 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "decoder")]
     #[test]
     fn test_spec_1bit_example() -> Result<(), BuilderError> {
         let encoder = StatusListEncoder::new(1);
@@ -227,6 +216,7 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "decoder")]
     #[test]
     fn test_spec_8bit_example() -> Result<(), BuilderError> {
         let encoder = StatusListEncoder::new(8);
@@ -317,11 +307,11 @@ mod tests {
         ];
 
         let bytes = encoder.encode_statuses(&statuses)?;
-        assert_eq!(bytes[0], 0x12);
-        assert_eq!(bytes[1], 0x03);
-        assert_eq!(bytes[2], 0x01);
-        assert_eq!(bytes[3], 0x01);
-        assert_eq!(bytes[4], 0x12);
+        assert_eq!(bytes[0], 0x21);
+        assert_eq!(bytes[1], 0x30);
+        assert_eq!(bytes[2], 0x10);
+        assert_eq!(bytes[3], 0x10);
+        assert_eq!(bytes[4], 0x21);
         assert_eq!(bytes[5], 0x33);
 
         Ok(())
@@ -349,6 +339,7 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "decoder")]
     #[test]
     fn test_compression_and_encoding() -> Result<(), BuilderError> {
         let encoder = StatusListEncoder::new(2);
@@ -367,6 +358,7 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "decoder")]
     #[test]
     fn test_partial_byte() -> Result<(), BuilderError> {
         let encoder = StatusListEncoder::new(2);
@@ -403,6 +395,174 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_encode_status_in_place() -> Result<(), BuilderError> {
+        let encoder = StatusListEncoder::new(2);
+        let mut bytes = encoder.encode_statuses(&[StatusType::Valid; 4])?;
+
+        encoder.encode_status(&mut bytes, 2, StatusType::Suspended)?;
+        assert_eq!(bytes[0], 0b00100000);
+
+        match encoder.encode_status(&mut bytes, 4, StatusType::Invalid) {
+            Err(BuilderError::EncodingError(msg)) => assert!(msg.contains("Invalid Byte Index")),
+            _ => panic!("Expected EncodingError"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_status_wider_than_bits() {
+        let encoder = StatusListEncoder::new(1);
+
+        match encoder.encode_statuses(&[StatusType::Suspended]) {
+            Err(BuilderError::EncodingError(msg)) => {
+                assert!(msg.contains("cannot be represented with 1 bits"))
+            }
+            _ => panic!("Expected EncodingError"),
+        }
+    }
+
+    #[test]
+    fn test_from_packed_bytes_compresses() -> Result<(), BuilderError> {
+        let status_list = StatusList::from_packed_bytes(1, &[0xB9, 0xA3])?;
+
+        assert_eq!(status_list.bits, 1);
+        assert_eq!(
+            status_list.lst,
+            vec![0x78, 0xda, 0xdb, 0xb9, 0x18, 0x00, 0x02, 0x17, 0x01, 0x5d]
+        );
+        assert_eq!(status_list.aggregation_uri, None);
+
+        match StatusList::from_packed_bytes(3, &[0x00]) {
+            Err(BuilderError::InvalidBitsPerStatus(3)) => (),
+            _ => panic!("Expected InvalidBitsPerStatus error"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_crate_produced_lists_are_zlib() -> Result<(), BuilderError> {
+        use flate2::read::ZlibDecoder;
+        use std::io::Read;
+
+        let statuses = vec![StatusType::Invalid, StatusType::Valid, StatusType::Invalid];
+        let encoder = StatusListEncoder::new(1);
+        let bytes = encoder.encode_statuses(&statuses)?;
+        let produced = [
+            encoder.finalize(&bytes)?,
+            StatusList::from_packed_bytes(1, &bytes)?,
+            crate::builder::StatusListBuilder::from_vec(statuses, 1)
+                .unwrap()
+                .build()?,
+        ];
+
+        for status_list in produced {
+            let mut raw_bytes = Vec::new();
+            ZlibDecoder::new(&status_list.lst[..])
+                .read_to_end(&mut raw_bytes)
+                .map_err(|e| BuilderError::CompressionError(e.to_string()))?;
+            assert_eq!(raw_bytes, bytes);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_finalize_rejects_invalid_bits() {
+        match StatusListEncoder::new(5).finalize(&[0x00]) {
+            Err(BuilderError::InvalidBitsPerStatus(5)) => (),
+            _ => panic!("Expected InvalidBitsPerStatus error"),
+        }
+    }
+
+    #[test]
+    fn test_encode_iter_matches_finalize() -> Result<(), BuilderError> {
+        for bits in [1, 2, 4, 8] {
+            let encoder = StatusListEncoder::new(bits);
+            let statuses: Vec<StatusType> = (0..20_001)
+                .map(|i| match i % 5 {
+                    0 | 3 => StatusType::Invalid,
+                    _ => StatusType::Valid,
+                })
+                .collect();
+
+            let (count, streamed) = encoder.encode_iter(statuses.iter().copied(), Vec::new())?;
+            let bytes = encoder.encode_statuses(&statuses)?;
+
+            assert_eq!(count, statuses.len());
+            assert_eq!(streamed, encoder.finalize(&bytes)?.lst);
+        }
+
+        let (count, empty) = StatusListEncoder::new(1).encode_iter(Vec::new(), Vec::new())?;
+        assert_eq!(count, 0);
+        assert_eq!(empty, StatusListEncoder::new(1).finalize(&[])?.lst);
+
+        match StatusListEncoder::new(1).encode_iter([StatusType::Suspended], Vec::new()) {
+            Err(BuilderError::EncodingError(_)) => (),
+            _ => panic!("Expected EncodingError"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_compressor() -> Result<(), BuilderError> {
+        #[derive(Debug)]
+        struct Identity;
+
+        impl Compressor for Identity {
+            fn compress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+                Ok(data.to_vec())
+            }
+        }
+
+        #[derive(Debug)]
+        struct Failing;
+
+        impl Compressor for Failing {
+            fn compress(&self, _data: &[u8]) -> Result<Vec<u8>, String> {
+                Err("offload unavailable".to_string())
+            }
+        }
+
+        #[derive(Debug)]
+        struct Offloaded;
+
+        impl Compressor for Offloaded {
+            fn compress(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+                ZlibCompression::new(1).compress(data)
+            }
+        }
+
+        let status_list = StatusListEncoder::new(1)
+            .with_compressor(Offloaded)
+            .finalize(&[0xB9, 0xA3])?;
+        assert_eq!(
+            status_list.lst,
+            ZlibCompression::new(1).compress(&[0xB9, 0xA3]).unwrap()
+        );
+
+        match StatusListEncoder::new(1)
+            .with_compressor(Identity)
+            .finalize(&[0xB9, 0xA3])
+        {
+            Err(BuilderError::CompressionError(msg)) => assert!(msg.contains("ZLIB")),
+            _ => panic!("Expected CompressionError"),
+        }
+
+        match StatusListEncoder::new(1)
+            .with_compressor(Failing)
+            .finalize(&[0xB9])
+        {
+            Err(BuilderError::CompressionError(msg)) => assert_eq!(msg, "offload unavailable"),
+            _ => panic!("Expected CompressionError"),
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_encoder_compression_error() {
         let encoder = StatusListEncoder::new(2);
@@ -422,6 +582,8 @@ mod tests {
             BuilderError::InvalidBitsPerStatus(3),
             BuilderError::CompressionError("test error".to_string()),
             BuilderError::EncodingError("encoding failed".to_string()),
+            BuilderError::MaxLenExceeded(8),
+            BuilderError::StatusTypeError(StatusTypeError::UnknownStatusName("X".to_string())),
         ];
 
         for error in errors {
@@ -436,6 +598,12 @@ mod tests {
                 BuilderError::EncodingError(_) => {
                     assert!(error_string.contains("Encoding error"));
                 }
+                BuilderError::MaxLenExceeded(_) => {
+                    assert!(error_string.contains("maximum length of 8 statuses"));
+                }
+                BuilderError::StatusTypeError(_) => {
+                    assert!(error_string.contains("No status is registered as X"));
+                }
             }
         }
     }
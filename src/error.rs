@@ -3,17 +3,17 @@ use std::fmt;
 
 #[derive(Debug)]
 pub enum StatusTypeError {
-    UndefinedStatusType(u8),
     InvalidByteIndex(usize),
     InvalidBitsPerStatus(u8),
+    StatusNotRepresentable(u8, u8),
+    NotZlibCompressed,
+    UnknownStatusName(String),
+    InvalidBase64(String),
 }
 
 impl fmt::Display for StatusTypeError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            StatusTypeError::UndefinedStatusType(x) => {
-                write!(f, "Undefined Status Type {}", x)
-            }
             StatusTypeError::InvalidByteIndex(x) => {
                 write!(f, "Invalid Byte Index {}", x)
             }
@@ -24,6 +24,22 @@ impl fmt::Display for StatusTypeError {
                     x
                 )
             }
+            StatusTypeError::StatusNotRepresentable(status, bits) => {
+                write!(
+                    f,
+                    "Status type {} cannot be represented with {} bits per status",
+                    status, bits
+                )
+            }
+            StatusTypeError::NotZlibCompressed => {
+                write!(f, "Status list is not ZLIB compressed")
+            }
+            StatusTypeError::UnknownStatusName(name) => {
+                write!(f, "No status is registered as {}", name)
+            }
+            StatusTypeError::InvalidBase64(msg) => {
+                write!(f, "Invalid base64url status list: {}", msg)
+            }
         }
     }
 }
@@ -35,6 +51,8 @@ pub enum BuilderError {
     InvalidBitsPerStatus(u8),
     CompressionError(String),
     EncodingError(String),
+    MaxLenExceeded(usize),
+    StatusTypeError(StatusTypeError),
 }
 
 impl fmt::Display for BuilderError {
@@ -53,40 +71,757 @@ impl fmt::Display for BuilderError {
             BuilderError::EncodingError(msg) => {
                 write!(f, "Encoding error: {}", msg)
             }
+            BuilderError::MaxLenExceeded(max_len) => write!(
+                f,
+                "Status list would grow past its maximum length of {} statuses",
+                max_len
+            ),
+            BuilderError::StatusTypeError(e) => write!(f, "{}", e),
         }
     }
 }
 
-impl Error for BuilderError {}
+impl Error for BuilderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BuilderError::StatusTypeError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
+#[cfg(feature = "encoder")]
 #[derive(Debug)]
+pub enum ApplyError {
+    BuilderError(BuilderError),
+    /// The progress callback stopped the replay after `applied` events.
+    Aborted {
+        applied: usize,
+        reason: String,
+    },
+}
+
+#[cfg(feature = "encoder")]
+impl fmt::Display for ApplyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApplyError::BuilderError(e) => write!(f, "{}", e),
+            ApplyError::Aborted { applied, reason } => {
+                write!(
+                    f,
+                    "Event replay aborted after {} events: {}",
+                    applied, reason
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "encoder")]
+impl Error for ApplyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ApplyError::BuilderError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "encoder")]
+#[derive(Debug)]
+pub enum TransitionError {
+    AlreadyRevoked(usize),
+    UnexpectedStatus(usize, u8),
+    BuilderError(BuilderError),
+}
+
+#[cfg(feature = "encoder")]
+impl fmt::Display for TransitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransitionError::AlreadyRevoked(idx) => {
+                write!(f, "Status at index {} is revoked and cannot change", idx)
+            }
+            TransitionError::UnexpectedStatus(idx, value) => write!(
+                f,
+                "Status at index {} has value {}, expected Valid or Suspended",
+                idx, value
+            ),
+            TransitionError::BuilderError(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[cfg(feature = "encoder")]
+impl Error for TransitionError {}
+
+#[cfg(feature = "decoder")]
+#[derive(Debug, Clone)]
 pub enum DecoderError {
     Base64Error(String),
     DecompressionError(String),
     InvalidByteIndex(usize),
+    IndexNotPresent(usize),
     InvalidStatusType(u8),
+    InvalidBitsPerStatus(u8),
+    BitsMismatch(u8, u8),
     StatusListCreationError(String),
     SerializationError(String),
+    BufferTooSmall(usize, usize),
 }
 
+#[cfg(feature = "decoder")]
 impl std::fmt::Display for DecoderError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             DecoderError::Base64Error(msg) => write!(f, "Base64 decoding error: {}", msg),
             DecoderError::DecompressionError(msg) => write!(f, "ZLIB decompression error: {}", msg),
             DecoderError::InvalidByteIndex(idx) => write!(f, "Invalid byte index: {}", idx),
+            DecoderError::IndexNotPresent(idx) => {
+                write!(f, "Index {} is not present in the status list", idx)
+            }
             DecoderError::InvalidStatusType(val) => write!(f, "Invalid status type value: {}", val),
+            DecoderError::InvalidBitsPerStatus(bits) => write!(
+                f,
+                "Invalid bits per status value: {}. Must be 1, 2, 4, or 8",
+                bits
+            ),
+            DecoderError::BitsMismatch(expected, found) => write!(
+                f,
+                "Bits per status mismatch: expected {}, found {}",
+                expected, found
+            ),
             DecoderError::StatusListCreationError(msg) => {
                 write!(f, "Status list creation error: {}", msg)
             }
             DecoderError::SerializationError(msg) => write!(f, "Serialization error: {}", msg),
+            DecoderError::BufferTooSmall(needed, got) => write!(
+                f,
+                "Buffer of {} bytes is too small for {} raw bytes",
+                got, needed
+            ),
         }
     }
 }
 
+#[cfg(feature = "decoder")]
 impl Error for DecoderError {}
 
-#[cfg(test)]
+#[cfg(feature = "uri")]
+#[derive(Debug)]
+pub enum UriError {
+    InvalidUri(String),
+    UnsupportedScheme(String),
+    Mismatch(String, String, String),
+}
+
+#[cfg(feature = "uri")]
+impl fmt::Display for UriError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UriError::InvalidUri(msg) => write!(f, "Invalid status list URI: {}", msg),
+            UriError::UnsupportedScheme(scheme) => {
+                write!(f, "Unsupported status list URI scheme: {}", scheme)
+            }
+            UriError::Mismatch(expected, found, detail) => write!(
+                f,
+                "Status list URI mismatch: expected {}, found {} ({})",
+                expected, found, detail
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "uri")]
+impl Error for UriError {}
+
+#[cfg(feature = "uri")]
+#[derive(Debug)]
+pub enum ReferenceError {
+    UnknownList(String),
+    IndexOutOfRange(usize),
+    Unallocated(usize),
+}
+
+#[cfg(feature = "uri")]
+impl fmt::Display for ReferenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReferenceError::UnknownList(uri) => {
+                write!(f, "Status reference points at unknown list {}", uri)
+            }
+            ReferenceError::IndexOutOfRange(idx) => {
+                write!(f, "Status reference index {} is outside the list", idx)
+            }
+            ReferenceError::Unallocated(idx) => {
+                write!(f, "Status reference index {} has not been allocated", idx)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "uri")]
+impl Error for ReferenceError {}
+
+#[cfg(feature = "bitstring")]
+#[derive(Debug)]
+pub enum BitstringError {
+    InvalidStatusSize(u8),
+    EncodingError(String),
+    DecodingError(String),
+}
+
+#[cfg(feature = "bitstring")]
+impl fmt::Display for BitstringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BitstringError::InvalidStatusSize(size) => write!(
+                f,
+                "Unsupported bitstring status size {}. Must be 1, 2, 4, or 8",
+                size
+            ),
+            BitstringError::EncodingError(msg) => write!(f, "Bitstring encoding error: {}", msg),
+            BitstringError::DecodingError(msg) => write!(f, "Bitstring decoding error: {}", msg),
+        }
+    }
+}
+
+#[cfg(feature = "bitstring")]
+impl Error for BitstringError {}
+
+#[cfg(all(feature = "encoder", feature = "uri"))]
+#[derive(Debug)]
+pub enum StatusListSetError {
+    UnknownList(String),
+    UnknownProfile(String),
+    UnknownTenant(String),
+    DuplicateTenant(String),
+    /// The first tenant's base URI nests within the second's, or the reverse.
+    OverlappingTenants(String, String),
+    IdempotencyConflict(String),
+    #[cfg(feature = "mapping")]
+    MappingError(MappingError),
+    UriError(UriError),
+    AllocatorError(AllocatorError),
+    ReferenceError(ReferenceError),
+    BuilderError(BuilderError),
+}
+
+#[cfg(all(feature = "encoder", feature = "uri"))]
+impl fmt::Display for StatusListSetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatusListSetError::UnknownList(uri) => {
+                write!(f, "Status list {} is not part of this set", uri)
+            }
+            StatusListSetError::UnknownProfile(name) => {
+                write!(f, "No list profile named {}", name)
+            }
+            StatusListSetError::UnknownTenant(tenant) => write!(f, "Unknown tenant {}", tenant),
+            StatusListSetError::DuplicateTenant(tenant) => {
+                write!(f, "Tenant {} is already registered", tenant)
+            }
+            StatusListSetError::OverlappingTenants(tenant, other) => write!(
+                f,
+                "Tenant {} has a base URI that overlaps tenant {}",
+                tenant, other
+            ),
+            StatusListSetError::IdempotencyConflict(key) => write!(
+                f,
+                "Idempotency key {} was already used for a different change",
+                key
+            ),
+            #[cfg(feature = "mapping")]
+            StatusListSetError::MappingError(e) => write!(f, "{}", e),
+            StatusListSetError::UriError(e) => write!(f, "{}", e),
+            StatusListSetError::AllocatorError(e) => write!(f, "{}", e),
+            StatusListSetError::ReferenceError(e) => write!(f, "{}", e),
+            StatusListSetError::BuilderError(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[cfg(all(feature = "encoder", feature = "uri"))]
+impl Error for StatusListSetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            StatusListSetError::UnknownList(_)
+            | StatusListSetError::UnknownProfile(_)
+            | StatusListSetError::UnknownTenant(_)
+            | StatusListSetError::DuplicateTenant(_)
+            | StatusListSetError::OverlappingTenants(_, _)
+            | StatusListSetError::IdempotencyConflict(_) => None,
+            #[cfg(feature = "mapping")]
+            StatusListSetError::MappingError(e) => Some(e),
+            StatusListSetError::UriError(e) => Some(e),
+            StatusListSetError::AllocatorError(e) => Some(e),
+            StatusListSetError::ReferenceError(e) => Some(e),
+            StatusListSetError::BuilderError(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(feature = "changelog")]
+#[derive(Debug)]
+pub enum ChangelogError {
+    EncodingError(String),
+    SignatureError(String),
+    InvalidVersionRange(u64, u64),
+    InvalidEntry(usize),
+    UriMismatch(String),
+    UnexpectedBits(u8, u8),
+}
+
+#[cfg(feature = "changelog")]
+impl fmt::Display for ChangelogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangelogError::EncodingError(msg) => write!(f, "Changelog encoding error: {}", msg),
+            ChangelogError::SignatureError(msg) => {
+                write!(f, "Changelog signature error: {}", msg)
+            }
+            ChangelogError::InvalidVersionRange(from, to) => {
+                write!(f, "Invalid changelog version range: {} -> {}", from, to)
+            }
+            ChangelogError::InvalidEntry(idx) => {
+                write!(f, "Invalid changelog entry for index {}", idx)
+            }
+            ChangelogError::UriMismatch(msg) => write!(f, "Changelog is for another list: {}", msg),
+            ChangelogError::UnexpectedBits(expected, found) => write!(
+                f,
+                "Changelog uses {} bits per status, expected {}",
+                found, expected
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "changelog")]
+impl Error for ChangelogError {}
+
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub enum PatchError {
+    EncodingError(String),
+    BitsMismatch(u8, u8),
+    InvalidEntry(usize),
+    RemovedIndex(usize),
+    BuilderError(BuilderError),
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::EncodingError(msg) => write!(f, "Patch encoding error: {}", msg),
+            PatchError::BitsMismatch(expected, found) => write!(
+                f,
+                "Patch bits per status mismatch: expected {}, found {}",
+                expected, found
+            ),
+            PatchError::InvalidEntry(idx) => write!(f, "Invalid patch entry for index {}", idx),
+            PatchError::RemovedIndex(idx) => {
+                write!(f, "Index {} was removed, which a patch cannot express", idx)
+            }
+            PatchError::BuilderError(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Error for PatchError {}
+
+#[derive(Debug)]
+pub enum AllocatorError {
+    Exhausted(usize),
+    IndexOutOfRange(usize),
+    AlreadyAllocated(usize),
+    InvalidState(String),
+}
+
+impl fmt::Display for AllocatorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AllocatorError::Exhausted(capacity) => {
+                write!(f, "All {} indices have been allocated", capacity)
+            }
+            AllocatorError::IndexOutOfRange(idx) => {
+                write!(f, "Index {} is outside the allocator's capacity", idx)
+            }
+            AllocatorError::AlreadyAllocated(idx) => {
+                write!(f, "Index {} is already allocated", idx)
+            }
+            AllocatorError::InvalidState(msg) => write!(f, "Invalid allocator state: {}", msg),
+        }
+    }
+}
+
+impl Error for AllocatorError {}
+
+#[cfg(feature = "mapping")]
+#[derive(Debug)]
+pub enum MappingError {
+    DuplicateId(String),
+    IndexTaken(String, usize),
+    StorageError(String),
+}
+
+#[cfg(feature = "mapping")]
+impl fmt::Display for MappingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MappingError::DuplicateId(id) => write!(f, "Credential ID {} is already mapped", id),
+            MappingError::IndexTaken(uri, idx) => {
+                write!(f, "Index {} of {} is already mapped", idx, uri)
+            }
+            MappingError::StorageError(msg) => write!(f, "Mapping storage error: {}", msg),
+        }
+    }
+}
+
+#[cfg(feature = "mapping")]
+impl Error for MappingError {}
+
+#[cfg(feature = "journal")]
+#[derive(Debug)]
+pub enum JournalError {
+    Corrupted(String),
+    StorageError(String),
+    InvalidIdempotencyKey(String),
+    /// The key was already used for a different change.
+    IdempotencyConflict(String),
+    FormatError(FormatError),
+    BuilderError(BuilderError),
+}
+
+#[cfg(feature = "journal")]
+impl fmt::Display for JournalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JournalError::Corrupted(msg) => write!(f, "Status journal is corrupted: {}", msg),
+            JournalError::StorageError(msg) => write!(f, "Status journal storage error: {}", msg),
+            JournalError::InvalidIdempotencyKey(key) => {
+                write!(f, "Invalid idempotency key {:?}", key)
+            }
+            JournalError::IdempotencyConflict(key) => write!(
+                f,
+                "Idempotency key {} was already used for a different change",
+                key
+            ),
+            JournalError::FormatError(e) => write!(f, "Status journal format error: {}", e),
+            JournalError::BuilderError(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[cfg(feature = "journal")]
+impl Error for JournalError {}
+
+#[cfg(feature = "test-utils")]
+#[derive(Debug)]
+pub enum LoadTestError {
+    BuilderError(BuilderError),
+    SigningError(String),
+    SerializationError(String),
+}
+
+#[cfg(feature = "test-utils")]
+impl fmt::Display for LoadTestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadTestError::BuilderError(e) => write!(f, "Load test build failed: {}", e),
+            LoadTestError::SigningError(msg) => write!(f, "Load test signing failed: {}", msg),
+            LoadTestError::SerializationError(msg) => {
+                write!(f, "Load test serialization failed: {}", msg)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "test-utils")]
+impl Error for LoadTestError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadTestError::BuilderError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "store")]
+#[derive(Debug)]
+pub enum StoreError {
+    Empty,
+    Corrupted(String),
+    StorageError(String),
+    FormatError(FormatError),
+}
+
+#[cfg(feature = "store")]
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Empty => write!(f, "Status list store holds no list"),
+            StoreError::Corrupted(msg) => write!(f, "Stored status list is corrupted: {}", msg),
+            StoreError::StorageError(msg) => write!(f, "Status list storage error: {}", msg),
+            StoreError::FormatError(e) => write!(f, "Status list store format error: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "store")]
+impl Error for StoreError {}
+
+#[derive(Debug)]
+pub enum FormatError {
+    UnknownFormat(String),
+    /// The artifact kind and the version it was found in or asked for.
+    UnsupportedVersion(String, u32),
+    StorageError(String),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::UnknownFormat(msg) => write!(f, "Unknown persisted format: {}", msg),
+            FormatError::UnsupportedVersion(kind, version) => write!(
+                f,
+                "Unsupported {} format version {}; migrate() converts between versions",
+                kind, version
+            ),
+            FormatError::StorageError(msg) => write!(f, "Format migration storage error: {}", msg),
+        }
+    }
+}
+
+impl Error for FormatError {}
+
+#[cfg(feature = "mmap")]
+#[derive(Debug)]
+pub enum MmapError {
+    StorageError(String),
+    InvalidFile(String),
+    DecoderError(DecoderError),
+}
+
+#[cfg(feature = "mmap")]
+impl fmt::Display for MmapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MmapError::StorageError(msg) => write!(f, "Mapped status list storage error: {}", msg),
+            MmapError::InvalidFile(msg) => write!(f, "Invalid mapped status list file: {}", msg),
+            MmapError::DecoderError(e) => write!(f, "Mapped status list decoding error: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl Error for MmapError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MmapError::DecoderError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "reasons")]
+#[derive(Debug)]
+pub enum ReasonsError {
+    EncodingError(String),
+    SignatureError(String),
+}
+
+#[cfg(feature = "reasons")]
+impl fmt::Display for ReasonsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReasonsError::EncodingError(msg) => write!(f, "Status reasons encoding error: {}", msg),
+            ReasonsError::SignatureError(msg) => {
+                write!(f, "Status reasons signature error: {}", msg)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "reasons")]
+impl Error for ReasonsError {}
+
+#[cfg(feature = "cwt")]
+#[derive(Debug, Clone)]
+pub enum TokenError {
+    EncodingError(String),
+    SignatureError(String),
+    MissingClaim(String),
+    InvalidClaim(String),
+    SubjectMismatch(String),
+    NotYetValid(u64),
+    Expired(u64),
+    Stale(u64),
+    StatusUnavailable(usize),
+    IndexNotPresent(usize),
+    Rollback(u64),
+    UnexpectedBits(u8, u8),
+}
+
+#[cfg(feature = "cwt")]
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenError::EncodingError(msg) => {
+                write!(f, "Status list token encoding error: {}", msg)
+            }
+            TokenError::SignatureError(msg) => {
+                write!(f, "Status list token signature error: {}", msg)
+            }
+            TokenError::MissingClaim(claim) => {
+                write!(f, "Status list token is missing the {} claim", claim)
+            }
+            TokenError::InvalidClaim(claim) => {
+                write!(f, "Status list token has an invalid {} claim", claim)
+            }
+            TokenError::SubjectMismatch(msg) => {
+                write!(f, "Status list token subject mismatch: {}", msg)
+            }
+            TokenError::NotYetValid(iat) => {
+                write!(f, "Status list token is issued in the future (iat {})", iat)
+            }
+            TokenError::Expired(at) => write!(f, "Status list token expired at {}", at),
+            TokenError::Stale(at) => write!(f, "Status list token went stale at {}", at),
+            TokenError::StatusUnavailable(idx) => {
+                write!(f, "Status list token has no status at index {}", idx)
+            }
+            TokenError::IndexNotPresent(idx) => {
+                write!(f, "Index {} is past the end of the status list", idx)
+            }
+            TokenError::Rollback(iat) => write!(
+                f,
+                "Status list token issued at {} is older than one already seen",
+                iat
+            ),
+            TokenError::UnexpectedBits(expected, found) => write!(
+                f,
+                "Status list token uses {} bits per status, expected {}",
+                found, expected
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "cwt")]
+impl Error for TokenError {}
+
+#[cfg(all(feature = "cwt", feature = "decoder"))]
+#[derive(Debug, Clone)]
+pub enum ReadinessError {
+    TokenError(TokenError),
+    NearStale(u64),
+}
+
+#[cfg(all(feature = "cwt", feature = "decoder"))]
+impl fmt::Display for ReadinessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadinessError::TokenError(e) => write!(f, "{}", e),
+            ReadinessError::NearStale(stale_at) => write!(
+                f,
+                "Served status list token goes stale at {}, within the readiness margin",
+                stale_at
+            ),
+        }
+    }
+}
+
+#[cfg(all(feature = "cwt", feature = "decoder"))]
+impl Error for ReadinessError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReadinessError::TokenError(e) => Some(e),
+            ReadinessError::NearStale(_) => None,
+        }
+    }
+}
+
+#[cfg(any(feature = "http", feature = "wasm-fetch"))]
+#[derive(Debug, Clone)]
+pub enum ClientError {
+    RequestError(String),
+    HttpStatus(u16),
+    Retired(String),
+    UnexpectedContentType(String),
+    TokenError(TokenError),
+    DecoderError(DecoderError),
+}
+
+#[cfg(any(feature = "http", feature = "wasm-fetch"))]
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::RequestError(msg) => write!(f, "Status list request error: {}", msg),
+            ClientError::HttpStatus(status) => {
+                write!(f, "Status list request failed with HTTP status {}", status)
+            }
+            ClientError::Retired(uri) => write!(f, "Status list {} has been retired", uri),
+            ClientError::UnexpectedContentType(content_type) => write!(
+                f,
+                "Status list response has unexpected content type '{}'",
+                content_type
+            ),
+            ClientError::TokenError(e) => write!(f, "{}", e),
+            ClientError::DecoderError(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[cfg(any(feature = "http", feature = "wasm-fetch"))]
+impl Error for ClientError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ClientError::TokenError(e) => Some(e),
+            ClientError::DecoderError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "uniffi")]
+#[derive(Debug, uniffi::Error)]
+#[uniffi(flat_error)]
+pub enum FfiError {
+    UriError(UriError),
+    SerializationError(crate::types::SerializationError),
+    DecoderError(DecoderError),
+    TokenError(TokenError),
+    ClientError(ClientError),
+}
+
+#[cfg(feature = "uniffi")]
+impl fmt::Display for FfiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FfiError::UriError(e) => write!(f, "{}", e),
+            FfiError::SerializationError(e) => write!(f, "{}", e),
+            FfiError::DecoderError(e) => write!(f, "{}", e),
+            FfiError::TokenError(e) => write!(f, "{}", e),
+            FfiError::ClientError(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[cfg(feature = "uniffi")]
+impl Error for FfiError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FfiError::UriError(e) => Some(e),
+            FfiError::SerializationError(e) => Some(e),
+            FfiError::DecoderError(e) => Some(e),
+            FfiError::TokenError(e) => Some(e),
+            FfiError::ClientError(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "decoder"))]
 mod tests {
     use super::*;
 
@@ -114,9 +849,13 @@ mod tests {
             DecoderError::Base64Error("invalid base64".to_string()),
             DecoderError::DecompressionError("failed to decompress".to_string()),
             DecoderError::InvalidByteIndex(42),
+            DecoderError::IndexNotPresent(42),
             DecoderError::InvalidStatusType(255),
+            DecoderError::InvalidBitsPerStatus(3),
+            DecoderError::BitsMismatch(1, 8),
             DecoderError::StatusListCreationError("invalid creation".to_string()),
             DecoderError::SerializationError("invalid json".to_string()),
+            DecoderError::BufferTooSmall(8, 4),
         ];
 
         for error in errors {
@@ -131,15 +870,27 @@ mod tests {
                 DecoderError::InvalidByteIndex(_) => {
                     assert!(error_string.contains("Invalid byte index"));
                 }
+                DecoderError::IndexNotPresent(_) => {
+                    assert!(error_string.contains("not present in the status list"));
+                }
                 DecoderError::InvalidStatusType(_) => {
                     assert!(error_string.contains("Invalid status type value"));
                 }
+                DecoderError::InvalidBitsPerStatus(_) => {
+                    assert!(error_string.contains("Invalid bits per status value"));
+                }
+                DecoderError::BitsMismatch(_, _) => {
+                    assert!(error_string.contains("Bits per status mismatch"));
+                }
                 DecoderError::StatusListCreationError(_) => {
                     assert!(error_string.contains("Status list creation error"));
                 }
                 DecoderError::SerializationError(_) => {
                     assert!(error_string.contains("Serialization error"));
                 }
+                DecoderError::BufferTooSmall(_, _) => {
+                    assert!(error_string.contains("too small"));
+                }
             }
         }
     }
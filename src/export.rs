@@ -0,0 +1,94 @@
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use crate::decoder::StatusListDecoder;
+
+// Escapes a status name for a JSON string. Registry names are arbitrary, and
+// decoder-only builds have no JSON library to lean on.
+fn json_string(value: &str, out: &mut String) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+impl StatusListDecoder {
+    /// Streams every status as a JSON Lines record such as
+    /// `{"idx":3,"status":"INVALID"}`, naming statuses with the decoder's
+    /// registry. Returns the number of records written.
+    pub fn export_jsonl(&self, mut out: impl Write) -> io::Result<usize> {
+        let mut line = String::new();
+        for index in 0..self.len() {
+            let status = self
+                .get_named_status(index)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+            line.clear();
+            let _ = write!(line, "{{\"idx\":{},\"status\":", index);
+            json_string(&status.to_string(), &mut line);
+            line.push_str("}\n");
+            out.write_all(line.as_bytes())?;
+        }
+        Ok(self.len())
+    }
+}
+
+#[cfg(all(test, feature = "encoder"))]
+mod tests {
+    use super::*;
+    use crate::registry::StatusRegistry;
+    use crate::types::{StatusList, StatusType};
+
+    #[test]
+    fn test_export_jsonl() {
+        let decoder =
+            StatusListDecoder::new(&StatusList::from_packed_bytes(2, &[0b1110_0100]).unwrap())
+                .unwrap()
+                .with_registry(
+                    StatusRegistry::default()
+                        .with_status(StatusType::ApplicationSpecific3, "ON \"HOLD\""),
+                );
+
+        let mut out = Vec::new();
+        assert_eq!(decoder.export_jsonl(&mut out).unwrap(), 4);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"idx\":0,\"status\":\"VALID\"}\n\
+             {\"idx\":1,\"status\":\"INVALID\"}\n\
+             {\"idx\":2,\"status\":\"SUSPENDED\"}\n\
+             {\"idx\":3,\"status\":\"ON \\\"HOLD\\\"\"}\n"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_export_jsonl_parses() {
+        let decoder = StatusListDecoder::new(&StatusList::from_packed_bytes(8, &[0x42]).unwrap())
+            .unwrap()
+            .with_registry(StatusRegistry::new());
+
+        let mut out = Vec::new();
+        decoder.export_jsonl(&mut out).unwrap();
+        let record: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(record["idx"], 0);
+        assert_eq!(record["status"], "0x42");
+
+        let mut line = String::new();
+        json_string("tab\there\u{1}", &mut line);
+        assert_eq!(
+            serde_json::from_str::<String>(&line).unwrap(),
+            "tab\there\u{1}"
+        );
+    }
+}
@@ -0,0 +1,305 @@
+use std::sync::Arc;
+
+use coset::iana::{self, EnumI64};
+
+use crate::client::{StatusListCache, StatusListClient};
+use crate::decoder::StatusListDecoder;
+use crate::error::FfiError;
+use crate::signing::SignatureVerifier;
+use crate::token::{ReplayWindow, StatusListTokenVerifier};
+use crate::types::StatusList;
+use crate::uri::StatusListUri;
+
+// Kotlin and Swift bindings, generated with uniffi-bindgen from the compiled
+// library. Indices cross the boundary as u64 since uniffi has no usize.
+
+/// Signature verification implemented by the host app, e.g. on top of
+/// CryptoKit or the Android keystore. `algorithm` is the COSE algorithm value
+/// (-7 for ES256).
+#[uniffi::export(with_foreign)]
+pub trait FfiSignatureVerifier: Send + Sync {
+    fn verify(&self, algorithm: i64, data: Vec<u8>, signature: Vec<u8>) -> bool;
+}
+
+struct ForeignVerifier(Arc<dyn FfiSignatureVerifier>);
+
+impl SignatureVerifier for ForeignVerifier {
+    fn verify(
+        &self,
+        algorithm: iana::Algorithm,
+        data: &[u8],
+        signature: &[u8],
+    ) -> Result<(), String> {
+        if !self
+            .0
+            .verify(algorithm.to_i64(), data.to_vec(), signature.to_vec())
+        {
+            return Err("signature rejected by verifier".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn parse_uri(uri: &str) -> Result<StatusListUri, FfiError> {
+    StatusListUri::parse(uri).map_err(FfiError::UriError)
+}
+
+#[derive(uniffi::Object)]
+pub struct FfiStatusListDecoder {
+    inner: Arc<StatusListDecoder>,
+}
+
+#[uniffi::export]
+impl FfiStatusListDecoder {
+    /// Decodes a `status_list` JSON object.
+    #[uniffi::constructor]
+    pub fn from_json(json: String) -> Result<Arc<Self>, FfiError> {
+        let status_list = StatusList::from_json(&json).map_err(FfiError::SerializationError)?;
+        Self::new(&status_list)
+    }
+
+    /// Decodes a CBOR-encoded status list, as found in a CWT.
+    #[uniffi::constructor]
+    pub fn from_cbor(cbor: Vec<u8>) -> Result<Arc<Self>, FfiError> {
+        let status_list = StatusList::from_cbor(&cbor).map_err(FfiError::SerializationError)?;
+        Self::new(&status_list)
+    }
+
+    pub fn get_status(&self, index: u64) -> Result<u8, FfiError> {
+        self.inner
+            .get_status(index as usize)
+            .map(|status| status.value())
+            .map_err(FfiError::DecoderError)
+    }
+
+    pub fn bits(&self) -> u8 {
+        self.inner.get_bits_per_status()
+    }
+
+    pub fn len(&self) -> u64 {
+        self.inner.len() as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
+impl FfiStatusListDecoder {
+    fn new(status_list: &StatusList) -> Result<Arc<Self>, FfiError> {
+        let decoder = StatusListDecoder::new(status_list).map_err(FfiError::DecoderError)?;
+        Ok(Arc::new(Self {
+            inner: Arc::new(decoder),
+        }))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct FfiStatusCheckReport {
+    pub uri: String,
+    pub idx: u64,
+    pub status: u8,
+    pub token_sha256: String,
+    pub token_iat: u64,
+    pub token_exp: Option<u64>,
+    pub checked_at: u64,
+    pub rollback_detected: bool,
+}
+
+/// Verifies CWT status list tokens the app fetched itself. Remembers the
+/// newest `iat` per list to flag rollbacks.
+#[derive(uniffi::Object)]
+pub struct FfiStatusListChecker {
+    token_verifier: StatusListTokenVerifier,
+    verifier: ForeignVerifier,
+}
+
+#[uniffi::export]
+impl FfiStatusListChecker {
+    #[uniffi::constructor]
+    pub fn new(
+        uri: String,
+        verifier: Arc<dyn FfiSignatureVerifier>,
+        leeway: u64,
+    ) -> Result<Arc<Self>, FfiError> {
+        Ok(Arc::new(Self {
+            token_verifier: StatusListTokenVerifier::new(parse_uri(&uri)?)
+                .with_leeway(leeway)
+                .with_replay_window(ReplayWindow::new()),
+            verifier: ForeignVerifier(verifier),
+        }))
+    }
+
+    /// Like `new`, for a list known to hold `known_len` statuses. Checks at
+    /// or past it fail with `TokenError::IndexNotPresent`.
+    #[uniffi::constructor]
+    pub fn with_known_len(
+        uri: String,
+        verifier: Arc<dyn FfiSignatureVerifier>,
+        leeway: u64,
+        known_len: u64,
+    ) -> Result<Arc<Self>, FfiError> {
+        Ok(Arc::new(Self {
+            token_verifier: StatusListTokenVerifier::new(parse_uri(&uri)?)
+                .with_leeway(leeway)
+                .with_replay_window(ReplayWindow::new())
+                .with_known_len(known_len as usize),
+            verifier: ForeignVerifier(verifier),
+        }))
+    }
+
+    pub fn check(
+        &self,
+        token: Vec<u8>,
+        index: u64,
+        now: u64,
+    ) -> Result<FfiStatusCheckReport, FfiError> {
+        let report = self
+            .token_verifier
+            .check_cwt(&token, &self.verifier, index as usize, now)
+            .map_err(FfiError::TokenError)?;
+        Ok(FfiStatusCheckReport {
+            uri: report.uri,
+            idx: report.idx as u64,
+            status: report.status,
+            token_sha256: report.token_sha256,
+            token_iat: report.token_iat,
+            token_exp: report.token_exp,
+            checked_at: report.checked_at,
+            rollback_detected: report.rollback_detected,
+        })
+    }
+
+    pub fn verify(&self, token: Vec<u8>, now: u64) -> Result<Arc<FfiStatusListDecoder>, FfiError> {
+        let decoder = self
+            .token_verifier
+            .verify_cwt(&token, &self.verifier, now)
+            .map_err(FfiError::TokenError)?;
+        Ok(Arc::new(FfiStatusListDecoder {
+            inner: Arc::new(decoder),
+        }))
+    }
+}
+
+/// Fetches, verifies and caches status list tokens over HTTPS.
+#[derive(uniffi::Object)]
+pub struct FfiStatusListCache {
+    cache: StatusListCache,
+    verifier: ForeignVerifier,
+}
+
+#[uniffi::export(async_runtime = "tokio")]
+impl FfiStatusListCache {
+    #[uniffi::constructor]
+    pub fn new(verifier: Arc<dyn FfiSignatureVerifier>, leeway: u64) -> Arc<Self> {
+        Arc::new(Self {
+            cache: StatusListCache::new(StatusListClient::new().with_leeway(leeway)),
+            verifier: ForeignVerifier(verifier),
+        })
+    }
+
+    pub async fn get(&self, uri: String, now: u64) -> Result<Arc<FfiStatusListDecoder>, FfiError> {
+        let decoder = self
+            .cache
+            .get(&parse_uri(&uri)?, &self.verifier, now)
+            .await
+            .map_err(FfiError::ClientError)?;
+        Ok(Arc::new(FfiStatusListDecoder { inner: decoder }))
+    }
+
+    pub async fn get_status(&self, uri: String, index: u64, now: u64) -> Result<u8, FfiError> {
+        self.get(uri, now).await?.get_status(index)
+    }
+
+    pub fn invalidate(&self, uri: String) -> Result<(), FfiError> {
+        self.cache.invalidate(&parse_uri(&uri)?);
+        Ok(())
+    }
+
+    pub fn len(&self) -> u64 {
+        self.cache.len() as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+#[cfg(all(test, feature = "encoder"))]
+mod tests {
+    use super::*;
+    use crate::builder::StatusListBuilder;
+    use crate::error::TokenError;
+    use crate::signing::tests::TestKey;
+    use crate::token::CwtStatusListToken;
+    use crate::types::StatusType;
+
+    impl FfiSignatureVerifier for TestKey {
+        fn verify(&self, algorithm: i64, data: Vec<u8>, signature: Vec<u8>) -> bool {
+            iana::Algorithm::from_i64(algorithm).is_some_and(|algorithm| {
+                SignatureVerifier::verify(self, algorithm, &data, &signature).is_ok()
+            })
+        }
+    }
+
+    fn status_list() -> StatusList {
+        StatusListBuilder::from_vec(vec![StatusType::Valid, StatusType::Invalid], 1)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_decoder() {
+        let decoder = FfiStatusListDecoder::from_json(status_list().to_json().unwrap()).unwrap();
+        assert_eq!(decoder.bits(), 1);
+        assert_eq!(decoder.get_status(1).unwrap(), 1);
+        assert!(matches!(
+            decoder.get_status(8),
+            Err(FfiError::DecoderError(_))
+        ));
+
+        let decoder =
+            FfiStatusListDecoder::from_cbor(status_list().to_cbor_bytes().unwrap()).unwrap();
+        assert_eq!(decoder.len(), 8);
+    }
+
+    #[test]
+    fn test_checker() {
+        let uri = "https://example.com/statuslists/1";
+        let sign = |iat| {
+            CwtStatusListToken::new(StatusListUri::parse(uri).unwrap(), iat, status_list())
+                .sign(&TestKey(1))
+                .unwrap()
+        };
+        let checker = FfiStatusListChecker::new(uri.to_string(), Arc::new(TestKey(1)), 0).unwrap();
+
+        let report = checker.check(sign(1000), 1, 1500).unwrap();
+        assert_eq!(report.status, 1);
+        assert_eq!(report.idx, 1);
+        assert!(!report.rollback_detected);
+        assert!(checker.check(sign(900), 1, 1500).unwrap().rollback_detected);
+
+        let mut tampered = sign(1000);
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(matches!(
+            checker.verify(tampered, 1500),
+            Err(FfiError::TokenError(_))
+        ));
+
+        assert!(matches!(
+            FfiStatusListChecker::new("not a uri".to_string(), Arc::new(TestKey(1)), 0),
+            Err(FfiError::UriError(_))
+        ));
+
+        let checker =
+            FfiStatusListChecker::with_known_len(uri.to_string(), Arc::new(TestKey(1)), 0, 2)
+                .unwrap();
+        assert_eq!(checker.check(sign(1000), 1, 1500).unwrap().status, 1);
+        assert!(matches!(
+            checker.check(sign(1000), 5, 1500),
+            Err(FfiError::TokenError(TokenError::IndexNotPresent(5)))
+        ));
+    }
+}
@@ -0,0 +1,358 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer};
+
+use crate::error::FormatError;
+
+/// The revision of the Token Status List draft the persisted formats follow.
+pub const SPEC_DRAFT: &str = "draft-ietf-oauth-status-list-13";
+
+/// The persisted artifacts that start with a `FormatHeader`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatKind {
+    /// A `SledStatusListStore` database.
+    Store,
+    /// A `FileJournal` file.
+    Journal,
+    /// A `StatusListSnapshot` or `TokenSnapshot` golden file, as text or JSON.
+    Snapshot,
+}
+
+impl FormatKind {
+    const ALL: [FormatKind; 3] = [FormatKind::Store, FormatKind::Journal, FormatKind::Snapshot];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FormatKind::Store => "tsl-store",
+            FormatKind::Journal => "tsl-journal",
+            FormatKind::Snapshot => "tsl-snapshot",
+        }
+    }
+
+    /// The version this build writes and reads. Version 0 is the headerless
+    /// layout written before the formats were versioned.
+    pub fn current_version(self) -> u32 {
+        1
+    }
+}
+
+// Written as "<kind> <version> <spec draft>", for example
+// "tsl-journal 1 draft-ietf-oauth-status-list-13".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatHeader {
+    pub kind: FormatKind,
+    pub version: u32,
+    pub spec: String,
+}
+
+impl FormatHeader {
+    pub fn current(kind: FormatKind) -> Self {
+        Self {
+            kind,
+            version: kind.current_version(),
+            spec: SPEC_DRAFT.to_string(),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, FormatError> {
+        let unknown = || FormatError::UnknownFormat(format!("not a format header: {:?}", text));
+        let mut fields = text.trim().split(' ');
+        let (Some(kind), Some(version), Some(spec), None) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Err(unknown());
+        };
+        Ok(Self {
+            kind: FormatKind::ALL
+                .into_iter()
+                .find(|candidate| candidate.name() == kind)
+                .ok_or_else(unknown)?,
+            version: version.parse().map_err(|_| unknown())?,
+            spec: spec.to_string(),
+        })
+    }
+
+    /// Checks the header a loader found for an artifact of `kind`. A missing
+    /// or unparsable header means the artifact predates versioning.
+    #[cfg(any(feature = "journal", feature = "sled"))]
+    pub(crate) fn expect(kind: FormatKind, header: Option<&str>) -> Result<(), FormatError> {
+        let Some(header) = header.and_then(|header| Self::parse(header).ok()) else {
+            return Err(FormatError::UnsupportedVersion(kind.name().to_string(), 0));
+        };
+        if header.kind != kind {
+            return Err(FormatError::UnknownFormat(format!(
+                "expected a {} header, found {}",
+                kind.name(),
+                header.kind.name()
+            )));
+        }
+        if header.version != kind.current_version() {
+            return Err(FormatError::UnsupportedVersion(
+                kind.name().to_string(),
+                header.version,
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for FormatHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.kind.name(), self.version, self.spec)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for FormatHeader {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// What `migrate` found and wrote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    pub kind: FormatKind,
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+// How the header is embedded in a text artifact.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Layout {
+    // The header is the first line.
+    Line,
+    // A "format: <header>" first line.
+    Field,
+    // A "format" member first in the top-level JSON object.
+    Json,
+}
+
+const JSON_MEMBER: &str = "\"format\":";
+
+/// Rewrites the journal, snapshot or sled store at `path` in format
+/// `to_version`, which may be older than the current version to roll back.
+/// Nothing may have the artifact open while it is migrated. Text files are
+/// replaced by rename, so a crash leaves either the old or the new file.
+pub fn migrate(path: impl AsRef<Path>, to_version: u32) -> Result<Migration, FormatError> {
+    let path = path.as_ref();
+    if path.is_dir() {
+        #[cfg(feature = "sled")]
+        return crate::store::migrate_sled(path, to_version);
+        #[cfg(not(feature = "sled"))]
+        return Err(FormatError::UnknownFormat(
+            "store migration needs the sled feature".to_string(),
+        ));
+    }
+
+    let contents = fs::read_to_string(path).map_err(storage_error)?;
+    let (kind, from_version, layout, body) = identify(&contents)?;
+    check_target(kind, to_version)?;
+    let migration = Migration {
+        kind,
+        from_version,
+        to_version,
+    };
+    if from_version == to_version {
+        return Ok(migration);
+    }
+
+    let header = FormatHeader {
+        version: to_version,
+        ..FormatHeader::current(kind)
+    };
+    let migrated = match (to_version, layout) {
+        (0, _) => body,
+        (_, Layout::Line) => format!("{}\n{}", header, body),
+        (_, Layout::Field) => format!("format: {}\n{}", header, body),
+        (_, Layout::Json) => {
+            let (open, members) = body.split_at(body.find('{').map_or(0, |pos| pos + 1));
+            let separator = if members.trim_start().starts_with('}') {
+                ""
+            } else {
+                ","
+            };
+            format!(
+                "{}{}\"{}\"{}{}",
+                open, JSON_MEMBER, header, separator, members
+            )
+        }
+    };
+
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    fs::write(&tmp_path, migrated)
+        .and_then(|()| fs::rename(&tmp_path, path))
+        .map_err(storage_error)?;
+    Ok(migration)
+}
+
+pub(crate) fn check_target(kind: FormatKind, to_version: u32) -> Result<(), FormatError> {
+    if to_version > kind.current_version() {
+        return Err(FormatError::UnsupportedVersion(
+            kind.name().to_string(),
+            to_version,
+        ));
+    }
+    Ok(())
+}
+
+fn storage_error(error: std::io::Error) -> FormatError {
+    FormatError::StorageError(error.to_string())
+}
+
+// Returns the kind, version and layout of a text artifact along with its
+// contents minus the header.
+fn identify(contents: &str) -> Result<(FormatKind, u32, Layout, String), FormatError> {
+    let trimmed = contents.trim_start();
+    if let Some(object) = trimmed.strip_prefix('{') {
+        let Some(member) = object.trim_start().strip_prefix(JSON_MEMBER) else {
+            return Ok((FormatKind::Snapshot, 0, Layout::Json, contents.to_string()));
+        };
+        let malformed = || FormatError::UnknownFormat("malformed format member".to_string());
+        let value = member
+            .trim_start()
+            .strip_prefix('"')
+            .ok_or_else(malformed)?;
+        let (header, rest) = value.split_once('"').ok_or_else(malformed)?;
+        let header = FormatHeader::parse(header)?;
+        let rest = rest.trim_start();
+        let open = &contents[..contents.len() - object.len()];
+        let body = format!("{}{}", open, rest.strip_prefix(',').unwrap_or(rest));
+        return Ok((header.kind, header.version, Layout::Json, body));
+    }
+
+    let (first, rest) = contents.split_once('\n').unwrap_or((contents, ""));
+    if let Some(header) = first.strip_prefix("format: ") {
+        let header = FormatHeader::parse(header)?;
+        return Ok((header.kind, header.version, Layout::Field, rest.to_string()));
+    }
+    if let Ok(header) = FormatHeader::parse(first) {
+        return Ok((header.kind, header.version, Layout::Line, rest.to_string()));
+    }
+    if first.starts_with("bits: ") || first.starts_with("sub: ") {
+        return Ok((FormatKind::Snapshot, 0, Layout::Field, contents.to_string()));
+    }
+    if first.starts_with(|c: char| c.is_ascii_digit()) {
+        return Ok((FormatKind::Journal, 0, Layout::Line, contents.to_string()));
+    }
+    Err(FormatError::UnknownFormat(
+        "not a journal or snapshot file".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_round_trip() {
+        let header = FormatHeader::current(FormatKind::Journal);
+        assert_eq!(
+            header.to_string(),
+            "tsl-journal 1 draft-ietf-oauth-status-list-13"
+        );
+        assert_eq!(FormatHeader::parse(&header.to_string()).unwrap(), header);
+        assert!(matches!(
+            FormatHeader::parse("tsl-journal one draft"),
+            Err(FormatError::UnknownFormat(_))
+        ));
+        assert!(FormatHeader::parse("0 4 0 1 10").is_err());
+    }
+
+    #[cfg(any(feature = "journal", feature = "sled"))]
+    #[test]
+    fn test_expect() {
+        let current = FormatHeader::current(FormatKind::Store).to_string();
+        assert!(FormatHeader::expect(FormatKind::Store, Some(&current)).is_ok());
+        assert!(matches!(
+            FormatHeader::expect(FormatKind::Journal, Some(&current)),
+            Err(FormatError::UnknownFormat(_))
+        ));
+        match FormatHeader::expect(FormatKind::Store, Some("tsl-store 2 draft-x")) {
+            Err(FormatError::UnsupportedVersion(kind, 2)) => assert_eq!(kind, "tsl-store"),
+            _ => panic!("Expected UnsupportedVersion"),
+        }
+        assert!(matches!(
+            FormatHeader::expect(FormatKind::Store, None),
+            Err(FormatError::UnsupportedVersion(_, 0))
+        ));
+    }
+
+    fn migrate_file(name: &str, contents: &str, to_version: u32) -> (Migration, String) {
+        let path = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+        fs::write(&path, contents).unwrap();
+        let migration = migrate(&path, to_version).unwrap();
+        let migrated = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        (migration, migrated)
+    }
+
+    #[test]
+    fn test_migrate_journal() {
+        let (migration, migrated) = migrate_file("migrate.log", "0 4 0 1 10\n", 1);
+        assert_eq!(
+            migration,
+            Migration {
+                kind: FormatKind::Journal,
+                from_version: 0,
+                to_version: 1,
+            }
+        );
+        assert_eq!(
+            migrated,
+            "tsl-journal 1 draft-ietf-oauth-status-list-13\n0 4 0 1 10\n"
+        );
+
+        let (migration, rolled_back) = migrate_file("migrate.log", &migrated, 0);
+        assert_eq!(migration.from_version, 1);
+        assert_eq!(rolled_back, "0 4 0 1 10\n");
+        assert_eq!(migrate_file("migrate.log", &migrated, 1).1, migrated);
+    }
+
+    #[test]
+    fn test_migrate_snapshot() {
+        let text = "bits: 1\nlen: 8\n0..8: VALID\n";
+        let (migration, migrated) = migrate_file("migrate.snap", text, 1);
+        assert_eq!(migration.kind, FormatKind::Snapshot);
+        assert_eq!(
+            migrated,
+            format!("format: tsl-snapshot 1 {}\n{}", SPEC_DRAFT, text)
+        );
+        assert_eq!(migrate_file("migrate.snap", &migrated, 0).1, text);
+
+        let json = r#"{"bits":1,"len":8,"runs":[]}"#;
+        let (_, migrated) = migrate_file("migrate.json", json, 1);
+        assert_eq!(
+            migrated,
+            format!(
+                r#"{{"format":"tsl-snapshot 1 {}","bits":1,"len":8,"runs":[]}}"#,
+                SPEC_DRAFT
+            )
+        );
+        assert_eq!(migrate_file("migrate.json", &migrated, 0).1, json);
+    }
+
+    #[test]
+    fn test_migrate_errors() {
+        let path = std::env::temp_dir().join(format!("migrate-errors-{}", std::process::id()));
+        fs::write(&path, "0 4 0 1 10\n").unwrap();
+        assert!(matches!(
+            migrate(&path, 2),
+            Err(FormatError::UnsupportedVersion(_, 2))
+        ));
+        fs::write(&path, "hello\n").unwrap();
+        assert!(matches!(
+            migrate(&path, 1),
+            Err(FormatError::UnknownFormat(_))
+        ));
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            migrate(&path, 1),
+            Err(FormatError::StorageError(_))
+        ));
+    }
+}
@@ -0,0 +1,161 @@
+use sha2::{Digest, Sha256};
+
+use crate::error::TokenError;
+use crate::signing::{SignatureVerifier, Signer};
+use crate::token::{Provenance, StatusListTokenVerifier};
+use crate::uri::StatusListUri;
+
+/// Re-anchors an upstream issuer's status list under a federation
+/// gateway's key. The upstream token is fully verified, then its list is
+/// re-signed unchanged as a token for the gateway's own URI, keeping the
+/// upstream `iat`, `exp` and `ttl` and adding a `Provenance` claim.
+#[derive(Debug, Clone)]
+pub struct StatusListGateway {
+    upstream: StatusListTokenVerifier,
+    uri: StatusListUri,
+}
+
+impl StatusListGateway {
+    /// `upstream` checks tokens from the issuer; `uri` is where the gateway
+    /// serves its copy.
+    pub fn new(upstream: StatusListTokenVerifier, uri: StatusListUri) -> Self {
+        Self { upstream, uri }
+    }
+
+    pub fn cross_sign_cwt(
+        &self,
+        bytes: &[u8],
+        verifier: &impl SignatureVerifier,
+        signer: &impl Signer,
+        now: u64,
+    ) -> Result<Vec<u8>, TokenError> {
+        let mut token = self.upstream.verify_cwt_token(bytes, verifier, now)?;
+        token.provenance = Some(provenance(token.sub, bytes, now));
+        token.sub = self.uri.clone();
+        token.sign(signer)
+    }
+
+    #[cfg(feature = "jwt")]
+    pub fn cross_sign_jwt(
+        &self,
+        token: &str,
+        verifier: &impl SignatureVerifier,
+        signer: &impl Signer,
+        now: u64,
+    ) -> Result<String, TokenError> {
+        let mut jwt = self.upstream.verify_jwt_token(token, verifier, now)?;
+        jwt.provenance = Some(provenance(jwt.sub, token.as_bytes(), now));
+        jwt.sub = self.uri.clone();
+        jwt.sign(signer)
+    }
+}
+
+fn provenance(uri: StatusListUri, upstream: &[u8], now: u64) -> Provenance {
+    Provenance {
+        uri,
+        token_sha256: hex::encode(Sha256::digest(upstream)),
+        verified_at: now,
+    }
+}
+
+#[cfg(all(test, feature = "encoder"))]
+mod tests {
+    use super::*;
+    use crate::builder::StatusListBuilder;
+    #[cfg(feature = "jwt")]
+    use crate::jwt::JwtStatusListToken;
+    use crate::signing::tests::TestKey;
+    use crate::token::CwtStatusListToken;
+    use crate::types::{StatusList, StatusType};
+
+    fn upstream_uri() -> StatusListUri {
+        StatusListUri::parse("https://issuer.example/statuslists/1").unwrap()
+    }
+
+    fn gateway_uri() -> StatusListUri {
+        StatusListUri::parse("https://gateway.example/federation/issuer/1").unwrap()
+    }
+
+    fn status_list() -> StatusList {
+        StatusListBuilder::from_vec(vec![StatusType::Valid, StatusType::Invalid], 1)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    fn gateway() -> StatusListGateway {
+        StatusListGateway::new(StatusListTokenVerifier::new(upstream_uri()), gateway_uri())
+    }
+
+    #[test]
+    fn test_cross_sign_cwt() {
+        let upstream = CwtStatusListToken::new(upstream_uri(), 1000, status_list())
+            .with_exp(5000)
+            .with_ttl(300)
+            .sign(&TestKey(1))
+            .unwrap();
+
+        let signed = gateway()
+            .cross_sign_cwt(&upstream, &TestKey(1), &TestKey(2), 1100)
+            .unwrap();
+        let token = CwtStatusListToken::verify(&signed, &TestKey(2)).unwrap();
+        assert_eq!(token.sub, gateway_uri());
+        assert_eq!(
+            (token.iat, token.exp, token.ttl),
+            (1000, Some(5000), Some(300))
+        );
+        assert_eq!(token.status_list, status_list());
+        assert_eq!(
+            token.provenance,
+            Some(Provenance {
+                uri: upstream_uri(),
+                token_sha256: hex::encode(Sha256::digest(&upstream)),
+                verified_at: 1100,
+            })
+        );
+
+        // Relying parties check the gateway's token against the gateway URI.
+        let decoder = StatusListTokenVerifier::new(gateway_uri())
+            .verify_cwt(&signed, &TestKey(2), 1100)
+            .unwrap();
+        assert_eq!(decoder.get_status(1).unwrap(), StatusType::Invalid);
+
+        assert!(matches!(
+            gateway().cross_sign_cwt(&upstream, &TestKey(3), &TestKey(2), 1100),
+            Err(TokenError::SignatureError(_))
+        ));
+        assert!(matches!(
+            gateway().cross_sign_cwt(&upstream, &TestKey(1), &TestKey(2), 5000),
+            Err(TokenError::Expired(5000))
+        ));
+        let other = CwtStatusListToken::new(gateway_uri(), 1000, status_list())
+            .sign(&TestKey(1))
+            .unwrap();
+        assert!(matches!(
+            gateway().cross_sign_cwt(&other, &TestKey(1), &TestKey(2), 1100),
+            Err(TokenError::SubjectMismatch(_))
+        ));
+    }
+
+    #[cfg(feature = "jwt")]
+    #[test]
+    fn test_cross_sign_jwt() {
+        let upstream = JwtStatusListToken::new(upstream_uri(), 1000, status_list())
+            .with_exp(5000)
+            .sign(&TestKey(1))
+            .unwrap();
+
+        let signed = gateway()
+            .cross_sign_jwt(&upstream, &TestKey(1), &TestKey(2), 1100)
+            .unwrap();
+        let token = JwtStatusListToken::verify(&signed, &TestKey(2)).unwrap();
+        assert_eq!(token.sub, gateway_uri());
+        assert_eq!(token.status_list, status_list());
+        let provenance = token.provenance.unwrap();
+        assert_eq!(provenance.uri, upstream_uri());
+        assert_eq!(
+            provenance.token_sha256,
+            hex::encode(Sha256::digest(upstream.as_bytes()))
+        );
+    }
+}
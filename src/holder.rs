@@ -0,0 +1,248 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::client::StatusListCache;
+use crate::signing::SignatureVerifier;
+use crate::transport::Transport;
+use crate::types::StatusListReference;
+use crate::uri::StatusListUri;
+
+/// The status of one credential. Exactly one of `status` and `error` is set.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CredentialStatus {
+    pub uri: String,
+    pub idx: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// How current the list a status was read from is.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ListFreshness {
+    pub uri: String,
+    pub issued_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+    /// When the cache stops serving the list and refetches it.
+    pub fresh_until: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IssuerStatus {
+    /// The origin the issuer serves its lists from.
+    pub issuer: String,
+    pub credentials: Vec<CredentialStatus>,
+    /// One entry per list that could be fetched and verified.
+    pub lists: Vec<ListFreshness>,
+}
+
+/// The statuses of a holder's credentials grouped by issuer, for a wallet's
+/// credential overview.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HolderStatusReport {
+    pub checked_at: u64,
+    /// Issuers in origin order, credentials in input order.
+    pub issuers: Vec<IssuerStatus>,
+}
+
+/// A list a holder's credentials point into, and when it next needs
+/// fetching.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedFetch {
+    pub uri: StatusListUri,
+    /// `now` for lists not cached or already stale.
+    pub refresh_at: u64,
+    /// How many of the references point into this list.
+    pub credentials: usize,
+}
+
+impl<T: Transport> StatusListCache<T> {
+    /// Plans the fetches a scheduler needs to keep `references` current:
+    /// one entry per distinct list, earliest refresh first.
+    pub fn fetch_plan(&self, references: &[StatusListReference], now: u64) -> Vec<PlannedFetch> {
+        let mut credentials: BTreeMap<&StatusListUri, usize> = BTreeMap::new();
+        for reference in references {
+            *credentials.entry(&reference.uri).or_default() += 1;
+        }
+
+        let mut plan: Vec<_> = credentials
+            .into_iter()
+            .map(|(uri, credentials)| PlannedFetch {
+                uri: uri.clone(),
+                refresh_at: self
+                    .cached_freshness(uri)
+                    .map_or(now, |(_, expires_at)| expires_at.max(now)),
+                credentials,
+            })
+            .collect();
+        plan.sort_by_key(|fetch| fetch.refresh_at);
+        plan
+    }
+
+    /// Checks all of a holder's credentials through `check_many` and groups
+    /// the outcomes by the origin of each status list URI.
+    pub async fn check_holder(
+        &self,
+        references: &[StatusListReference],
+        verifier: &impl SignatureVerifier,
+        now: u64,
+    ) -> HolderStatusReport {
+        let results = self.check_many(references, verifier, now).await;
+        let mut issuers: BTreeMap<String, IssuerStatus> = BTreeMap::new();
+        for (reference, result) in references.iter().zip(results) {
+            let group = issuers
+                .entry(reference.uri.origin())
+                .or_insert_with_key(|issuer| IssuerStatus {
+                    issuer: issuer.clone(),
+                    credentials: Vec::new(),
+                    lists: Vec::new(),
+                });
+
+            let uri = reference.uri.as_str();
+            if !group.lists.iter().any(|list| list.uri == uri) {
+                if let Some((freshness, fresh_until)) = self.cached_freshness(&reference.uri) {
+                    group.lists.push(ListFreshness {
+                        uri: uri.to_string(),
+                        issued_at: freshness.iat,
+                        expires_at: freshness.exp,
+                        fresh_until,
+                    });
+                }
+            }
+            let (status, error) = match result {
+                Ok(status) => (Some(status.value()), None),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            group.credentials.push(CredentialStatus {
+                uri: uri.to_string(),
+                idx: reference.idx,
+                status,
+                error,
+            });
+        }
+
+        HolderStatusReport {
+            checked_at: now,
+            issuers: issuers.into_values().collect(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "http", feature = "encoder"))]
+mod tests {
+    use super::*;
+    use crate::builder::StatusListBuilder;
+    use crate::client::StatusListClient;
+    use crate::error::ClientError;
+    use crate::signing::tests::TestKey;
+    use crate::token::{CwtStatusListToken, CWT_TYPE};
+    use crate::transport::TransportResponse;
+    use crate::types::StatusType;
+    use crate::uri::StatusListUri;
+
+    // Serves a token for every list on example.com and 404 elsewhere.
+    struct IssuerTransport;
+
+    impl Transport for IssuerTransport {
+        async fn get(&self, url: &str, _accept: &str) -> Result<TransportResponse, ClientError> {
+            let uri = StatusListUri::parse(url).unwrap();
+            if uri.origin() != "https://example.com" {
+                return Ok(TransportResponse {
+                    status: 404,
+                    content_type: None,
+                    cache_control: None,
+                    body: Vec::new(),
+                });
+            }
+            let status_list =
+                StatusListBuilder::from_vec(vec![StatusType::Valid, StatusType::Invalid], 1)
+                    .unwrap()
+                    .build()
+                    .unwrap();
+            Ok(TransportResponse {
+                status: 200,
+                content_type: Some(CWT_TYPE.to_string()),
+                cache_control: Some("max-age=60".to_string()),
+                body: CwtStatusListToken::new(uri, 1000, status_list)
+                    .with_exp(2000)
+                    .sign(&TestKey(1))
+                    .unwrap(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_holder() {
+        let uri = |uri: &str| StatusListUri::parse(uri).unwrap();
+        let references = [
+            StatusListReference::new(1, uri("https://example.com/statuslists/1")),
+            StatusListReference::new(0, uri("https://other.example/statuslists/1")),
+            StatusListReference::new(0, uri("https://example.com/statuslists/2")),
+            StatusListReference::new(0, uri("https://example.com/statuslists/1")),
+        ];
+        let cache = StatusListCache::new(StatusListClient::with_transport(IssuerTransport));
+
+        let report = cache.check_holder(&references, &TestKey(1), 1500).await;
+        assert_eq!(report.checked_at, 1500);
+        assert_eq!(report.issuers.len(), 2);
+
+        let example = &report.issuers[0];
+        assert_eq!(example.issuer, "https://example.com");
+        assert_eq!(
+            example
+                .credentials
+                .iter()
+                .map(|credential| (credential.idx, credential.status))
+                .collect::<Vec<_>>(),
+            [(1, Some(1)), (0, Some(0)), (0, Some(0))]
+        );
+        assert_eq!(
+            example.lists[0],
+            ListFreshness {
+                uri: "https://example.com/statuslists/1".to_string(),
+                issued_at: 1000,
+                expires_at: Some(2000),
+                fresh_until: 1560,
+            }
+        );
+        assert_eq!(example.lists.len(), 2);
+
+        let other = &report.issuers[1];
+        assert_eq!(other.issuer, "https://other.example");
+        assert!(other.lists.is_empty());
+        assert_eq!(other.credentials[0].status, None);
+        assert!(other.credentials[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("404"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_plan() {
+        let uri = |n: u8| {
+            StatusListUri::parse(&format!("https://example.com/statuslists/{}", n)).unwrap()
+        };
+        let cache = StatusListCache::new(StatusListClient::with_transport(IssuerTransport));
+        cache.get(&uri(2), &TestKey(1), 1500).await.unwrap();
+        cache.get(&uri(3), &TestKey(1), 1400).await.unwrap();
+
+        let references = [
+            StatusListReference::new(0, uri(2)),
+            StatusListReference::new(4, uri(1)),
+            StatusListReference::new(1, uri(2)),
+            StatusListReference::new(7, uri(3)),
+        ];
+        let plan = cache.fetch_plan(&references, 1470);
+        assert_eq!(
+            plan.iter()
+                .map(|fetch| (fetch.uri.clone(), fetch.refresh_at, fetch.credentials))
+                .collect::<Vec<_>>(),
+            [(uri(1), 1470, 1), (uri(3), 1470, 1), (uri(2), 1560, 2)]
+        );
+        assert!(cache.fetch_plan(&[], 1470).is_empty());
+    }
+}
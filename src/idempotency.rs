@@ -0,0 +1,82 @@
+use std::collections::{HashMap, VecDeque};
+
+/// How long idempotency keys are remembered unless configured otherwise:
+/// one day.
+pub const DEFAULT_KEY_RETENTION: u64 = 24 * 60 * 60;
+
+// Idempotency keys and the change each was used for. A key is forgotten
+// once a change `retention` seconds newer than it is looked up or recorded,
+// so memory stays bounded by the traffic of one window.
+#[derive(Debug)]
+pub(crate) struct IdempotencyKeys<C> {
+    retention: u64,
+    changes: HashMap<String, (C, u64)>,
+    // Keys in the order they were recorded, oldest first.
+    order: VecDeque<(u64, String)>,
+}
+
+impl<C: PartialEq> IdempotencyKeys<C> {
+    pub(crate) fn new(retention: u64) -> Self {
+        Self {
+            retention,
+            changes: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    pub(crate) fn set_retention(&mut self, retention: u64) {
+        self.retention = retention;
+    }
+
+    /// The change `key` was used for, if it is still remembered at `now`.
+    pub(crate) fn get(&mut self, key: &str, now: u64) -> Option<&C> {
+        self.expire(now);
+        self.changes.get(key).map(|(change, _)| change)
+    }
+
+    pub(crate) fn insert(&mut self, key: String, change: C, timestamp: u64) {
+        self.expire(timestamp);
+        self.order.push_back((timestamp, key.clone()));
+        self.changes.insert(key, (change, timestamp));
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    fn expire(&mut self, now: u64) {
+        while let Some((timestamp, _)) = self.order.front() {
+            if timestamp.saturating_add(self.retention) >= now {
+                break;
+            }
+            let (timestamp, key) = self.order.pop_front().unwrap();
+            // A key reused after expiring has a newer entry further back.
+            if self.changes.get(&key).is_some_and(|(_, t)| *t == timestamp) {
+                self.changes.remove(&key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_expire_after_retention() {
+        let mut keys = IdempotencyKeys::new(100);
+        keys.insert("a".to_string(), 1, 1000);
+        keys.insert("b".to_string(), 2, 1050);
+
+        assert_eq!(keys.get("a", 1100), Some(&1));
+        assert_eq!(keys.get("a", 1101), None);
+        assert_eq!(keys.get("b", 1101), Some(&2));
+        assert_eq!(keys.len(), 1);
+
+        keys.insert("a".to_string(), 3, 1200);
+        assert_eq!(keys.get("b", 1200), None);
+        assert_eq!(keys.get("a", 1200), Some(&3));
+        assert_eq!(keys.len(), 1);
+    }
+}
@@ -0,0 +1,181 @@
+use std::io::{self, BufRead};
+
+use serde_json::Value;
+
+use crate::builder::StatusListBuilder;
+use crate::types::StatusType;
+
+/// A record that was skipped during an import, with its 1-based line number.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineError {
+    pub line: usize,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    pub applied: usize,
+    pub errors: Vec<LineError>,
+}
+
+impl ImportReport {
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl StatusListBuilder {
+    /// Applies a JSON Lines stream of `{"idx":n,"status":s}` records, the
+    /// format written by `StatusListDecoder::export_jsonl`. `status` is a
+    /// registered name, a hex string such as `"0x0b"` or a number. Malformed
+    /// records are reported and skipped; only read errors abort the import.
+    pub fn import_jsonl(&self, input: impl BufRead) -> io::Result<ImportReport> {
+        let mut report = ImportReport::default();
+        for (number, line) in input.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match self.parse_record(&line) {
+                Ok((index, status)) => match self.try_set_status(index, status) {
+                    Ok(_) => report.applied += 1,
+                    Err(e) => report.errors.push(LineError {
+                        line: number + 1,
+                        reason: e.to_string(),
+                    }),
+                },
+                Err(reason) => report.errors.push(LineError {
+                    line: number + 1,
+                    reason,
+                }),
+            }
+        }
+        Ok(report)
+    }
+
+    fn parse_record(&self, line: &str) -> Result<(usize, StatusType), String> {
+        let record = match serde_json::from_str(line) {
+            Ok(Value::Object(record)) => record,
+            Ok(_) => return Err("record is not a JSON object".to_string()),
+            Err(e) => return Err(format!("invalid JSON: {}", e)),
+        };
+        if let Some(field) = record.keys().find(|key| *key != "idx" && *key != "status") {
+            return Err(format!("unexpected field {}", field));
+        }
+
+        let index = match record.get("idx") {
+            Some(idx) => idx
+                .as_u64()
+                .and_then(|idx| usize::try_from(idx).ok())
+                .ok_or_else(|| format!("idx {} is not a valid index", idx))?,
+            None => return Err("missing idx".to_string()),
+        };
+        let status = match record.get("status") {
+            Some(Value::String(name)) => self
+                .registry()
+                .lookup(name)
+                .or_else(|| {
+                    name.strip_prefix("0x")
+                        .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                        .map(StatusType::from)
+                })
+                .ok_or_else(|| format!("unknown status {}", name))?,
+            Some(value) => value
+                .as_u64()
+                .and_then(|value| u8::try_from(value).ok())
+                .map(StatusType::from)
+                .ok_or_else(|| format!("status {} is not a status value", value))?,
+            None => return Err("missing status".to_string()),
+        };
+
+        if (status.value() as u16) >> self.get_bits_per_status() != 0 {
+            return Err(format!(
+                "status {} does not fit in {} bits",
+                status.value(),
+                self.get_bits_per_status()
+            ));
+        }
+        Ok((index, status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::StatusRegistry;
+
+    #[test]
+    fn test_import_jsonl() {
+        let builder = StatusListBuilder::new(4).unwrap().with_registry(
+            StatusRegistry::default().with_status(StatusType::ApplicationSpecific11, "ON_HOLD"),
+        );
+        let input = r#"{"idx":0,"status":"INVALID"}
+
+{"idx":2,"status":"ON_HOLD"}
+{"idx":3,"status":"0x0c"}
+{"idx":4,"status":2}
+{"idx":5,"status":"REVOKED"}
+{"idx":-1,"status":1}
+{"idx":6}
+{"idx":7,"status":1,"reason":"key compromise"}
+{"idx":8,"status":16}
+not json
+"#;
+
+        let report = builder.import_jsonl(input.as_bytes()).unwrap();
+        assert_eq!(report.applied, 4);
+        assert_eq!(
+            report
+                .errors
+                .iter()
+                .take(5)
+                .map(|e| (e.line, e.reason.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (6, "unknown status REVOKED"),
+                (7, "idx -1 is not a valid index"),
+                (8, "missing status"),
+                (9, "unexpected field reason"),
+                (10, "status 16 does not fit in 4 bits"),
+            ]
+        );
+        assert_eq!(report.errors[5].line, 11);
+        assert!(report.errors[5].reason.starts_with("invalid JSON"));
+        assert!(!report.is_clean());
+
+        assert_eq!(builder.len(), 5);
+        assert_eq!(builder.get_status(0), Some(StatusType::Invalid));
+        assert_eq!(
+            builder.get_status(2),
+            Some(StatusType::ApplicationSpecific11)
+        );
+        assert_eq!(
+            builder.get_status(3),
+            Some(StatusType::ApplicationSpecific12)
+        );
+        assert_eq!(builder.get_status(4), Some(StatusType::Suspended));
+    }
+
+    #[cfg(feature = "decoder")]
+    #[test]
+    fn test_export_import_round_trip() {
+        let source = StatusListBuilder::from_vec(
+            vec![
+                StatusType::Valid,
+                StatusType::Invalid,
+                StatusType::Suspended,
+                StatusType::ApplicationSpecific3,
+            ],
+            2,
+        )
+        .unwrap();
+        let decoder = crate::decoder::StatusListDecoder::new(&source.build().unwrap()).unwrap();
+        let mut jsonl = Vec::new();
+        decoder.export_jsonl(&mut jsonl).unwrap();
+
+        let imported = StatusListBuilder::new(2).unwrap();
+        assert!(imported.import_jsonl(&jsonl[..]).unwrap().is_clean());
+        assert_eq!(imported.build().unwrap(), source.build().unwrap());
+    }
+}
@@ -0,0 +1,592 @@
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::builder::StatusListBuilder;
+use crate::error::{BuilderError, JournalError};
+use crate::format::{FormatHeader, FormatKind};
+use crate::idempotency::{IdempotencyKeys, DEFAULT_KEY_RETENTION};
+use crate::types::{StatusList, StatusType};
+
+/// One status change, as recorded before it is applied to the list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalEntry {
+    pub sequence: u64,
+    pub index: usize,
+    pub old: StatusType,
+    pub new: StatusType,
+    pub timestamp: u64,
+    /// Caller-supplied key that makes retries of this change no-ops.
+    pub idempotency_key: Option<String>,
+}
+
+impl JournalEntry {
+    fn to_line(&self) -> String {
+        let mut line = format!(
+            "{} {} {} {} {}",
+            self.sequence,
+            self.index,
+            self.old.value(),
+            self.new.value(),
+            self.timestamp
+        );
+        if let Some(key) = &self.idempotency_key {
+            line.push(' ');
+            line.push_str(key);
+        }
+        line.push('\n');
+        line
+    }
+
+    fn from_line(line: &str) -> Result<Self, JournalError> {
+        let corrupted = || JournalError::Corrupted(format!("malformed entry: {:?}", line));
+        let mut fields = line.split(' ');
+        let mut numbers = [0u64; 5];
+        for number in &mut numbers {
+            *number = fields
+                .next()
+                .and_then(|field| field.parse().ok())
+                .ok_or_else(corrupted)?;
+        }
+        let idempotency_key = fields.next().map(str::to_string);
+        if fields.next().is_some() {
+            return Err(corrupted());
+        }
+
+        let [sequence, index, old, new, timestamp] = numbers;
+        let status = |value: u64| u8::try_from(value).map(StatusType::from);
+        Ok(Self {
+            sequence,
+            index: index as usize,
+            old: status(old).map_err(|_| corrupted())?,
+            new: status(new).map_err(|_| corrupted())?,
+            timestamp,
+            idempotency_key,
+        })
+    }
+}
+
+// An append-only record of status changes. Entries are returned in the order
+// they were appended.
+pub trait StatusJournal {
+    fn append(&self, entry: &JournalEntry) -> Result<(), JournalError>;
+
+    fn entries(&self) -> Result<Vec<JournalEntry>, JournalError>;
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryJournal {
+    entries: Mutex<Vec<JournalEntry>>,
+}
+
+impl InMemoryJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StatusJournal for InMemoryJournal {
+    fn append(&self, entry: &JournalEntry) -> Result<(), JournalError> {
+        self.entries.lock().unwrap().push(entry.clone());
+        Ok(())
+    }
+
+    fn entries(&self) -> Result<Vec<JournalEntry>, JournalError> {
+        Ok(self.entries.lock().unwrap().clone())
+    }
+}
+
+// A format header line, then one line per entry, synced to disk before append
+// returns. A line without its trailing newline was cut off by a crash before
+// it was acknowledged, so open() drops it.
+#[derive(Debug)]
+pub struct FileJournal {
+    file: Mutex<File>,
+}
+
+impl FileJournal {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, JournalError> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)
+            .map_err(|e| JournalError::StorageError(e.to_string()))?;
+
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)
+            .map_err(|e| JournalError::StorageError(e.to_string()))?;
+        let complete = contents
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |pos| pos + 1);
+        if complete < contents.len() {
+            file.set_len(complete as u64)
+                .map_err(|e| JournalError::StorageError(e.to_string()))?;
+        }
+        if complete == 0 {
+            file.write_all(format!("{}\n", FormatHeader::current(FormatKind::Journal)).as_bytes())
+                .and_then(|()| file.sync_data())
+                .map_err(|e| JournalError::StorageError(e.to_string()))?;
+        } else {
+            let header = contents.split(|byte| *byte == b'\n').next();
+            FormatHeader::expect(
+                FormatKind::Journal,
+                header.and_then(|line| std::str::from_utf8(line).ok()),
+            )
+            .map_err(JournalError::FormatError)?;
+        }
+
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl StatusJournal for FileJournal {
+    fn append(&self, entry: &JournalEntry) -> Result<(), JournalError> {
+        let mut file = self.file.lock().unwrap();
+        file.write_all(entry.to_line().as_bytes())
+            .and_then(|()| file.sync_data())
+            .map_err(|e| JournalError::StorageError(e.to_string()))
+    }
+
+    fn entries(&self) -> Result<Vec<JournalEntry>, JournalError> {
+        let mut file = self.file.lock().unwrap();
+        let mut contents = String::new();
+        // Appends always go to the end, whatever the read position.
+        file.rewind()
+            .and_then(|()| file.read_to_string(&mut contents))
+            .map_err(|e| JournalError::StorageError(e.to_string()))?;
+
+        // The header was checked by open().
+        contents
+            .lines()
+            .skip(1)
+            .map(JournalEntry::from_line)
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+struct JournalState {
+    next_sequence: u64,
+    keys: IdempotencyKeys<(usize, StatusType)>,
+}
+
+/// A builder whose status changes are written to a journal before they are
+/// applied, so the list can be rebuilt after a crash or as of any past time.
+#[derive(Debug)]
+pub struct JournaledStatusList<J> {
+    builder: StatusListBuilder,
+    journal: J,
+    state: Mutex<JournalState>,
+}
+
+impl<J: StatusJournal> JournaledStatusList<J> {
+    /// Journals changes to `builder`, continuing the sequence numbers already
+    /// in `journal`.
+    pub fn new(builder: StatusListBuilder, journal: J) -> Result<Self, JournalError> {
+        let entries = journal.entries()?;
+        let next_sequence = entries.last().map_or(0, |entry| entry.sequence + 1);
+        let mut keys = IdempotencyKeys::new(DEFAULT_KEY_RETENTION);
+        for entry in entries {
+            if let Some(key) = entry.idempotency_key {
+                keys.insert(key, (entry.index, entry.new), entry.timestamp);
+            }
+        }
+        Ok(Self {
+            builder,
+            journal,
+            state: Mutex::new(JournalState {
+                next_sequence,
+                keys,
+            }),
+        })
+    }
+
+    /// Replays every journaled change onto `builder`, which should be set up
+    /// the way the journaled list was created (bit width, capacity, default
+    /// status).
+    pub fn recover(builder: StatusListBuilder, journal: J) -> Result<Self, JournalError> {
+        for entry in journal.entries()? {
+            builder
+                .try_set_status(entry.index, entry.new)
+                .map_err(JournalError::BuilderError)?;
+        }
+        Self::new(builder, journal)
+    }
+
+    /// Remembers idempotency keys for `retention` seconds instead of
+    /// `DEFAULT_KEY_RETENTION`. A key reused after that applies again.
+    pub fn with_key_retention(mut self, retention: u64) -> Self {
+        self.state.get_mut().unwrap().keys.set_retention(retention);
+        self
+    }
+
+    pub fn set_status(&self, index: usize, status: StatusType) -> Result<&Self, JournalError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        self.set_status_at(index, status, now)
+    }
+
+    pub fn set_status_at(
+        &self,
+        index: usize,
+        status: StatusType,
+        timestamp: u64,
+    ) -> Result<&Self, JournalError> {
+        self.record(index, status, timestamp, None)?;
+        Ok(self)
+    }
+
+    /// Like `set_status`, but a change whose `key` is already in the journal
+    /// is acknowledged without a new entry or builder write, so redelivered
+    /// commands are safe to apply. Returns whether the change was applied now.
+    pub fn set_status_with_key(
+        &self,
+        index: usize,
+        status: StatusType,
+        key: Option<&str>,
+    ) -> Result<bool, JournalError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        self.record(index, status, now, key)
+    }
+
+    pub fn set_status_at_with_key(
+        &self,
+        index: usize,
+        status: StatusType,
+        timestamp: u64,
+        key: Option<&str>,
+    ) -> Result<bool, JournalError> {
+        self.record(index, status, timestamp, key)
+    }
+
+    fn record(
+        &self,
+        index: usize,
+        status: StatusType,
+        timestamp: u64,
+        key: Option<&str>,
+    ) -> Result<bool, JournalError> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(key) = key {
+            // Keys share the line with the entry's numbers.
+            if key.is_empty() || key.contains(char::is_whitespace) {
+                return Err(JournalError::InvalidIdempotencyKey(key.to_string()));
+            }
+            if let Some(&change) = state.keys.get(key, timestamp) {
+                if change != (index, status) {
+                    return Err(JournalError::IdempotencyConflict(key.to_string()));
+                }
+                return Ok(false);
+            }
+        }
+
+        // Checked before journaling, so every entry applies on replay.
+        if index >= self.builder.max_len() {
+            return Err(JournalError::BuilderError(BuilderError::MaxLenExceeded(
+                self.builder.max_len(),
+            )));
+        }
+        let entry = JournalEntry {
+            sequence: state.next_sequence,
+            index,
+            old: self
+                .builder
+                .get_status(index)
+                .unwrap_or(self.builder.get_default_status()),
+            new: status,
+            timestamp,
+            idempotency_key: key.map(str::to_string),
+        };
+
+        self.journal.append(&entry)?;
+        self.builder
+            .try_set_status(index, status)
+            .map_err(JournalError::BuilderError)?;
+        if let Some(key) = key {
+            state
+                .keys
+                .insert(key.to_string(), (index, status), timestamp);
+        }
+        state.next_sequence += 1;
+        Ok(true)
+    }
+
+    pub fn get_status(&self, index: usize) -> Option<StatusType> {
+        self.builder.get_status(index)
+    }
+
+    /// Every journaled change to `index`, oldest first.
+    pub fn history(&self, index: usize) -> Result<Vec<JournalEntry>, JournalError> {
+        Ok(self
+            .journal
+            .entries()?
+            .into_iter()
+            .filter(|entry| entry.index == index)
+            .collect())
+    }
+
+    /// Replays the changes made up to and including `timestamp` onto `base`.
+    pub fn state_at(
+        &self,
+        base: StatusListBuilder,
+        timestamp: u64,
+    ) -> Result<StatusListBuilder, JournalError> {
+        for entry in self.journal.entries()? {
+            if entry.timestamp <= timestamp {
+                base.try_set_status(entry.index, entry.new)
+                    .map_err(JournalError::BuilderError)?;
+            }
+        }
+        Ok(base)
+    }
+
+    pub fn build(&self) -> Result<StatusList, BuilderError> {
+        self.builder.build()
+    }
+
+    pub fn builder(&self) -> &StatusListBuilder {
+        &self.builder
+    }
+
+    pub fn journal(&self) -> &J {
+        &self.journal
+    }
+
+    pub fn into_parts(self) -> (StatusListBuilder, J) {
+        (self.builder, self.journal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::FillStrategy;
+    use crate::error::FormatError;
+
+    fn builder() -> StatusListBuilder {
+        StatusListBuilder::new(2)
+            .unwrap()
+            .with_capacity(16, FillStrategy::AllValid)
+    }
+
+    #[test]
+    fn test_journal_records_changes() {
+        let list = JournaledStatusList::new(builder(), InMemoryJournal::new()).unwrap();
+        list.set_status_at(3, StatusType::Suspended, 100)
+            .unwrap()
+            .set_status_at(3, StatusType::Invalid, 200)
+            .unwrap()
+            .set_status_at(5, StatusType::Invalid, 300)
+            .unwrap();
+
+        assert_eq!(
+            list.history(3).unwrap(),
+            vec![
+                JournalEntry {
+                    sequence: 0,
+                    index: 3,
+                    old: StatusType::Valid,
+                    new: StatusType::Suspended,
+                    timestamp: 100,
+                    idempotency_key: None,
+                },
+                JournalEntry {
+                    sequence: 1,
+                    index: 3,
+                    old: StatusType::Suspended,
+                    new: StatusType::Invalid,
+                    timestamp: 200,
+                    idempotency_key: None,
+                },
+            ]
+        );
+
+        let then = list.state_at(builder(), 150).unwrap();
+        assert_eq!(then.get_status(3), Some(StatusType::Suspended));
+        assert_eq!(then.get_status(5), Some(StatusType::Valid));
+    }
+
+    #[test]
+    fn test_recover() {
+        let (_, journal) = {
+            let list = JournaledStatusList::new(builder(), InMemoryJournal::new()).unwrap();
+            list.set_status(1, StatusType::Invalid)
+                .unwrap()
+                .set_status(20, StatusType::Suspended)
+                .unwrap();
+            list.into_parts()
+        };
+
+        let recovered = JournaledStatusList::recover(builder(), journal).unwrap();
+        assert_eq!(recovered.get_status(1), Some(StatusType::Invalid));
+        assert_eq!(recovered.get_status(20), Some(StatusType::Suspended));
+        assert_eq!(recovered.builder().len(), 21);
+
+        recovered.set_status(2, StatusType::Invalid).unwrap();
+        let entries = recovered.journal().entries().unwrap();
+        assert_eq!(entries.last().unwrap().sequence, 2);
+    }
+
+    #[test]
+    fn test_rejects_index_past_max_len() {
+        let list =
+            JournaledStatusList::new(builder().with_max_len(32), InMemoryJournal::new()).unwrap();
+        match list.set_status(usize::MAX, StatusType::Invalid) {
+            Err(JournalError::BuilderError(BuilderError::MaxLenExceeded(32))) => (),
+            _ => panic!("Expected MaxLenExceeded"),
+        }
+        assert!(list.journal().entries().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_file_journal_drops_torn_entry() {
+        let path = std::env::temp_dir().join(format!("journal-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        {
+            let list =
+                JournaledStatusList::new(builder(), FileJournal::open(&path).unwrap()).unwrap();
+            list.set_status_at(4, StatusType::Invalid, 10).unwrap();
+        }
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"1 7 0 1 2").unwrap();
+
+        let recovered =
+            JournaledStatusList::recover(builder(), FileJournal::open(&path).unwrap()).unwrap();
+        assert_eq!(recovered.get_status(4), Some(StatusType::Invalid));
+        assert_eq!(recovered.get_status(7), Some(StatusType::Valid));
+
+        recovered.set_status_at(7, StatusType::Invalid, 20).unwrap();
+        let entries = FileJournal::open(&path).unwrap().entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].sequence, 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_journal_format_header() {
+        let path = std::env::temp_dir().join(format!("journal-header-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        FileJournal::open(&path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "tsl-journal 1 draft-ietf-oauth-status-list-13\n"
+        );
+        assert!(FileJournal::open(&path)
+            .unwrap()
+            .entries()
+            .unwrap()
+            .is_empty());
+
+        // Journals written before the header was introduced need a migration.
+        std::fs::write(&path, "0 4 0 1 10\n").unwrap();
+        assert!(matches!(
+            FileJournal::open(&path),
+            Err(JournalError::FormatError(FormatError::UnsupportedVersion(
+                _,
+                0
+            )))
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_idempotency_keys() {
+        let list = JournaledStatusList::new(builder(), InMemoryJournal::new()).unwrap();
+        assert!(list
+            .set_status_at_with_key(3, StatusType::Invalid, 100, Some("revoke-42"))
+            .unwrap());
+        let generation = list.builder().generation();
+
+        // A redelivered command is acknowledged without a new entry.
+        assert!(!list
+            .set_status_at_with_key(3, StatusType::Invalid, 200, Some("revoke-42"))
+            .unwrap());
+        assert_eq!(list.journal().entries().unwrap().len(), 1);
+        assert_eq!(list.builder().generation(), generation);
+
+        assert!(matches!(
+            list.set_status_at_with_key(4, StatusType::Invalid, 200, Some("revoke-42")),
+            Err(JournalError::IdempotencyConflict(_))
+        ));
+        assert!(matches!(
+            list.set_status_at_with_key(4, StatusType::Invalid, 200, Some("two words")),
+            Err(JournalError::InvalidIdempotencyKey(_))
+        ));
+        assert!(list
+            .set_status_at_with_key(4, StatusType::Suspended, 200, None)
+            .unwrap());
+
+        // Keys survive recovery.
+        let (_, journal) = list.into_parts();
+        let recovered = JournaledStatusList::recover(builder(), journal).unwrap();
+        assert!(!recovered
+            .set_status_at_with_key(3, StatusType::Invalid, 300, Some("revoke-42"))
+            .unwrap());
+        assert_eq!(
+            recovered.history(3).unwrap()[0].idempotency_key.as_deref(),
+            Some("revoke-42")
+        );
+    }
+
+    #[test]
+    fn test_idempotency_key_retention() {
+        let list = JournaledStatusList::new(builder(), InMemoryJournal::new())
+            .unwrap()
+            .with_key_retention(60);
+        assert!(list
+            .set_status_at_with_key(3, StatusType::Invalid, 100, Some("revoke-42"))
+            .unwrap());
+        assert!(!list
+            .set_status_at_with_key(3, StatusType::Invalid, 160, Some("revoke-42"))
+            .unwrap());
+
+        // Past the window the key is forgotten and may be reused.
+        assert!(list
+            .set_status_at_with_key(4, StatusType::Invalid, 161, Some("revoke-42"))
+            .unwrap());
+        assert_eq!(list.journal().entries().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_entry_lines() {
+        let entry = JournalEntry {
+            sequence: 7,
+            index: 12,
+            old: StatusType::Valid,
+            new: StatusType::Invalid,
+            timestamp: 1000,
+            idempotency_key: Some("msg-1".to_string()),
+        };
+        assert_eq!(entry.to_line(), "7 12 0 1 1000 msg-1\n");
+        assert_eq!(
+            JournalEntry::from_line("7 12 0 1 1000 msg-1").unwrap(),
+            entry
+        );
+        assert_eq!(
+            JournalEntry::from_line("7 12 0 1 1000")
+                .unwrap()
+                .idempotency_key,
+            None
+        );
+        assert!(JournalEntry::from_line("7 12 0 1 1000 msg-1 extra").is_err());
+    }
+
+    #[test]
+    fn test_corrupted_entry() {
+        match JournalEntry::from_line("0 1 2") {
+            Err(JournalError::Corrupted(msg)) => assert_eq!(msg, "malformed entry: \"0 1 2\""),
+            _ => panic!("Expected Corrupted"),
+        }
+    }
+}
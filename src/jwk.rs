@@ -0,0 +1,229 @@
+use coset::iana;
+use p256::ecdsa::signature::Verifier;
+use p256::ecdsa::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::signing::SignatureVerifier;
+use crate::types::SerializationError;
+
+/// A public key in JWK form (RFC 7517). Only EC P-256 keys can verify
+/// signatures; other keys are kept so a set round-trips unchanged.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Jwk {
+    pub kty: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alg: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crv: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub y: Option<String>,
+}
+
+impl Jwk {
+    pub fn from_json(json: &str) -> Result<Self, SerializationError> {
+        serde_json::from_str(json).map_err(|e| SerializationError::JsonError(e.to_string()))
+    }
+
+    fn verifying_key(&self) -> Result<VerifyingKey, String> {
+        if self.kty != "EC" || self.crv.as_deref() != Some("P-256") {
+            return Err(format!("unsupported key type {}", self.kty));
+        }
+        let coordinate = |value: &Option<String>, name: &str| {
+            let bytes = base64url::decode(value.as_deref().unwrap_or_default())
+                .map_err(|e| format!("invalid {} coordinate: {}", name, e))?;
+            if bytes.len() != 32 {
+                return Err(format!("invalid {} coordinate length", name));
+            }
+            Ok(bytes)
+        };
+
+        let mut point = vec![0x04];
+        point.extend(coordinate(&self.x, "x")?);
+        point.extend(coordinate(&self.y, "y")?);
+        VerifyingKey::from_sec1_bytes(&point).map_err(|e| e.to_string())
+    }
+
+    fn accepts(&self, algorithm: iana::Algorithm) -> bool {
+        algorithm == iana::Algorithm::ES256 && self.alg.as_deref().is_none_or(|alg| alg == "ES256")
+    }
+}
+
+impl SignatureVerifier for Jwk {
+    fn verify(
+        &self,
+        algorithm: iana::Algorithm,
+        data: &[u8],
+        signature: &[u8],
+    ) -> Result<(), String> {
+        if !self.accepts(algorithm) {
+            return Err(format!("unsupported algorithm {:?}", algorithm));
+        }
+        let signature = Signature::from_slice(signature).map_err(|e| e.to_string())?;
+        self.verifying_key()?
+            .verify(data, &signature)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// A JWK set as published by an issuer. Tokens naming a `kid` are checked
+/// against that key only; others against every key that fits the algorithm.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
+
+impl JwkSet {
+    pub fn from_json(json: &str) -> Result<Self, SerializationError> {
+        serde_json::from_str(json).map_err(|e| SerializationError::JsonError(e.to_string()))
+    }
+
+    pub fn find(&self, kid: &str) -> Option<&Jwk> {
+        self.keys.iter().find(|key| key.kid.as_deref() == Some(kid))
+    }
+}
+
+impl SignatureVerifier for JwkSet {
+    fn verify(
+        &self,
+        algorithm: iana::Algorithm,
+        data: &[u8],
+        signature: &[u8],
+    ) -> Result<(), String> {
+        self.verify_with_key_id(None, algorithm, data, signature)
+    }
+
+    fn verify_with_key_id(
+        &self,
+        key_id: Option<&[u8]>,
+        algorithm: iana::Algorithm,
+        data: &[u8],
+        signature: &[u8],
+    ) -> Result<(), String> {
+        if let Some(key_id) = key_id {
+            let kid = String::from_utf8_lossy(key_id);
+            return self
+                .find(&kid)
+                .ok_or_else(|| format!("no key with kid {} in the set", kid))?
+                .verify(algorithm, data, signature);
+        }
+
+        let mut candidates = self.keys.iter().filter(|key| key.accepts(algorithm));
+        if candidates.any(|key| key.verify(algorithm, data, signature).is_ok()) {
+            return Ok(());
+        }
+        Err("no key in the set verifies the signature".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::ecdsa::signature::Signer as _;
+    use p256::ecdsa::SigningKey;
+
+    struct Es256Key(SigningKey, &'static str);
+
+    impl Es256Key {
+        fn new(seed: u8, kid: &'static str) -> Self {
+            Self(SigningKey::from_slice(&[seed; 32]).unwrap(), kid)
+        }
+
+        fn jwk(&self) -> Jwk {
+            let point = self.0.verifying_key().to_encoded_point(false);
+            Jwk {
+                kty: "EC".to_string(),
+                kid: Some(self.1.to_string()),
+                alg: Some("ES256".to_string()),
+                crv: Some("P-256".to_string()),
+                x: Some(base64url::encode(point.x().unwrap())),
+                y: Some(base64url::encode(point.y().unwrap())),
+            }
+        }
+    }
+
+    impl crate::signing::Signer for Es256Key {
+        fn algorithm(&self) -> iana::Algorithm {
+            iana::Algorithm::ES256
+        }
+
+        fn key_id(&self) -> Option<Vec<u8>> {
+            Some(self.1.as_bytes().to_vec())
+        }
+
+        fn sign(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+            let signature: Signature = self.0.sign(data);
+            Ok(signature.to_bytes().to_vec())
+        }
+    }
+
+    #[test]
+    fn test_jwk_verifies_es256() {
+        use crate::signing::Signer;
+
+        let key = Es256Key::new(1, "k1");
+        let signature = key.sign(b"data").unwrap();
+        let jwk = Jwk::from_json(&serde_json::to_string(&key.jwk()).unwrap()).unwrap();
+
+        assert!(jwk
+            .verify(iana::Algorithm::ES256, b"data", &signature)
+            .is_ok());
+        assert!(jwk
+            .verify(iana::Algorithm::ES256, b"other", &signature)
+            .is_err());
+        assert!(jwk
+            .verify(iana::Algorithm::ES384, b"data", &signature)
+            .is_err());
+    }
+
+    #[cfg(all(feature = "encoder", feature = "decoder"))]
+    #[test]
+    fn test_jwk_set_verifies_tokens() {
+        use crate::builder::StatusListBuilder;
+        use crate::jwt::JwtStatusListToken;
+        use crate::token::{CwtStatusListToken, StatusListTokenVerifier};
+        use crate::types::StatusType;
+        use crate::uri::StatusListUri;
+
+        let uri = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        let status_list = StatusListBuilder::from_vec(vec![StatusType::Invalid], 1)
+            .unwrap()
+            .build()
+            .unwrap();
+        let (k1, k2) = (Es256Key::new(1, "k1"), Es256Key::new(2, "k2"));
+        let jwks = JwkSet::from_json(&format!(
+            r#"{{"keys":[{},{}]}}"#,
+            serde_json::to_string(&k1.jwk()).unwrap(),
+            serde_json::to_string(&k2.jwk()).unwrap()
+        ))
+        .unwrap();
+        assert_eq!(jwks.find("k2"), Some(&k2.jwk()));
+
+        let verifier = StatusListTokenVerifier::new(uri.clone());
+        let jwt = JwtStatusListToken::new(uri.clone(), 1000, status_list.clone())
+            .sign(&k2)
+            .unwrap();
+        let decoder = verifier.verify_jwt(&jwt, &jwks, 1500).unwrap();
+        assert_eq!(decoder.get_status(0).unwrap(), StatusType::Invalid);
+
+        let cwt = CwtStatusListToken::new(uri, 1000, status_list)
+            .sign(&k1)
+            .unwrap();
+        assert!(verifier.verify_cwt(&cwt, &jwks, 1500).is_ok());
+
+        let only_k1 = JwkSet {
+            keys: vec![k1.jwk()],
+        };
+        assert!(verifier.verify_jwt(&jwt, &only_k1, 1500).is_err());
+        assert!(only_k1
+            .verify(
+                iana::Algorithm::ES256,
+                b"data",
+                &crate::signing::Signer::sign(&k2, b"data").unwrap()
+            )
+            .is_err());
+    }
+}
@@ -0,0 +1,316 @@
+use coset::iana;
+use serde::{Deserialize, Serialize};
+
+use crate::error::TokenError;
+use crate::signing::{SignatureVerifier, Signer};
+use crate::token::Provenance;
+use crate::types::StatusList;
+use crate::uri::StatusListUri;
+
+/// The `typ` header of a Status List Token in JWT form.
+pub const JWT_TYPE: &str = "statuslist+jwt";
+/// The media type a status list endpoint serves JWTs with.
+pub const JWT_MEDIA_TYPE: &str = "application/statuslist+jwt";
+
+#[derive(Serialize, Deserialize)]
+struct JwsHeader {
+    alg: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    typ: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kid: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JwtClaims {
+    sub: String,
+    iat: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    exp: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ttl: Option<u64>,
+    status_list: StatusList,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    provenance: Option<Provenance>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JwtStatusListToken {
+    pub sub: StatusListUri,
+    pub iat: u64,
+    pub exp: Option<u64>,
+    pub ttl: Option<u64>,
+    pub status_list: StatusList,
+    pub provenance: Option<Provenance>,
+}
+
+impl JwtStatusListToken {
+    pub fn new(sub: StatusListUri, iat: u64, status_list: StatusList) -> Self {
+        Self {
+            sub,
+            iat,
+            exp: None,
+            ttl: None,
+            status_list,
+            provenance: None,
+        }
+    }
+
+    pub fn with_exp(mut self, exp: u64) -> Self {
+        self.exp = Some(exp);
+        self
+    }
+
+    pub fn with_ttl(mut self, ttl: u64) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub fn with_provenance(mut self, provenance: Provenance) -> Self {
+        self.provenance = Some(provenance);
+        self
+    }
+
+    /// The same token with a new `iat` and `exp`, for periodically re-signing
+    /// a list that has not changed. The already compressed `lst` is reused
+    /// as is, so nothing is re-packed or re-compressed.
+    pub fn reissue(&self, iat: u64, exp: Option<u64>) -> Self {
+        Self {
+            iat,
+            exp,
+            ..self.clone()
+        }
+    }
+
+    /// Signs the token as a JWS in compact serialization.
+    pub fn sign(&self, signer: &impl Signer) -> Result<String, TokenError> {
+        let alg = jws_algorithm(signer.algorithm()).ok_or_else(|| {
+            TokenError::SignatureError(format!(
+                "algorithm {:?} has no JWS name",
+                signer.algorithm()
+            ))
+        })?;
+        let kid = signer
+            .key_id()
+            .map(|key_id| {
+                String::from_utf8(key_id)
+                    .map_err(|_| TokenError::EncodingError("key ID is not UTF-8".to_string()))
+            })
+            .transpose()?;
+        let header = JwsHeader {
+            alg: alg.to_string(),
+            typ: Some(JWT_TYPE.to_string()),
+            kid,
+        };
+        let claims = JwtClaims {
+            sub: self.sub.to_string(),
+            iat: self.iat,
+            exp: self.exp,
+            ttl: self.ttl,
+            status_list: self.status_list.clone(),
+            provenance: self.provenance.clone(),
+        };
+
+        let signing_input = format!("{}.{}", encode_json(&header)?, encode_json(&claims)?);
+        let signature = signer
+            .sign(signing_input.as_bytes())
+            .map_err(TokenError::SignatureError)?;
+        Ok(format!(
+            "{}.{}",
+            signing_input,
+            base64url::encode(signature)
+        ))
+    }
+
+    /// Parses a compact JWS, checks its `typ` header and signature, and
+    /// returns the claims. Time and subject checks are left to
+    /// `StatusListTokenVerifier`.
+    pub fn verify(token: &str, verifier: &impl SignatureVerifier) -> Result<Self, TokenError> {
+        let token = token.trim();
+        let mut parts = token.split('.');
+        let (Some(header), Some(payload), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(TokenError::EncodingError(
+                "not a JWS in compact serialization".to_string(),
+            ));
+        };
+
+        let jws_header: JwsHeader = decode_json(header)?;
+        let typ = jws_header.typ.as_deref().map(|typ| {
+            // RFC 7515 section 4.1.9: the "application/" prefix may be left out
+            typ.strip_prefix("application/").unwrap_or(typ)
+        });
+        if !typ.is_some_and(|typ| typ.eq_ignore_ascii_case(JWT_TYPE)) {
+            return Err(TokenError::EncodingError(
+                "unexpected typ header".to_string(),
+            ));
+        }
+        let algorithm = cose_algorithm(&jws_header.alg).ok_or_else(|| {
+            TokenError::SignatureError(format!("unsupported algorithm {}", jws_header.alg))
+        })?;
+        let signature =
+            base64url::decode(signature).map_err(|e| TokenError::EncodingError(e.to_string()))?;
+
+        let signing_input = &token[..header.len() + 1 + payload.len()];
+        verifier
+            .verify_with_key_id(
+                jws_header.kid.as_deref().map(str::as_bytes),
+                algorithm,
+                signing_input.as_bytes(),
+                &signature,
+            )
+            .map_err(TokenError::SignatureError)?;
+
+        let claims: JwtClaims = decode_json(payload)?;
+        Ok(Self {
+            sub: StatusListUri::parse_with_schemes(&claims.sub, &["https", "http"])
+                .map_err(|_| TokenError::InvalidClaim("sub".to_string()))?,
+            iat: claims.iat,
+            exp: claims.exp,
+            ttl: claims.ttl,
+            status_list: claims.status_list,
+            provenance: claims.provenance,
+        })
+    }
+}
+
+fn encode_json(value: &impl Serialize) -> Result<String, TokenError> {
+    let json = serde_json::to_vec(value).map_err(|e| TokenError::EncodingError(e.to_string()))?;
+    Ok(base64url::encode(json))
+}
+
+pub(crate) fn decode_json<T: for<'de> Deserialize<'de>>(part: &str) -> Result<T, TokenError> {
+    let json = base64url::decode(part).map_err(|e| TokenError::EncodingError(e.to_string()))?;
+    serde_json::from_slice(&json).map_err(|e| match e.classify() {
+        serde_json::error::Category::Data => TokenError::InvalidClaim(e.to_string()),
+        _ => TokenError::EncodingError(e.to_string()),
+    })
+}
+
+const ALGORITHMS: &[(&str, iana::Algorithm)] = &[
+    ("ES256", iana::Algorithm::ES256),
+    ("ES384", iana::Algorithm::ES384),
+    ("ES512", iana::Algorithm::ES512),
+    ("EdDSA", iana::Algorithm::EdDSA),
+    ("PS256", iana::Algorithm::PS256),
+    ("PS384", iana::Algorithm::PS384),
+    ("PS512", iana::Algorithm::PS512),
+    ("RS256", iana::Algorithm::RS256),
+    ("RS384", iana::Algorithm::RS384),
+    ("RS512", iana::Algorithm::RS512),
+];
+
+pub(crate) fn jws_algorithm(algorithm: iana::Algorithm) -> Option<&'static str> {
+    ALGORITHMS
+        .iter()
+        .find(|(_, cose)| *cose == algorithm)
+        .map(|(name, _)| *name)
+}
+
+pub(crate) fn cose_algorithm(name: &str) -> Option<iana::Algorithm> {
+    ALGORITHMS
+        .iter()
+        .find(|(jws, _)| *jws == name)
+        .map(|(_, cose)| *cose)
+}
+
+#[cfg(all(test, feature = "encoder"))]
+mod tests {
+    use super::*;
+    use crate::builder::StatusListBuilder;
+    use crate::signing::tests::TestKey;
+    use crate::types::StatusType;
+
+    fn sample_token() -> JwtStatusListToken {
+        let status_list = StatusListBuilder::from_vec(vec![StatusType::Invalid; 2], 1)
+            .unwrap()
+            .build()
+            .unwrap();
+        let sub = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+
+        JwtStatusListToken::new(sub, 1686920170, status_list)
+            .with_exp(2291720170)
+            .with_ttl(43200)
+    }
+
+    #[test]
+    fn test_reissue_reuses_lst() {
+        use crate::compression::{Compressor, ZlibCompression};
+
+        // Stored rather than deflated, so re-compressing would change it.
+        let lst = ZlibCompression::new(0).compress(&[0b10]).unwrap();
+        let status_list = StatusList::from_compressed(1, lst.clone()).unwrap();
+        let sub = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        let token = JwtStatusListToken::new(sub, 1000, status_list).with_ttl(300);
+
+        let reissued = token.reissue(2000, Some(5000));
+        assert_eq!(
+            (reissued.iat, reissued.exp, reissued.ttl),
+            (2000, Some(5000), Some(300))
+        );
+        let signed = reissued.sign(&TestKey(1)).unwrap();
+        let verified = JwtStatusListToken::verify(&signed, &TestKey(1)).unwrap();
+        assert_eq!(verified.status_list.lst(), &lst[..]);
+        assert_eq!(verified, reissued);
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let token = sample_token();
+        let signed = token.sign(&TestKey(1)).unwrap();
+        assert_eq!(signed.split('.').count(), 3);
+
+        let header: serde_json::Value = decode_json(signed.split('.').next().unwrap()).unwrap();
+        assert_eq!(
+            header,
+            serde_json::json!({"alg": "ES256", "typ": "statuslist+jwt", "kid": "test-key"})
+        );
+        let claims: serde_json::Value = decode_json(signed.split('.').nth(1).unwrap()).unwrap();
+        assert_eq!(claims["status_list"]["bits"], 1);
+        assert_eq!(claims["sub"], "https://example.com/statuslists/1");
+
+        assert_eq!(
+            JwtStatusListToken::verify(&signed, &TestKey(1)).unwrap(),
+            token
+        );
+        match JwtStatusListToken::verify(&signed, &TestKey(2)) {
+            Err(TokenError::SignatureError(msg)) => assert_eq!(msg, "bad signature"),
+            _ => panic!("Expected SignatureError"),
+        }
+    }
+
+    #[test]
+    fn test_verify_rejects_wrong_typ() {
+        let token = sample_token().sign(&TestKey(1)).unwrap();
+        let payload = token.split('.').nth(1).unwrap();
+        let header = encode_json(&JwsHeader {
+            alg: "ES256".to_string(),
+            typ: Some("JWT".to_string()),
+            kid: None,
+        })
+        .unwrap();
+        let input = format!("{}.{}", header, payload);
+        let forged = format!(
+            "{}.{}",
+            input,
+            base64url::encode(TestKey(1).sign(input.as_bytes()).unwrap())
+        );
+
+        match JwtStatusListToken::verify(&forged, &TestKey(1)) {
+            Err(TokenError::EncodingError(msg)) => assert_eq!(msg, "unexpected typ header"),
+            _ => panic!("Expected EncodingError"),
+        }
+        assert!(matches!(
+            JwtStatusListToken::verify("a.b", &TestKey(1)),
+            Err(TokenError::EncodingError(_))
+        ));
+    }
+
+    #[test]
+    fn test_algorithm_names() {
+        assert_eq!(jws_algorithm(iana::Algorithm::ES256), Some("ES256"));
+        assert_eq!(cose_algorithm("EdDSA"), Some(iana::Algorithm::EdDSA));
+        assert_eq!(cose_algorithm("HS256"), None);
+    }
+}
@@ -0,0 +1,143 @@
+use flate2::{Decompress, FlushDecompress, Status};
+
+use crate::bitops;
+use crate::error::DecoderError;
+use crate::types::{BitsPerStatus, StatusList, StatusType};
+
+const DEFAULT_CHUNK_SIZE: usize = 16 * 1024;
+
+// Inflates only as much of the list as the highest index looked up so far.
+// The inflated prefix is kept, so later lookups below it cost nothing.
+#[derive(Debug)]
+pub struct LazyStatusListDecoder {
+    compressed: Vec<u8>,
+    bits_per_status: BitsPerStatus,
+    inflater: Decompress,
+    raw_bytes: Vec<u8>,
+    finished: bool,
+    chunk_size: usize,
+}
+
+impl LazyStatusListDecoder {
+    pub fn new(status_list: &StatusList) -> Result<Self, DecoderError> {
+        let bits_per_status = BitsPerStatus::try_from(status_list.bits)
+            .map_err(|_| DecoderError::InvalidBitsPerStatus(status_list.bits))?;
+
+        Ok(Self {
+            compressed: status_list.lst.clone(),
+            bits_per_status,
+            inflater: Decompress::new(true),
+            raw_bytes: Vec::new(),
+            finished: false,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        })
+    }
+
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    pub fn get_bits_per_status(&self) -> u8 {
+        self.bits_per_status as u8
+    }
+
+    pub fn decompressed_len(&self) -> usize {
+        self.raw_bytes.len()
+    }
+
+    pub fn get_status(&mut self, index: usize) -> Result<StatusType, DecoderError> {
+        let bits = self.bits_per_status;
+        let byte_index = index / bitops::statuses_per_byte(bits);
+        self.inflate_to(byte_index)?;
+
+        let value = bitops::decode_status(&self.raw_bytes, index, bits)
+            .map_err(|_| DecoderError::InvalidByteIndex(byte_index))?;
+        Ok(StatusType::from(value))
+    }
+
+    fn inflate_to(&mut self, byte_index: usize) -> Result<(), DecoderError> {
+        while self.raw_bytes.len() <= byte_index && !self.finished {
+            let (total_in, total_out) = (self.inflater.total_in(), self.inflater.total_out());
+            self.raw_bytes.reserve(self.chunk_size);
+
+            let status = self
+                .inflater
+                .decompress_vec(
+                    &self.compressed[total_in as usize..],
+                    &mut self.raw_bytes,
+                    FlushDecompress::None,
+                )
+                .map_err(|e| DecoderError::DecompressionError(e.to_string()))?;
+
+            if status == Status::StreamEnd {
+                self.finished = true;
+            } else if self.inflater.total_in() == total_in && self.inflater.total_out() == total_out
+            {
+                return Err(DecoderError::DecompressionError(
+                    "truncated ZLIB stream".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "encoder"))]
+mod tests {
+    use super::*;
+    use crate::builder::StatusListBuilder;
+
+    #[test]
+    fn test_lazy_lookup_stops_early() {
+        let mut statuses = vec![StatusType::Valid; 1_000_000];
+        statuses[10] = StatusType::Invalid;
+        statuses[999_999] = StatusType::Invalid;
+        let status_list = StatusListBuilder::from_vec(statuses, 1)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let mut decoder = LazyStatusListDecoder::new(&status_list)
+            .unwrap()
+            .with_chunk_size(1024);
+        assert_eq!(decoder.get_status(10).unwrap(), StatusType::Invalid);
+        assert_eq!(decoder.get_status(11).unwrap(), StatusType::Valid);
+        assert!(decoder.decompressed_len() < 125_000);
+
+        assert_eq!(decoder.get_status(999_999).unwrap(), StatusType::Invalid);
+        assert_eq!(decoder.decompressed_len(), 125_000);
+
+        match decoder.get_status(1_000_000) {
+            Err(DecoderError::InvalidByteIndex(125_000)) => (),
+            _ => panic!("Expected InvalidByteIndex error"),
+        }
+    }
+
+    #[test]
+    fn test_lazy_decode_errors() {
+        let status_list = StatusListBuilder::from_vec(vec![StatusType::Invalid; 64], 1)
+            .unwrap()
+            .build()
+            .unwrap();
+        let truncated = StatusList {
+            bits: 1,
+            lst: status_list.lst[..4].to_vec(),
+            aggregation_uri: None,
+        };
+
+        match LazyStatusListDecoder::new(&truncated)
+            .unwrap()
+            .get_status(63)
+        {
+            Err(DecoderError::DecompressionError(_)) => (),
+            _ => panic!("Expected DecompressionError"),
+        }
+
+        let invalid = StatusList {
+            bits: 3,
+            ..truncated
+        };
+        assert!(LazyStatusListDecoder::new(&invalid).is_err());
+    }
+}
@@ -1,14 +1,241 @@
+mod allocator;
+pub mod bitops;
+#[cfg(feature = "bitstring")]
+mod bitstring;
+#[cfg(feature = "encoder")]
 mod builder;
+#[cfg(feature = "encoder")]
+mod bulk;
+#[cfg(any(feature = "encoder", feature = "decoder"))]
+mod capacity;
+#[cfg(feature = "changelog")]
+mod changelog;
+#[cfg(any(feature = "http", feature = "wasm-fetch"))]
+mod client;
+mod compression;
+mod conformance;
+#[cfg(feature = "decoder")]
 mod decoder;
+#[cfg(feature = "decoder")]
+mod diff;
+#[cfg(feature = "embedded")]
+mod embedded;
+#[cfg(feature = "encoder")]
 mod encoder;
 mod error;
+#[cfg(feature = "decoder")]
+mod export;
+#[cfg(feature = "uniffi")]
+mod ffi;
+mod format;
+#[cfg(all(feature = "cwt", feature = "decoder"))]
+mod gateway;
+#[cfg(any(feature = "http", feature = "wasm-fetch"))]
+mod holder;
+#[cfg(any(feature = "journal", all(feature = "encoder", feature = "uri")))]
+mod idempotency;
+#[cfg(all(feature = "encoder", feature = "serde"))]
+mod import;
+#[cfg(feature = "journal")]
+mod journal;
+#[cfg(feature = "jwk")]
+mod jwk;
+#[cfg(feature = "jwt")]
+mod jwt;
+#[cfg(feature = "decoder")]
+mod lazy;
+#[cfg(feature = "mapping")]
+mod mapping;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "serde")]
+mod patch;
+#[cfg(feature = "decoder")]
+mod pool;
+#[cfg(all(feature = "cwt", feature = "decoder"))]
+mod readiness;
+#[cfg(feature = "reasons")]
+mod reasons;
+#[cfg(feature = "decoder")]
+mod reconcile;
+mod registry;
+#[cfg(feature = "encoder")]
+mod revocation;
+mod rng;
+#[cfg(all(feature = "encoder", feature = "uri"))]
+mod set;
+mod severity;
+#[cfg(any(feature = "cwt", feature = "changelog", feature = "reasons"))]
+mod signing;
+#[cfg(feature = "test-utils")]
+mod simulation;
+#[cfg(feature = "decoder")]
+mod snapshot;
+#[cfg(feature = "decoder")]
+mod stats;
+#[cfg(feature = "store")]
+mod store;
+#[cfg(feature = "encoder")]
+mod suspension;
+#[cfg(feature = "cwt")]
+mod token;
+#[cfg(any(feature = "http", feature = "wasm-fetch"))]
+mod transport;
 mod types;
+#[cfg(feature = "uri")]
+mod uri;
+#[cfg(feature = "uri")]
+mod validation;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "watch")]
+mod watch;
 
-pub use builder::StatusListBuilder;
-pub use decoder::StatusListDecoder;
+pub use allocator::{AllocationStrategy, AllocatorState, IndexAllocator};
+#[cfg(feature = "bitstring")]
+pub use bitstring::{BitstringStatusList, BITSTRING_MIN_BYTES};
+#[cfg(feature = "encoder")]
+pub use builder::{FillStrategy, SizeAnomaly, StatusListBuilder};
+#[cfg(feature = "encoder")]
+pub use bulk::{ApplyOptions, ApplyProgress};
+#[cfg(feature = "encoder")]
+pub use capacity::{size_estimate, SizeEstimate};
+#[cfg(feature = "changelog")]
+pub use changelog::{ChangelogEntry, StatusListChangelog, CHANGELOG_CONTENT_TYPE};
+#[cfg(any(feature = "http", feature = "wasm-fetch"))]
+pub use client::{FetchedToken, StatusListCache, StatusListClient};
+#[cfg(feature = "libdeflater")]
+pub use compression::LibdeflateCompression;
+pub use compression::{Compressor, Decompressor, ZlibCompression, DEFAULT_MAX_DECOMPRESSED_LEN};
+pub use conformance::Violation;
+#[cfg(feature = "decoder")]
+pub use decoder::{SharedStatusListDecoder, StatusListDecoder};
+#[cfg(feature = "decoder")]
+pub use diff::{
+    DiffBucket, DiffSummary, DiffTimeline, DiffTimelineStep, StatusChange, StatusListDiff,
+    DEFAULT_BUCKET_SIZE,
+};
+#[cfg(feature = "embedded")]
+pub use embedded::{embed_status_list, embedded_status_list, EMBEDDED_STATUS_LIST_HEADER};
+#[cfg(feature = "encoder")]
 pub use encoder::StatusListEncoder;
-pub use error::{BuilderError, StatusTypeError};
-pub use types::{BitsPerStatus, StatusList, StatusType};
+#[cfg(feature = "bitstring")]
+pub use error::BitstringError;
+#[cfg(feature = "changelog")]
+pub use error::ChangelogError;
+#[cfg(any(feature = "http", feature = "wasm-fetch"))]
+pub use error::ClientError;
+#[cfg(feature = "uniffi")]
+pub use error::FfiError;
+#[cfg(feature = "journal")]
+pub use error::JournalError;
+#[cfg(feature = "test-utils")]
+pub use error::LoadTestError;
+#[cfg(feature = "mapping")]
+pub use error::MappingError;
+#[cfg(feature = "mmap")]
+pub use error::MmapError;
+#[cfg(feature = "serde")]
+pub use error::PatchError;
+#[cfg(all(feature = "cwt", feature = "decoder"))]
+pub use error::ReadinessError;
+#[cfg(feature = "reasons")]
+pub use error::ReasonsError;
+#[cfg(all(feature = "encoder", feature = "uri"))]
+pub use error::StatusListSetError;
+#[cfg(feature = "store")]
+pub use error::StoreError;
+#[cfg(feature = "cwt")]
+pub use error::TokenError;
+pub use error::{AllocatorError, BuilderError, FormatError, StatusTypeError};
+#[cfg(feature = "encoder")]
+pub use error::{ApplyError, TransitionError};
+#[cfg(feature = "uri")]
+pub use error::{ReferenceError, UriError};
+#[cfg(feature = "uniffi")]
+pub use ffi::{
+    FfiSignatureVerifier, FfiStatusCheckReport, FfiStatusListCache, FfiStatusListChecker,
+    FfiStatusListDecoder,
+};
+pub use format::{migrate, FormatHeader, FormatKind, Migration, SPEC_DRAFT};
+#[cfg(all(feature = "cwt", feature = "decoder"))]
+pub use gateway::StatusListGateway;
+#[cfg(any(feature = "http", feature = "wasm-fetch"))]
+pub use holder::{CredentialStatus, HolderStatusReport, IssuerStatus, ListFreshness, PlannedFetch};
+#[cfg(any(feature = "journal", all(feature = "encoder", feature = "uri")))]
+pub use idempotency::DEFAULT_KEY_RETENTION;
+#[cfg(all(feature = "encoder", feature = "serde"))]
+pub use import::{ImportReport, LineError};
+#[cfg(feature = "journal")]
+pub use journal::{FileJournal, InMemoryJournal, JournalEntry, JournaledStatusList, StatusJournal};
+#[cfg(feature = "jwk")]
+pub use jwk::{Jwk, JwkSet};
+#[cfg(feature = "jwt")]
+pub use jwt::{JwtStatusListToken, JWT_MEDIA_TYPE, JWT_TYPE};
+#[cfg(feature = "decoder")]
+pub use lazy::LazyStatusListDecoder;
+#[cfg(feature = "mapping")]
+pub use mapping::{InMemoryMappingStore, IndexLocation, MappingStore, ScopedMappingStore};
+#[cfg(feature = "mmap")]
+pub use mmap::MmapStatusList;
+#[cfg(feature = "serde")]
+pub use patch::{PatchEntry, StatusListPatch};
+#[cfg(feature = "decoder")]
+pub use pool::BufferPool;
+#[cfg(all(feature = "cwt", feature = "decoder"))]
+pub use readiness::ReadinessProbe;
+#[cfg(feature = "reasons")]
+pub use reasons::{StatusReasons, REASONS_CONTENT_TYPE};
+#[cfg(feature = "decoder")]
+pub use reconcile::{Divergence, ReconciliationReport};
+pub use registry::{NamedStatus, StatusRegistry};
+#[cfg(feature = "encoder")]
+pub use revocation::RevocationList;
+#[cfg(all(feature = "encoder", feature = "uri"))]
+pub use set::{ListPolicy, StatusListAggregation, StatusListSet, StatusListTenants};
+pub use severity::SeverityOrder;
+#[cfg(any(feature = "cwt", feature = "changelog", feature = "reasons"))]
+pub use signing::{SignatureVerifier, Signer};
+#[cfg(feature = "test-utils")]
+pub use simulation::{ListGenerator, LoadHarness, LoadReport};
+#[cfg(all(feature = "cwt", feature = "decoder"))]
+pub use snapshot::TokenSnapshot;
+#[cfg(feature = "decoder")]
+pub use snapshot::{StatusListSnapshot, StatusRun};
+#[cfg(feature = "decoder")]
+pub use stats::StatusStats;
+#[cfg(feature = "sled")]
+pub use store::SledStatusListStore;
+#[cfg(feature = "store")]
+pub use store::{InMemoryStatusListStore, StatusListStore, StoreMetadata};
+#[cfg(feature = "encoder")]
+pub use suspension::SuspendableStatusList;
+#[cfg(feature = "cwt")]
+pub use token::{CwtStatusListToken, Provenance, CWT_TYPE};
+#[cfg(all(feature = "cwt", feature = "decoder"))]
+pub use token::{ReplayWindow, StatusCheckReport, StatusListTokenVerifier};
+#[cfg(feature = "wasm-fetch")]
+pub use transport::FetchTransport;
+#[cfg(feature = "http")]
+pub use transport::ReqwestTransport;
+#[cfg(any(feature = "http", feature = "wasm-fetch"))]
+pub use transport::{DefaultTransport, Transport, TransportResponse};
+pub use types::{BitsPerStatus, RawStatus, StatusList, StatusType};
+#[cfg(feature = "serde")]
+pub use types::{CborStatusList, JsonStatusList, SerializationError};
+#[cfg(feature = "uri")]
+pub use types::{StatusClaim, StatusListReference};
+#[cfg(feature = "uri")]
+pub use uri::{StatusListUri, DEFAULT_URI_SCHEMES};
+#[cfg(feature = "uri")]
+pub use validation::ReferenceValidator;
+#[cfg(feature = "wasm")]
+pub use wasm::{WasmStatusListBuilder, WasmStatusListDecoder};
+#[cfg(feature = "watch")]
+pub use watch::WatchableStatusList;
 
-#[cfg(test)]
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
+
+#[cfg(all(test, feature = "encoder", feature = "decoder", feature = "serde"))]
 mod tests;
@@ -0,0 +1,331 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use crate::error::MappingError;
+use crate::uri::StatusListUri;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IndexLocation {
+    pub uri: StatusListUri,
+    pub idx: usize,
+}
+
+impl IndexLocation {
+    pub fn new(uri: StatusListUri, idx: usize) -> Self {
+        Self { uri, idx }
+    }
+}
+
+pub trait MappingStore {
+    fn insert(&self, id: &str, location: IndexLocation) -> Result<(), MappingError>;
+
+    fn location_of(&self, id: &str) -> Result<Option<IndexLocation>, MappingError>;
+
+    fn id_at(&self, location: &IndexLocation) -> Result<Option<String>, MappingError>;
+
+    fn remove(&self, id: &str) -> Result<Option<IndexLocation>, MappingError>;
+
+    /// Records several mappings at once; on error none of them is kept.
+    fn insert_all(&self, entries: &[(String, IndexLocation)]) -> Result<(), MappingError> {
+        for (inserted, (id, location)) in entries.iter().enumerate() {
+            if let Err(e) = self.insert(id, location.clone()) {
+                for (id, _) in &entries[..inserted] {
+                    self.remove(id)?;
+                }
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct Mappings {
+    by_id: HashMap<String, IndexLocation>,
+    by_location: HashMap<IndexLocation, String>,
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryMappingStore {
+    mappings: Mutex<Mappings>,
+}
+
+impl InMemoryMappingStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.mappings.lock().unwrap().by_id.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl MappingStore for InMemoryMappingStore {
+    fn insert(&self, id: &str, location: IndexLocation) -> Result<(), MappingError> {
+        let mut mappings = self.mappings.lock().unwrap();
+        if mappings.by_id.contains_key(id) {
+            return Err(MappingError::DuplicateId(id.to_string()));
+        }
+        if mappings.by_location.contains_key(&location) {
+            return Err(MappingError::IndexTaken(
+                location.uri.to_string(),
+                location.idx,
+            ));
+        }
+
+        mappings
+            .by_location
+            .insert(location.clone(), id.to_string());
+        mappings.by_id.insert(id.to_string(), location);
+        Ok(())
+    }
+
+    fn location_of(&self, id: &str) -> Result<Option<IndexLocation>, MappingError> {
+        Ok(self.mappings.lock().unwrap().by_id.get(id).cloned())
+    }
+
+    fn id_at(&self, location: &IndexLocation) -> Result<Option<String>, MappingError> {
+        Ok(self
+            .mappings
+            .lock()
+            .unwrap()
+            .by_location
+            .get(location)
+            .cloned())
+    }
+
+    fn remove(&self, id: &str) -> Result<Option<IndexLocation>, MappingError> {
+        let mut mappings = self.mappings.lock().unwrap();
+        let location = mappings.by_id.remove(id);
+        if let Some(location) = &location {
+            mappings.by_location.remove(location);
+        }
+        Ok(location)
+    }
+
+    fn insert_all(&self, entries: &[(String, IndexLocation)]) -> Result<(), MappingError> {
+        let mut mappings = self.mappings.lock().unwrap();
+        let (mut ids, mut locations) = (HashSet::new(), HashSet::new());
+        for (id, location) in entries {
+            if mappings.by_id.contains_key(id) || !ids.insert(id) {
+                return Err(MappingError::DuplicateId(id.clone()));
+            }
+            if mappings.by_location.contains_key(location) || !locations.insert(location) {
+                return Err(MappingError::IndexTaken(
+                    location.uri.to_string(),
+                    location.idx,
+                ));
+            }
+        }
+
+        for (id, location) in entries {
+            mappings.by_location.insert(location.clone(), id.clone());
+            mappings.by_id.insert(id.clone(), location.clone());
+        }
+        Ok(())
+    }
+}
+
+/// One tenant's view of a shared `MappingStore`. Credential IDs are stored
+/// under the tenant's namespace, so tenants may reuse the same IDs.
+#[derive(Debug)]
+pub struct ScopedMappingStore<'a, S: ?Sized> {
+    store: &'a S,
+    prefix: String,
+}
+
+impl<'a, S: MappingStore + ?Sized> ScopedMappingStore<'a, S> {
+    pub fn new(store: &'a S, tenant: &str) -> Self {
+        // The length keeps namespaces apart whatever the tenant names contain.
+        Self {
+            store,
+            prefix: format!("{}:{}:", tenant.len(), tenant),
+        }
+    }
+
+    fn scoped(&self, id: &str) -> String {
+        format!("{}{}", self.prefix, id)
+    }
+
+    fn unscoped(&self, error: MappingError) -> MappingError {
+        match error {
+            MappingError::DuplicateId(id) => {
+                MappingError::DuplicateId(id.strip_prefix(&self.prefix).unwrap_or(&id).to_string())
+            }
+            error => error,
+        }
+    }
+}
+
+impl<S: MappingStore + ?Sized> MappingStore for ScopedMappingStore<'_, S> {
+    fn insert(&self, id: &str, location: IndexLocation) -> Result<(), MappingError> {
+        self.store
+            .insert(&self.scoped(id), location)
+            .map_err(|e| self.unscoped(e))
+    }
+
+    fn location_of(&self, id: &str) -> Result<Option<IndexLocation>, MappingError> {
+        self.store.location_of(&self.scoped(id))
+    }
+
+    fn id_at(&self, location: &IndexLocation) -> Result<Option<String>, MappingError> {
+        Ok(self
+            .store
+            .id_at(location)?
+            .and_then(|id| id.strip_prefix(&self.prefix).map(str::to_string)))
+    }
+
+    fn remove(&self, id: &str) -> Result<Option<IndexLocation>, MappingError> {
+        self.store.remove(&self.scoped(id))
+    }
+
+    fn insert_all(&self, entries: &[(String, IndexLocation)]) -> Result<(), MappingError> {
+        let entries: Vec<_> = entries
+            .iter()
+            .map(|(id, location)| (self.scoped(id), location.clone()))
+            .collect();
+        self.store
+            .insert_all(&entries)
+            .map_err(|e| self.unscoped(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(idx: usize) -> IndexLocation {
+        let uri = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        IndexLocation::new(uri, idx)
+    }
+
+    #[test]
+    fn test_bidirectional_lookup() {
+        let store = InMemoryMappingStore::new();
+        store.insert("urn:credential:1", location(7)).unwrap();
+        store.insert("urn:credential:2", location(8)).unwrap();
+
+        assert_eq!(
+            store.location_of("urn:credential:1").unwrap(),
+            Some(location(7))
+        );
+        assert_eq!(
+            store.id_at(&location(8)).unwrap(),
+            Some("urn:credential:2".to_string())
+        );
+        assert_eq!(store.location_of("urn:credential:3").unwrap(), None);
+        assert_eq!(store.len(), 2);
+
+        assert_eq!(store.remove("urn:credential:1").unwrap(), Some(location(7)));
+        assert_eq!(store.id_at(&location(7)).unwrap(), None);
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn test_conflicting_inserts() {
+        let store = InMemoryMappingStore::new();
+        store.insert("urn:credential:1", location(7)).unwrap();
+
+        match store.insert("urn:credential:1", location(9)) {
+            Err(MappingError::DuplicateId(id)) => assert_eq!(id, "urn:credential:1"),
+            _ => panic!("Expected DuplicateId error"),
+        }
+        match store.insert("urn:credential:2", location(7)) {
+            Err(MappingError::IndexTaken(uri, 7)) => {
+                assert_eq!(uri, "https://example.com/statuslists/1")
+            }
+            _ => panic!("Expected IndexTaken error"),
+        }
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn test_insert_all() {
+        let store = InMemoryMappingStore::new();
+        store.insert("urn:credential:1", location(1)).unwrap();
+        let entries = |ids: &[(&str, usize)]| -> Vec<(String, IndexLocation)> {
+            ids.iter()
+                .map(|&(id, idx)| (id.to_string(), location(idx)))
+                .collect()
+        };
+
+        match store.insert_all(&entries(&[
+            ("urn:credential:2", 2),
+            ("urn:credential:1", 3),
+        ])) {
+            Err(MappingError::DuplicateId(id)) => assert_eq!(id, "urn:credential:1"),
+            _ => panic!("Expected DuplicateId error"),
+        }
+        match store.insert_all(&entries(&[
+            ("urn:credential:2", 2),
+            ("urn:credential:3", 2),
+        ])) {
+            Err(MappingError::IndexTaken(_, 2)) => (),
+            _ => panic!("Expected IndexTaken error"),
+        }
+        assert_eq!(store.len(), 1);
+
+        store
+            .insert_all(&entries(&[
+                ("urn:credential:2", 2),
+                ("urn:credential:3", 3),
+            ]))
+            .unwrap();
+        assert_eq!(store.len(), 3);
+
+        let scoped = ScopedMappingStore::new(&store, "acme");
+        match scoped.insert_all(&entries(&[
+            ("urn:credential:9", 9),
+            ("urn:credential:9", 8),
+        ])) {
+            Err(MappingError::DuplicateId(id)) => assert_eq!(id, "urn:credential:9"),
+            _ => panic!("Expected DuplicateId error"),
+        }
+        scoped
+            .insert_all(&entries(&[("urn:credential:1", 9)]))
+            .unwrap();
+        assert_eq!(
+            scoped.id_at(&location(9)).unwrap().unwrap(),
+            "urn:credential:1"
+        );
+    }
+
+    #[test]
+    fn test_scoped_stores() {
+        let store = InMemoryMappingStore::new();
+        let acme = ScopedMappingStore::new(&store, "acme");
+        let globex = ScopedMappingStore::new(&store, "globex");
+        let other = IndexLocation::new(
+            StatusListUri::parse("https://example.com/statuslists/2").unwrap(),
+            7,
+        );
+
+        acme.insert("urn:credential:1", location(7)).unwrap();
+        globex.insert("urn:credential:1", other.clone()).unwrap();
+        assert_eq!(
+            acme.location_of("urn:credential:1").unwrap(),
+            Some(location(7))
+        );
+        assert_eq!(
+            globex.location_of("urn:credential:1").unwrap(),
+            Some(other.clone())
+        );
+        assert_eq!(
+            acme.id_at(&location(7)).unwrap(),
+            Some("urn:credential:1".to_string())
+        );
+        assert_eq!(globex.id_at(&location(7)).unwrap(), None);
+        assert_eq!(store.location_of("urn:credential:1").unwrap(), None);
+
+        match acme.insert("urn:credential:1", location(8)) {
+            Err(MappingError::DuplicateId(id)) => assert_eq!(id, "urn:credential:1"),
+            _ => panic!("Expected DuplicateId error"),
+        }
+        assert_eq!(globex.remove("urn:credential:1").unwrap(), Some(other));
+        assert_eq!(store.len(), 1);
+    }
+}
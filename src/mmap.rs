@@ -0,0 +1,155 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::bitops;
+use crate::decoder::StatusListDecoder;
+use crate::error::{DecoderError, MmapError};
+use crate::types::{BitsPerStatus, StatusList, StatusType};
+
+const MAGIC: &[u8; 4] = b"TSL\x01";
+// Magic, bit width, three reserved bytes and the status count.
+const HEADER_LEN: usize = 16;
+
+// A decompressed list in a read-only memory map, so verifier processes on one
+// host share a single copy through the page cache. The file is only ever
+// replaced by rename, never rewritten, so existing maps stay valid and keep
+// serving the list they opened until they reopen.
+#[derive(Debug)]
+pub struct MmapStatusList {
+    map: Mmap,
+    bits_per_status: BitsPerStatus,
+    len: usize,
+}
+
+impl MmapStatusList {
+    /// Decompresses `status_list` and atomically replaces the file at `path`.
+    pub fn publish(path: impl AsRef<Path>, status_list: &StatusList) -> Result<(), MmapError> {
+        let path = path.as_ref();
+        let decoder = StatusListDecoder::new(status_list).map_err(MmapError::DecoderError)?;
+
+        let mut header = [0u8; HEADER_LEN];
+        header[..4].copy_from_slice(MAGIC);
+        header[4] = status_list.bits();
+        header[8..].copy_from_slice(&(decoder.len() as u64).to_le_bytes());
+
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        let write = || -> std::io::Result<()> {
+            let mut file = File::create(&tmp_path)?;
+            file.write_all(&header)?;
+            file.write_all(decoder.get_raw_bytes())?;
+            file.sync_all()?;
+            fs::rename(&tmp_path, path)
+        };
+        write().map_err(|e| MmapError::StorageError(e.to_string()))
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, MmapError> {
+        let file = File::open(path).map_err(|e| MmapError::StorageError(e.to_string()))?;
+        // Safety: publish() never modifies a file in place, so the mapped
+        // bytes do not change underneath us.
+        let map =
+            unsafe { Mmap::map(&file) }.map_err(|e| MmapError::StorageError(e.to_string()))?;
+
+        if map.len() < HEADER_LEN || &map[..4] != MAGIC {
+            return Err(MmapError::InvalidFile(
+                "missing status list header".to_string(),
+            ));
+        }
+        let bits_per_status = BitsPerStatus::try_from(map[4])
+            .map_err(|_| MmapError::InvalidFile(format!("invalid bit width {}", map[4])))?;
+        let len = u64::from_le_bytes(map[8..HEADER_LEN].try_into().unwrap()) as usize;
+        let capacity = (map.len() - HEADER_LEN) * bitops::statuses_per_byte(bits_per_status);
+        if len > capacity {
+            return Err(MmapError::InvalidFile(format!(
+                "length {} exceeds the {} statuses in the file",
+                len, capacity
+            )));
+        }
+
+        Ok(Self {
+            map,
+            bits_per_status,
+            len,
+        })
+    }
+
+    pub fn get_status(&self, index: usize) -> Result<StatusType, DecoderError> {
+        if index >= self.len {
+            return Err(DecoderError::IndexNotPresent(index));
+        }
+        bitops::decode_status(self.raw_bytes(), index, self.bits_per_status)
+            .map(StatusType::from)
+            .map_err(|_| {
+                DecoderError::InvalidByteIndex(
+                    index / bitops::statuses_per_byte(self.bits_per_status),
+                )
+            })
+    }
+
+    pub fn get_bits_per_status(&self) -> u8 {
+        self.bits_per_status as u8
+    }
+
+    pub fn raw_bytes(&self) -> &[u8] {
+        &self.map[HEADER_LEN..]
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+#[cfg(all(test, feature = "encoder"))]
+mod tests {
+    use super::*;
+
+    fn path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("{}-{}.tsl", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_publish_and_open() {
+        let path = path("mmap-publish");
+        let status_list = StatusList::from_packed_bytes(2, &[0b1001_0100, 0x00]).unwrap();
+        MmapStatusList::publish(&path, &status_list).unwrap();
+
+        let mapped = MmapStatusList::open(&path).unwrap();
+        assert_eq!(mapped.get_bits_per_status(), 2);
+        assert_eq!(mapped.len(), 8);
+        assert_eq!(mapped.get_status(1).unwrap(), StatusType::Invalid);
+        assert_eq!(mapped.get_status(3).unwrap(), StatusType::Suspended);
+        assert!(matches!(
+            mapped.get_status(8),
+            Err(DecoderError::IndexNotPresent(8))
+        ));
+
+        // Readers keep the list they opened until they reopen.
+        let replacement = StatusList::from_packed_bytes(2, &[0x00]).unwrap();
+        MmapStatusList::publish(&path, &replacement).unwrap();
+        assert_eq!(mapped.get_status(1).unwrap(), StatusType::Invalid);
+        let reopened = MmapStatusList::open(&path).unwrap();
+        assert_eq!(reopened.get_status(1).unwrap(), StatusType::Valid);
+        assert_eq!(reopened.len(), 4);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_open_rejects_other_files() {
+        let path = path("mmap-invalid");
+        fs::write(&path, b"not a status list").unwrap();
+        match MmapStatusList::open(&path) {
+            Err(MmapError::InvalidFile(msg)) => assert_eq!(msg, "missing status list header"),
+            _ => panic!("Expected InvalidFile"),
+        }
+        fs::remove_file(&path).unwrap();
+    }
+}
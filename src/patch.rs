@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "encoder")]
+use crate::builder::StatusListBuilder;
+#[cfg(feature = "decoder")]
+use crate::diff::{StatusChange, StatusListDiff};
+#[cfg(feature = "encoder")]
+use crate::error::BuilderError;
+use crate::error::PatchError;
+use crate::types::StatusType;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct PatchEntry {
+    pub idx: usize,
+    pub status: u8,
+}
+
+// The statuses an issuer node changed since it last reported, so a central
+// publisher can apply them instead of receiving the whole list. `len`, when
+// set, is the length the list grows to.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StatusListPatch {
+    pub bits: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub len: Option<usize>,
+    pub changes: Vec<PatchEntry>,
+}
+
+impl StatusListPatch {
+    pub fn new(bits: u8) -> Self {
+        Self {
+            bits,
+            len: None,
+            changes: Vec::new(),
+        }
+    }
+
+    pub fn with_len(mut self, len: usize) -> Self {
+        self.len = Some(len);
+        self
+    }
+
+    pub fn record(&mut self, idx: usize, status: StatusType) -> &mut Self {
+        self.changes.push(PatchEntry {
+            idx,
+            status: status.value(),
+        });
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len.is_none() && self.changes.is_empty()
+    }
+
+    /// Turns a diff into a patch. Lists only grow, so a diff that removes
+    /// indices has no patch. Added Valid indices only extend `len`.
+    #[cfg(feature = "decoder")]
+    pub fn from_diff(bits: u8, diff: &StatusListDiff) -> Result<Self, PatchError> {
+        let mut patch = Self::new(bits);
+        for change in &diff.changes {
+            match *change {
+                StatusChange::Modified { index, new, .. } => {
+                    patch.record(index, new);
+                }
+                StatusChange::Added { index, status } => {
+                    patch.len = Some(index + 1);
+                    if status != StatusType::Valid {
+                        patch.record(index, status);
+                    }
+                }
+                StatusChange::Removed { index, .. } => {
+                    return Err(PatchError::RemovedIndex(index));
+                }
+            }
+        }
+        Ok(patch)
+    }
+
+    pub fn to_json(&self) -> Result<String, PatchError> {
+        serde_json::to_string(self).map_err(|e| PatchError::EncodingError(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, PatchError> {
+        serde_json::from_str(json).map_err(|e| PatchError::EncodingError(e.to_string()))
+    }
+
+    pub fn to_cbor_bytes(&self) -> Result<Vec<u8>, PatchError> {
+        let mut cbor = Vec::new();
+        ciborium::ser::into_writer(self, &mut cbor)
+            .map_err(|e| PatchError::EncodingError(e.to_string()))?;
+        Ok(cbor)
+    }
+
+    pub fn from_cbor(cbor: &[u8]) -> Result<Self, PatchError> {
+        ciborium::de::from_reader(cbor).map_err(|e| PatchError::EncodingError(e.to_string()))
+    }
+
+    #[cfg(feature = "encoder")]
+    fn check(&self, bits: u8) -> Result<(), PatchError> {
+        if self.bits != bits {
+            return Err(PatchError::BitsMismatch(bits, self.bits));
+        }
+        match self
+            .changes
+            .iter()
+            .find(|entry| (entry.status as u16) >> bits != 0)
+        {
+            Some(entry) => Err(PatchError::InvalidEntry(entry.idx)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "encoder")]
+impl StatusListBuilder {
+    /// Applies every change in `patch`, or none of them if any entry does not
+    /// fit this builder's bit width or maximum length.
+    pub fn apply_patch(&self, patch: &StatusListPatch) -> Result<&Self, PatchError> {
+        patch.check(self.get_bits_per_status())?;
+        let past_max = patch.len.is_some_and(|len| len > self.max_len())
+            || patch
+                .changes
+                .iter()
+                .any(|entry| entry.idx >= self.max_len());
+        if past_max {
+            return Err(PatchError::BuilderError(BuilderError::MaxLenExceeded(
+                self.max_len(),
+            )));
+        }
+
+        if let Some(len) = patch.len {
+            self.try_pad_to(len).map_err(PatchError::BuilderError)?;
+        }
+        for entry in &patch.changes {
+            self.try_set_status(entry.idx, StatusType::from(entry.status))
+                .map_err(PatchError::BuilderError)?;
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(all(test, feature = "encoder", feature = "decoder"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patch_from_diff() {
+        let central = StatusListBuilder::from_vec(vec![StatusType::Valid; 16], 2).unwrap();
+        let node = StatusListBuilder::from_vec(vec![StatusType::Valid; 16], 2).unwrap();
+        let before = node.build().unwrap();
+        node.set_status(4, StatusType::Suspended)
+            .set_status(17, StatusType::Invalid)
+            .pad_to(20);
+
+        let diff = before.diff(&node.build().unwrap()).unwrap();
+        let patch = StatusListPatch::from_diff(2, &diff).unwrap();
+        assert_eq!(patch.len, Some(20));
+        assert_eq!(
+            patch.changes,
+            vec![
+                PatchEntry { idx: 4, status: 2 },
+                PatchEntry { idx: 17, status: 1 },
+            ]
+        );
+
+        let shipped = StatusListPatch::from_cbor(&patch.to_cbor_bytes().unwrap()).unwrap();
+        central.apply_patch(&shipped).unwrap();
+        assert_eq!(central.build().unwrap(), node.build().unwrap());
+
+        match StatusListPatch::from_diff(2, &node.build().unwrap().diff(&before).unwrap()) {
+            Err(PatchError::RemovedIndex(16)) => (),
+            _ => panic!("Expected RemovedIndex"),
+        }
+    }
+
+    #[test]
+    fn test_patch_json() {
+        let mut patch = StatusListPatch::new(1);
+        patch.record(3, StatusType::Invalid);
+        let json = patch.to_json().unwrap();
+        assert_eq!(json, r#"{"bits":1,"changes":[{"idx":3,"status":1}]}"#);
+        assert_eq!(StatusListPatch::from_json(&json).unwrap(), patch);
+    }
+
+    #[test]
+    fn test_apply_patch_is_all_or_nothing() {
+        let builder = StatusListBuilder::from_vec(vec![StatusType::Valid; 8], 1).unwrap();
+
+        let mut patch = StatusListPatch::new(1);
+        patch
+            .record(0, StatusType::Invalid)
+            .record(1, StatusType::Suspended);
+        match builder.apply_patch(&patch) {
+            Err(PatchError::InvalidEntry(1)) => (),
+            _ => panic!("Expected InvalidEntry"),
+        }
+        assert_eq!(builder.get_status(0), Some(StatusType::Valid));
+
+        match builder.apply_patch(&StatusListPatch::new(2)) {
+            Err(PatchError::BitsMismatch(1, 2)) => (),
+            _ => panic!("Expected BitsMismatch"),
+        }
+
+        let builder = builder.with_max_len(16);
+        let mut patch = StatusListPatch::new(1);
+        patch
+            .record(0, StatusType::Invalid)
+            .record(16, StatusType::Invalid);
+        match builder.apply_patch(&patch) {
+            Err(PatchError::BuilderError(BuilderError::MaxLenExceeded(16))) => (),
+            _ => panic!("Expected MaxLenExceeded"),
+        }
+        assert_eq!(builder.get_status(0), Some(StatusType::Valid));
+    }
+}
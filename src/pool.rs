@@ -0,0 +1,117 @@
+use std::sync::Mutex;
+
+use crate::decoder::StatusListDecoder;
+use crate::error::DecoderError;
+use crate::types::StatusList;
+
+#[derive(Debug)]
+pub struct BufferPool {
+    buffers: Mutex<Vec<Vec<u8>>>,
+    max_buffers: usize,
+}
+
+impl BufferPool {
+    pub fn new(max_buffers: usize) -> Self {
+        Self {
+            buffers: Mutex::new(Vec::new()),
+            max_buffers,
+        }
+    }
+
+    pub fn take(&self) -> Vec<u8> {
+        self.buffers.lock().unwrap().pop().unwrap_or_default()
+    }
+
+    pub fn put(&self, mut buffer: Vec<u8>) {
+        let mut buffers = self.buffers.lock().unwrap();
+        if buffers.len() < self.max_buffers {
+            buffer.clear();
+            buffers.push(buffer);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffers.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn decode(&self, status_list: &StatusList) -> Result<StatusListDecoder, DecoderError> {
+        let buffer = self.take();
+        StatusListDecoder::from_buffer(status_list, buffer)
+    }
+
+    pub fn decode_base64(
+        &self,
+        bits: u8,
+        base64_str: &str,
+    ) -> Result<StatusListDecoder, DecoderError> {
+        let compressed =
+            base64url::decode(base64_str).map_err(|e| DecoderError::Base64Error(e.to_string()))?;
+        let status_list = StatusList {
+            bits,
+            lst: compressed,
+            aggregation_uri: None,
+        };
+
+        let decoder = self.decode(&status_list);
+        self.put(status_list.lst);
+        decoder
+    }
+
+    pub fn recycle(&self, decoder: StatusListDecoder) {
+        self.put(decoder.into_raw_bytes());
+    }
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        Self::new(64)
+    }
+}
+
+#[cfg(all(test, feature = "encoder"))]
+mod tests {
+    use super::*;
+    use crate::builder::StatusListBuilder;
+    use crate::types::StatusType;
+
+    #[test]
+    fn test_pool_reuses_buffers() {
+        let pool = BufferPool::new(2);
+        let status_list = StatusListBuilder::from_vec(vec![StatusType::Invalid; 16], 1)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let decoder = pool.decode(&status_list).unwrap();
+        assert_eq!(decoder.get_status(15).unwrap(), StatusType::Invalid);
+        assert!(pool.is_empty());
+
+        pool.recycle(decoder);
+        assert_eq!(pool.len(), 1);
+
+        let decoder = pool.decode(&status_list).unwrap();
+        assert!(pool.is_empty());
+        pool.recycle(decoder);
+
+        pool.put(Vec::new());
+        pool.put(Vec::new());
+        assert_eq!(pool.len(), 2);
+    }
+
+    #[test]
+    fn test_pool_decode_base64() {
+        let pool = BufferPool::default();
+        let decoder = pool.decode_base64(1, "eNrbuRgAAhcBXQ").unwrap();
+
+        assert_eq!(decoder.get_raw_bytes(), &[0xB9, 0xA3]);
+        assert_eq!(decoder.get_bits_per_status(), 1);
+        assert_eq!(pool.len(), 1);
+
+        assert!(pool.decode_base64(1, "!!!").is_err());
+        assert!(pool.decode_base64(3, "eNrbuRgAAhcBXQ").is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use crate::error::ReadinessError;
+use crate::signing::SignatureVerifier;
+use crate::token::{Freshness, StatusListTokenVerifier};
+
+/// A readiness check for a status list server: the token it serves must
+/// parse, verify against the configured key and claims, and stay fresh for
+/// at least `margin` more seconds, so a bad publish fails the probe before
+/// relying parties see it.
+#[derive(Debug, Clone)]
+pub struct ReadinessProbe {
+    token_verifier: StatusListTokenVerifier,
+    margin: u64,
+}
+
+impl ReadinessProbe {
+    pub fn new(token_verifier: StatusListTokenVerifier, margin: u64) -> Self {
+        Self {
+            token_verifier,
+            margin,
+        }
+    }
+
+    /// Returns when the served token goes stale, if it ever does.
+    pub fn check_cwt(
+        &self,
+        bytes: &[u8],
+        verifier: &impl SignatureVerifier,
+        now: u64,
+    ) -> Result<Option<u64>, ReadinessError> {
+        let (_, freshness) = self
+            .token_verifier
+            .verify_cwt_until(bytes, verifier, now)
+            .map_err(ReadinessError::TokenError)?;
+        self.check(freshness, now)
+    }
+
+    #[cfg(feature = "jwt")]
+    pub fn check_jwt(
+        &self,
+        token: &str,
+        verifier: &impl SignatureVerifier,
+        now: u64,
+    ) -> Result<Option<u64>, ReadinessError> {
+        let (_, freshness) = self
+            .token_verifier
+            .verify_jwt_until(token, verifier, now)
+            .map_err(ReadinessError::TokenError)?;
+        self.check(freshness, now)
+    }
+
+    fn check(&self, freshness: Freshness, now: u64) -> Result<Option<u64>, ReadinessError> {
+        let stale_at = freshness.stale_at();
+        match stale_at {
+            Some(stale_at) if now.saturating_add(self.margin) >= stale_at => {
+                Err(ReadinessError::NearStale(stale_at))
+            }
+            _ => Ok(stale_at),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "encoder"))]
+mod tests {
+    use super::*;
+    use crate::builder::StatusListBuilder;
+    use crate::error::TokenError;
+    #[cfg(feature = "jwt")]
+    use crate::jwt::JwtStatusListToken;
+    use crate::signing::tests::TestKey;
+    use crate::token::CwtStatusListToken;
+    use crate::types::StatusType;
+    use crate::uri::StatusListUri;
+
+    fn token() -> CwtStatusListToken {
+        let status_list = StatusListBuilder::from_vec(vec![StatusType::Invalid; 2], 1)
+            .unwrap()
+            .build()
+            .unwrap();
+        let sub = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        CwtStatusListToken::new(sub, 1000, status_list)
+            .with_exp(5000)
+            .with_ttl(600)
+    }
+
+    fn probe(margin: u64) -> ReadinessProbe {
+        let uri = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        ReadinessProbe::new(StatusListTokenVerifier::new(uri), margin)
+    }
+
+    #[test]
+    fn test_probe() {
+        let signed = token().sign(&TestKey(1)).unwrap();
+
+        assert_eq!(
+            probe(60).check_cwt(&signed, &TestKey(1), 1500).unwrap(),
+            Some(1600)
+        );
+        assert!(matches!(
+            probe(60).check_cwt(&signed, &TestKey(1), 1540),
+            Err(ReadinessError::NearStale(1600))
+        ));
+        assert!(matches!(
+            probe(0).check_cwt(&signed, &TestKey(1), 1500),
+            Ok(Some(1600))
+        ));
+        assert!(matches!(
+            probe(0).check_cwt(&signed, &TestKey(2), 1500),
+            Err(ReadinessError::TokenError(TokenError::SignatureError(_)))
+        ));
+
+        let unbounded = CwtStatusListToken {
+            exp: None,
+            ttl: None,
+            ..token()
+        };
+        let signed = unbounded.sign(&TestKey(1)).unwrap();
+        assert_eq!(
+            probe(60).check_cwt(&signed, &TestKey(1), 9000).unwrap(),
+            None
+        );
+    }
+
+    #[cfg(feature = "jwt")]
+    #[test]
+    fn test_probe_jwt() {
+        let token = token();
+        let jwt = JwtStatusListToken::new(token.sub, token.iat, token.status_list)
+            .with_exp(1200)
+            .sign(&TestKey(1))
+            .unwrap();
+
+        assert_eq!(
+            probe(60).check_jwt(&jwt, &TestKey(1), 1100).unwrap(),
+            Some(1200)
+        );
+        assert!(matches!(
+            probe(60).check_jwt(&jwt, &TestKey(1), 1150),
+            Err(ReadinessError::NearStale(1200))
+        ));
+        assert!(matches!(
+            probe(60).check_jwt(&jwt, &TestKey(1), 1200),
+            Err(ReadinessError::TokenError(TokenError::Expired(1200)))
+        ));
+    }
+}
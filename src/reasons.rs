@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "decoder")]
+use crate::decoder::StatusListDecoder;
+#[cfg(feature = "decoder")]
+use crate::error::DecoderError;
+use crate::error::ReasonsError;
+use crate::signing::{self, PayloadType, SignatureVerifier, Signer};
+use crate::types::StatusType;
+use crate::uri::StatusListUri;
+
+pub const REASONS_CONTENT_TYPE: &str = "application/statuslist-reasons+cbor";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StatusReasons {
+    pub uri: StatusListUri,
+    pub reasons: BTreeMap<u8, String>,
+}
+
+impl StatusReasons {
+    pub fn new(uri: StatusListUri) -> Self {
+        Self {
+            uri,
+            reasons: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_reason(mut self, status: StatusType, code: impl Into<String>) -> Self {
+        self.reasons.insert(status.value(), code.into());
+        self
+    }
+
+    pub fn reason_for(&self, status: StatusType) -> Option<&str> {
+        self.reasons.get(&status.value()).map(String::as_str)
+    }
+
+    #[cfg(feature = "decoder")]
+    pub fn reason_at(
+        &self,
+        decoder: &StatusListDecoder,
+        index: usize,
+    ) -> Result<(StatusType, Option<&str>), DecoderError> {
+        let status = decoder.get_status(index)?;
+        Ok((status, self.reason_for(status)))
+    }
+
+    pub fn sign(&self, signer: &impl Signer) -> Result<Vec<u8>, ReasonsError> {
+        let mut payload = Vec::new();
+        ciborium::ser::into_writer(self, &mut payload)
+            .map_err(|e| ReasonsError::EncodingError(e.to_string()))?;
+
+        Ok(signing::sign_cose(
+            PayloadType::ContentType(REASONS_CONTENT_TYPE),
+            payload,
+            signer,
+        )?)
+    }
+
+    pub fn verify(bytes: &[u8], verifier: &impl SignatureVerifier) -> Result<Self, ReasonsError> {
+        let payload = signing::verify_cose(
+            bytes,
+            PayloadType::ContentType(REASONS_CONTENT_TYPE),
+            verifier,
+        )?;
+        ciborium::de::from_reader(&payload[..])
+            .map_err(|e| ReasonsError::EncodingError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing::tests::TestKey;
+
+    fn sample_reasons() -> StatusReasons {
+        let uri = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        StatusReasons::new(uri)
+            .with_reason(StatusType::Invalid, "key-compromise")
+            .with_reason(StatusType::ApplicationSpecific3, "superseded")
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let reasons = sample_reasons();
+        let signed = reasons.sign(&TestKey(3)).unwrap();
+
+        let verified = StatusReasons::verify(&signed, &TestKey(3)).unwrap();
+        assert_eq!(verified, reasons);
+        assert_eq!(
+            verified.reason_for(StatusType::Invalid),
+            Some("key-compromise")
+        );
+        assert_eq!(verified.reason_for(StatusType::Suspended), None);
+
+        match StatusReasons::verify(&signed, &TestKey(4)) {
+            Err(ReasonsError::SignatureError(msg)) => assert_eq!(msg, "bad signature"),
+            _ => panic!("Expected SignatureError"),
+        }
+    }
+
+    #[cfg(all(feature = "encoder", feature = "decoder"))]
+    #[test]
+    fn test_reason_at() {
+        let status_list = crate::builder::StatusListBuilder::from_vec(
+            vec![StatusType::Valid, StatusType::ApplicationSpecific3],
+            2,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        let decoder = StatusListDecoder::new(&status_list).unwrap();
+        let reasons = sample_reasons();
+
+        assert_eq!(
+            reasons.reason_at(&decoder, 1).unwrap(),
+            (StatusType::ApplicationSpecific3, Some("superseded"))
+        );
+        assert_eq!(
+            reasons.reason_at(&decoder, 0).unwrap(),
+            (StatusType::Valid, None)
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use crate::bitops;
+use crate::decoder::StatusListDecoder;
+use crate::types::StatusType;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Divergence {
+    Mismatch {
+        index: usize,
+        expected: StatusType,
+        found: u8,
+    },
+    Missing {
+        index: usize,
+        expected: StatusType,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReconciliationReport {
+    pub checked: usize,
+    pub divergences: Vec<Divergence>,
+}
+
+impl ReconciliationReport {
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+impl StatusListDecoder {
+    pub fn reconcile(
+        &self,
+        expected: impl IntoIterator<Item = (usize, StatusType)>,
+    ) -> ReconciliationReport {
+        let bits = self.bits();
+        let len = self.len();
+        let mut report = ReconciliationReport::default();
+
+        for (index, expected) in expected {
+            report.checked += 1;
+            let found = if index < len {
+                bitops::decode_status(self.get_raw_bytes(), index, bits).ok()
+            } else {
+                None
+            };
+
+            match found {
+                Some(found) if found == expected.value() => (),
+                Some(found) => report.divergences.push(Divergence::Mismatch {
+                    index,
+                    expected,
+                    found,
+                }),
+                None => report
+                    .divergences
+                    .push(Divergence::Missing { index, expected }),
+            }
+        }
+        report
+    }
+}
+
+#[cfg(all(test, feature = "encoder"))]
+mod tests {
+    use super::*;
+    use crate::builder::StatusListBuilder;
+
+    #[test]
+    fn test_reconcile() {
+        let status_list = StatusListBuilder::from_vec(
+            vec![
+                StatusType::Valid,
+                StatusType::Invalid,
+                StatusType::Suspended,
+            ],
+            2,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        let decoder = StatusListDecoder::new(&status_list)
+            .unwrap()
+            .with_len(3)
+            .unwrap();
+
+        let report = decoder.reconcile(vec![
+            (0, StatusType::Valid),
+            (1, StatusType::Invalid),
+            (2, StatusType::Invalid),
+            (3, StatusType::Valid),
+        ]);
+
+        assert_eq!(report.checked, 4);
+        assert!(!report.is_consistent());
+        assert_eq!(
+            report.divergences,
+            vec![
+                Divergence::Mismatch {
+                    index: 2,
+                    expected: StatusType::Invalid,
+                    found: 2,
+                },
+                Divergence::Missing {
+                    index: 3,
+                    expected: StatusType::Valid,
+                },
+            ]
+        );
+
+        assert!(decoder
+            .reconcile([(0, StatusType::Valid), (2, StatusType::Suspended)])
+            .is_consistent());
+    }
+}
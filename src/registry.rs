@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::types::StatusType;
+
+// Names for status values, so applications can map the application-specific
+// values they use to their own meaning.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StatusRegistry {
+    names: BTreeMap<u8, String>,
+}
+
+impl Default for StatusRegistry {
+    /// Names the statuses defined by the specification.
+    fn default() -> Self {
+        Self::new()
+            .with_status(StatusType::Valid, "VALID")
+            .with_status(StatusType::Invalid, "INVALID")
+            .with_status(StatusType::Suspended, "SUSPENDED")
+    }
+}
+
+impl StatusRegistry {
+    pub fn new() -> Self {
+        Self {
+            names: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_status(mut self, status: StatusType, name: impl Into<String>) -> Self {
+        self.names.insert(status.value(), name.into());
+        self
+    }
+
+    pub fn name_of(&self, status: StatusType) -> Option<&str> {
+        self.names.get(&status.value()).map(String::as_str)
+    }
+
+    pub fn lookup(&self, name: &str) -> Option<StatusType> {
+        self.names
+            .iter()
+            .find(|(_, n)| n.as_str() == name)
+            .map(|(value, _)| StatusType::from(*value))
+    }
+
+    pub fn describe(&self, status: StatusType) -> NamedStatus<'_> {
+        NamedStatus {
+            status,
+            name: self.name_of(status),
+        }
+    }
+}
+
+/// A status together with its registered name, if any. Displays as the name,
+/// or as the hex value for unnamed statuses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NamedStatus<'a> {
+    pub status: StatusType,
+    pub name: Option<&'a str>,
+}
+
+impl fmt::Display for NamedStatus<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "{:#04x}", self.status.value()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_names() {
+        let registry = StatusRegistry::default()
+            .with_status(StatusType::ApplicationSpecific11, "PendingReview");
+
+        assert_eq!(registry.name_of(StatusType::Invalid), Some("INVALID"));
+        assert_eq!(
+            registry.name_of(StatusType::from(0x0B)),
+            Some("PendingReview")
+        );
+        assert_eq!(registry.name_of(StatusType::ApplicationSpecific12), None);
+        assert_eq!(
+            registry.lookup("PendingReview"),
+            Some(StatusType::ApplicationSpecific11)
+        );
+        assert_eq!(registry.lookup("Unknown"), None);
+
+        assert_eq!(
+            registry
+                .describe(StatusType::ApplicationSpecific11)
+                .to_string(),
+            "PendingReview"
+        );
+        assert_eq!(
+            registry.describe(StatusType::from(0x42)).to_string(),
+            "0x42"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_registry_serde() {
+        let registry =
+            StatusRegistry::new().with_status(StatusType::ApplicationSpecific11, "PendingReview");
+        let json = serde_json::to_string(&registry).unwrap();
+        assert_eq!(json, r#"{"names":{"11":"PendingReview"}}"#);
+
+        let parsed: StatusRegistry = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, registry);
+    }
+}
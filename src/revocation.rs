@@ -0,0 +1,98 @@
+use crate::builder::{FillStrategy, StatusListBuilder};
+use crate::error::BuilderError;
+use crate::types::{StatusList, StatusType};
+
+// A 1-bit list where every index is either revoked or not.
+#[derive(Debug)]
+pub struct RevocationList {
+    builder: StatusListBuilder,
+}
+
+impl Default for RevocationList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RevocationList {
+    pub fn new() -> Self {
+        Self {
+            builder: StatusListBuilder::new(1).expect("1 bit per status is valid"),
+        }
+    }
+
+    pub fn with_capacity(self, size: usize) -> Self {
+        Self {
+            builder: self.builder.with_capacity(size, FillStrategy::AllValid),
+        }
+    }
+
+    pub fn revoke(&self, index: usize) -> &Self {
+        self.set_revoked(index, true)
+    }
+
+    pub fn set_revoked(&self, index: usize, revoked: bool) -> &Self {
+        let status = if revoked {
+            StatusType::Invalid
+        } else {
+            StatusType::Valid
+        };
+        self.builder.set_status(index, status);
+        self
+    }
+
+    /// Indices past the end of the list are not revoked.
+    pub fn is_revoked(&self, index: usize) -> bool {
+        self.builder.get_status(index) == Some(StatusType::Invalid)
+    }
+
+    pub fn len(&self) -> usize {
+        self.builder.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.builder.is_empty()
+    }
+
+    pub fn build(&self) -> Result<StatusList, BuilderError> {
+        self.builder.build()
+    }
+
+    pub fn into_builder(self) -> StatusListBuilder {
+        self.builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_revoke() {
+        let list = RevocationList::new().with_capacity(16);
+        list.revoke(3).revoke(9);
+
+        assert!(list.is_revoked(3));
+        assert!(list.is_revoked(9));
+        assert!(!list.is_revoked(4));
+        assert!(!list.is_revoked(100));
+        assert_eq!(list.len(), 16);
+
+        list.set_revoked(3, false);
+        assert!(!list.is_revoked(3));
+
+        let status_list = list.build().unwrap();
+        assert_eq!(status_list.bits(), 1);
+    }
+
+    #[test]
+    fn test_revoke_grows_list() {
+        let list = RevocationList::new();
+        assert!(list.is_empty());
+
+        list.revoke(20);
+        assert_eq!(list.len(), 21);
+        assert!(list.is_revoked(20));
+        assert!(!list.is_revoked(19));
+    }
+}
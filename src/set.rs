@@ -0,0 +1,852 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::allocator::{AllocationStrategy, IndexAllocator};
+use crate::builder::{FillStrategy, StatusListBuilder};
+use crate::error::{AllocatorError, StatusListSetError, StatusTypeError, UriError};
+use crate::idempotency::{IdempotencyKeys, DEFAULT_KEY_RETENTION};
+#[cfg(feature = "jwt")]
+use crate::jwt::JwtStatusListToken;
+#[cfg(feature = "mapping")]
+use crate::mapping::{IndexLocation, MappingStore};
+#[cfg(feature = "cwt")]
+use crate::token::CwtStatusListToken;
+#[cfg(feature = "serde")]
+use crate::types::SerializationError;
+use crate::types::{BitsPerStatus, StatusList, StatusListReference, StatusType};
+use crate::uri::StatusListUri;
+use crate::validation::ReferenceValidator;
+
+/// The Status List Aggregation document, listing every list URI of an issuer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StatusListAggregation {
+    pub status_lists: Vec<String>,
+}
+
+#[cfg(feature = "serde")]
+impl StatusListAggregation {
+    pub fn to_json(&self) -> Result<String, SerializationError> {
+        serde_json::to_string(self).map_err(|e| SerializationError::JsonError(e.to_string()))
+    }
+}
+
+/// How the lists of one kind of credential are built and issued. The key ID
+/// names the signing key; resolving it to a `Signer` is up to the issuer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListPolicy {
+    pub bits: BitsPerStatus,
+    pub capacity: usize,
+    pub key_id: Option<String>,
+    pub ttl: Option<u64>,
+    /// Seconds from `iat` to `exp`
+    pub lifetime: Option<u64>,
+}
+
+impl ListPolicy {
+    pub fn new(bits: BitsPerStatus, capacity: usize) -> Self {
+        Self {
+            bits,
+            capacity,
+            key_id: None,
+            ttl: None,
+            lifetime: None,
+        }
+    }
+
+    pub fn with_key_id(mut self, key_id: impl Into<String>) -> Self {
+        self.key_id = Some(key_id.into());
+        self
+    }
+
+    pub fn with_ttl(mut self, ttl: u64) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub fn with_lifetime(mut self, lifetime: u64) -> Self {
+        self.lifetime = Some(lifetime);
+        self
+    }
+
+    pub fn exp(&self, iat: u64) -> Option<u64> {
+        self.lifetime.map(|lifetime| iat.saturating_add(lifetime))
+    }
+}
+
+#[derive(Debug)]
+struct ManagedList {
+    uri: StatusListUri,
+    profile: Option<String>,
+    policy: ListPolicy,
+    builder: StatusListBuilder,
+    allocator: IndexAllocator,
+}
+
+// Owns every list of an issuer. Lists are created on demand as
+// `<base_uri>/1`, `<base_uri>/2`, ... whenever the newest one fills up;
+// lists of a named profile go under `<base_uri>/<profile>/1` and so on.
+// A list keeps the policy it was created with.
+#[derive(Debug)]
+pub struct StatusListSet {
+    base_uri: StatusListUri,
+    policy: ListPolicy,
+    profiles: BTreeMap<String, ListPolicy>,
+    strategy: AllocationStrategy,
+    aggregation_uri: Option<String>,
+    lists: Vec<ManagedList>,
+    keys: Mutex<IdempotencyKeys<(StatusListReference, StatusType)>>,
+}
+
+impl StatusListSet {
+    pub fn new(
+        base_uri: StatusListUri,
+        bits_per_status: u8,
+        list_capacity: usize,
+    ) -> Result<Self, StatusTypeError> {
+        let bits = BitsPerStatus::try_from(bits_per_status)?;
+        Ok(Self::with_policy(
+            base_uri,
+            ListPolicy::new(bits, list_capacity),
+        ))
+    }
+
+    pub fn with_policy(base_uri: StatusListUri, policy: ListPolicy) -> Self {
+        Self {
+            base_uri,
+            policy,
+            profiles: BTreeMap::new(),
+            strategy: AllocationStrategy::Sequential,
+            aggregation_uri: None,
+            lists: Vec::new(),
+            keys: Mutex::new(IdempotencyKeys::new(DEFAULT_KEY_RETENTION)),
+        }
+    }
+
+    /// Adds a named policy; `allocate_in` hands out indices from its lists.
+    pub fn with_profile(mut self, name: impl Into<String>, policy: ListPolicy) -> Self {
+        self.profiles.insert(name.into(), policy);
+        self
+    }
+
+    pub fn with_allocation_strategy(mut self, strategy: AllocationStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Remembers idempotency keys for `retention` seconds instead of
+    /// `DEFAULT_KEY_RETENTION`.
+    pub fn with_key_retention(mut self, retention: u64) -> Self {
+        self.keys.get_mut().unwrap().set_retention(retention);
+        self
+    }
+
+    pub fn with_aggregation_uri(mut self, aggregation_uri: impl Into<String>) -> Self {
+        self.aggregation_uri = Some(aggregation_uri.into());
+        self
+    }
+
+    pub fn allocate(&mut self) -> Result<StatusListReference, StatusListSetError> {
+        self.allocate_from(None)
+    }
+
+    pub fn allocate_in(
+        &mut self,
+        profile: &str,
+    ) -> Result<StatusListReference, StatusListSetError> {
+        self.allocate_from(Some(profile))
+    }
+
+    /// Allocates an index for each credential ID and records all mappings
+    /// with one `insert_all`. If that fails, the allocations are undone.
+    #[cfg(feature = "mapping")]
+    pub fn allocate_for<I>(
+        &mut self,
+        ids: I,
+        store: &(impl MappingStore + ?Sized),
+    ) -> Result<Vec<(String, StatusListReference)>, StatusListSetError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let lists = self.lists.len();
+        let newest = self.lists.iter().rposition(|list| list.profile.is_none());
+        let state = newest.map(|position| self.lists[position].allocator.to_state());
+
+        let allocated = self
+            .allocate_batch(ids.into_iter().map(Into::into))
+            .and_then(|allocated| {
+                let entries: Vec<_> = allocated
+                    .iter()
+                    .map(|(id, reference)| {
+                        (
+                            id.clone(),
+                            IndexLocation::new(reference.uri.clone(), reference.idx),
+                        )
+                    })
+                    .collect();
+                store
+                    .insert_all(&entries)
+                    .map_err(StatusListSetError::MappingError)?;
+                Ok(allocated)
+            });
+        if allocated.is_err() {
+            self.lists.truncate(lists);
+            if let (Some(position), Some(state)) = (newest, state) {
+                self.lists[position].allocator =
+                    IndexAllocator::from_state(state).expect("state taken from a live allocator");
+            }
+        }
+        allocated
+    }
+
+    /// The policy the list at `uri` was created with.
+    pub fn policy(&self, uri: &StatusListUri) -> Result<&ListPolicy, StatusListSetError> {
+        Ok(&self.managed(uri)?.policy)
+    }
+
+    /// An unsigned CWT of the list, with `ttl` and `exp` from its policy.
+    #[cfg(feature = "cwt")]
+    pub fn cwt(
+        &self,
+        uri: &StatusListUri,
+        iat: u64,
+    ) -> Result<CwtStatusListToken, StatusListSetError> {
+        let list = self.managed(uri)?;
+        let mut token = CwtStatusListToken::new(list.uri.clone(), iat, self.build_list(list)?);
+        token.exp = list.policy.exp(iat);
+        token.ttl = list.policy.ttl;
+        Ok(token)
+    }
+
+    /// An unsigned JWT of the list, with `ttl` and `exp` from its policy.
+    #[cfg(feature = "jwt")]
+    pub fn jwt(
+        &self,
+        uri: &StatusListUri,
+        iat: u64,
+    ) -> Result<JwtStatusListToken, StatusListSetError> {
+        let list = self.managed(uri)?;
+        let mut token = JwtStatusListToken::new(list.uri.clone(), iat, self.build_list(list)?);
+        token.exp = list.policy.exp(iat);
+        token.ttl = list.policy.ttl;
+        Ok(token)
+    }
+
+    pub fn set_status(
+        &self,
+        reference: &StatusListReference,
+        status: StatusType,
+    ) -> Result<(), StatusListSetError> {
+        let list = self.find(reference)?;
+        list.builder
+            .try_set_status(reference.idx, status)
+            .map_err(StatusListSetError::BuilderError)?;
+        Ok(())
+    }
+
+    /// Like `set_status`, but a change whose `key` was already applied
+    /// within the retention window is acknowledged without touching the
+    /// list, so redelivered commands cause no version bump. Returns whether
+    /// the change was applied now.
+    pub fn set_status_with_key(
+        &self,
+        reference: &StatusListReference,
+        status: StatusType,
+        key: Option<&str>,
+    ) -> Result<bool, StatusListSetError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        self.set_status_at_with_key(reference, status, now, key)
+    }
+
+    pub fn set_status_at_with_key(
+        &self,
+        reference: &StatusListReference,
+        status: StatusType,
+        timestamp: u64,
+        key: Option<&str>,
+    ) -> Result<bool, StatusListSetError> {
+        let Some(key) = key else {
+            self.set_status(reference, status)?;
+            return Ok(true);
+        };
+
+        let mut keys = self.keys.lock().unwrap();
+        let change = (reference.clone(), status);
+        if let Some(seen) = keys.get(key, timestamp) {
+            if *seen != change {
+                return Err(StatusListSetError::IdempotencyConflict(key.to_string()));
+            }
+            return Ok(false);
+        }
+        self.set_status(reference, status)?;
+        keys.insert(key.to_string(), change, timestamp);
+        Ok(true)
+    }
+
+    pub fn get_status(
+        &self,
+        reference: &StatusListReference,
+    ) -> Result<Option<StatusType>, StatusListSetError> {
+        Ok(self.find(reference)?.builder.get_status(reference.idx))
+    }
+
+    pub fn validator(&self) -> ReferenceValidator<'_> {
+        self.lists
+            .iter()
+            .fold(ReferenceValidator::new(), |validator, list| {
+                validator.with_list(list.uri.clone(), &list.allocator)
+            })
+    }
+
+    pub fn base_uri(&self) -> &StatusListUri {
+        &self.base_uri
+    }
+
+    pub fn uris(&self) -> impl Iterator<Item = &StatusListUri> {
+        self.lists.iter().map(|list| &list.uri)
+    }
+
+    pub fn len(&self) -> usize {
+        self.lists.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lists.is_empty()
+    }
+
+    pub fn build(&self, uri: &StatusListUri) -> Result<StatusList, StatusListSetError> {
+        self.build_list(self.managed(uri)?)
+    }
+
+    pub fn build_all(&self) -> Result<Vec<(StatusListUri, StatusList)>, StatusListSetError> {
+        self.lists
+            .iter()
+            .map(|list| Ok((list.uri.clone(), self.build_list(list)?)))
+            .collect()
+    }
+
+    pub fn aggregation(&self) -> StatusListAggregation {
+        StatusListAggregation {
+            status_lists: self.uris().map(|uri| uri.to_string()).collect(),
+        }
+    }
+
+    fn build_list(&self, list: &ManagedList) -> Result<StatusList, StatusListSetError> {
+        let status_list = list
+            .builder
+            .build_incremental()
+            .map_err(StatusListSetError::BuilderError)?;
+        Ok(match &self.aggregation_uri {
+            Some(aggregation_uri) => status_list.with_aggregation_uri(aggregation_uri.clone()),
+            None => status_list,
+        })
+    }
+
+    fn managed(&self, uri: &StatusListUri) -> Result<&ManagedList, StatusListSetError> {
+        self.lists
+            .iter()
+            .find(|list| &list.uri == uri)
+            .ok_or_else(|| StatusListSetError::UnknownList(uri.to_string()))
+    }
+
+    fn allocate_from(
+        &mut self,
+        profile: Option<&str>,
+    ) -> Result<StatusListReference, StatusListSetError> {
+        let position = self.list_with_room(profile)?;
+        let list = &mut self.lists[position];
+        let idx = list
+            .allocator
+            .allocate()
+            .map_err(StatusListSetError::AllocatorError)?;
+        Ok(StatusListReference::new(idx, list.uri.clone()))
+    }
+
+    #[cfg(feature = "mapping")]
+    fn allocate_batch(
+        &mut self,
+        ids: impl Iterator<Item = String>,
+    ) -> Result<Vec<(String, StatusListReference)>, StatusListSetError> {
+        let mut ids = ids.peekable();
+        let mut allocated = Vec::new();
+        while ids.peek().is_some() {
+            let position = self.list_with_room(None)?;
+            let list = &mut self.lists[position];
+            let chunk = ids.by_ref().take(list.allocator.remaining());
+            for (id, idx) in list
+                .allocator
+                .allocate_for(chunk)
+                .map_err(StatusListSetError::AllocatorError)?
+            {
+                allocated.push((id, StatusListReference::new(idx, list.uri.clone())));
+            }
+        }
+        Ok(allocated)
+    }
+
+    // The newest list of the profile, or a new one if that is full.
+    fn list_with_room(&mut self, profile: Option<&str>) -> Result<usize, StatusListSetError> {
+        let policy = match profile {
+            None => &self.policy,
+            Some(name) => self
+                .profiles
+                .get(name)
+                .ok_or_else(|| StatusListSetError::UnknownProfile(name.to_string()))?,
+        };
+        if policy.capacity == 0 {
+            return Err(StatusListSetError::AllocatorError(
+                AllocatorError::Exhausted(0),
+            ));
+        }
+
+        let newest = self
+            .lists
+            .iter()
+            .rposition(|list| list.profile.as_deref() == profile);
+        match newest {
+            Some(position) if self.lists[position].allocator.remaining() > 0 => Ok(position),
+            _ => self.add_list(profile),
+        }
+    }
+
+    fn find(&self, reference: &StatusListReference) -> Result<&ManagedList, StatusListSetError> {
+        self.validator()
+            .validate(reference)
+            .map_err(StatusListSetError::ReferenceError)?;
+
+        Ok(self
+            .lists
+            .iter()
+            .find(|list| list.uri.ensure_matches(&reference.uri).is_ok())
+            .expect("validated references point at a managed list"))
+    }
+
+    fn add_list(&mut self, profile: Option<&str>) -> Result<usize, StatusListSetError> {
+        let policy = match profile {
+            None => self.policy.clone(),
+            Some(name) => self.profiles[name].clone(),
+        };
+        let number = self
+            .lists
+            .iter()
+            .filter(|list| list.profile.as_deref() == profile)
+            .count()
+            + 1;
+
+        let invalid = |e: &dyn std::fmt::Display| {
+            StatusListSetError::UriError(UriError::InvalidUri(e.to_string()))
+        };
+        let mut url = Url::parse(self.base_uri.as_str()).map_err(|e| invalid(&e))?;
+        url.path_segments_mut()
+            .map_err(|_| invalid(&"base URI cannot have a path"))?
+            .pop_if_empty()
+            .extend(profile)
+            .push(&number.to_string());
+        let uri = StatusListUri::parse(url.as_str()).map_err(StatusListSetError::UriError)?;
+
+        let builder = StatusListBuilder::new(policy.bits as u8)
+            .expect("bits per status is a BitsPerStatus")
+            .with_capacity(policy.capacity, FillStrategy::AllValid);
+        self.lists.push(ManagedList {
+            uri,
+            profile: profile.map(str::to_string),
+            allocator: IndexAllocator::new(policy.capacity, self.strategy),
+            policy,
+            builder,
+        });
+        Ok(self.lists.len() - 1)
+    }
+}
+
+/// The lists of many tenants, each an independent `StatusListSet` with its
+/// own base URI, keys and policies.
+#[derive(Debug, Default)]
+pub struct StatusListTenants {
+    tenants: BTreeMap<String, StatusListSet>,
+}
+
+impl StatusListTenants {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a tenant. Base URIs may not nest, so that every list URI belongs
+    /// to exactly one tenant.
+    pub fn add(
+        &mut self,
+        tenant: impl Into<String>,
+        set: StatusListSet,
+    ) -> Result<(), StatusListSetError> {
+        let tenant = tenant.into();
+        if self.tenants.contains_key(&tenant) {
+            return Err(StatusListSetError::DuplicateTenant(tenant));
+        }
+        if let Some((other, _)) = self
+            .tenants
+            .iter()
+            .find(|(_, other)| nests(other.base_uri(), set.base_uri()))
+        {
+            return Err(StatusListSetError::OverlappingTenants(
+                tenant,
+                other.clone(),
+            ));
+        }
+        self.tenants.insert(tenant, set);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, tenant: &str) -> Option<StatusListSet> {
+        self.tenants.remove(tenant)
+    }
+
+    pub fn tenant(&self, tenant: &str) -> Result<&StatusListSet, StatusListSetError> {
+        self.tenants
+            .get(tenant)
+            .ok_or_else(|| StatusListSetError::UnknownTenant(tenant.to_string()))
+    }
+
+    pub fn tenant_mut(&mut self, tenant: &str) -> Result<&mut StatusListSet, StatusListSetError> {
+        self.tenants
+            .get_mut(tenant)
+            .ok_or_else(|| StatusListSetError::UnknownTenant(tenant.to_string()))
+    }
+
+    /// The tenant owning the list at `uri`, for routing list requests.
+    pub fn tenant_of(&self, uri: &StatusListUri) -> Option<&str> {
+        self.tenants
+            .iter()
+            .find(|(_, set)| set.uris().any(|list| list == uri))
+            .map(|(tenant, _)| tenant.as_str())
+    }
+
+    pub fn tenants(&self) -> impl Iterator<Item = &str> {
+        self.tenants.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tenants.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tenants.is_empty()
+    }
+}
+
+fn nests(a: &StatusListUri, b: &StatusListUri) -> bool {
+    let (a, b) = (
+        a.as_str().trim_end_matches('/'),
+        b.as_str().trim_end_matches('/'),
+    );
+    let within = |inner: &str, outer: &str| {
+        inner
+            .strip_prefix(outer)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    };
+    within(a, b) || within(b, a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ReferenceError;
+
+    fn uri(uri: &str) -> StatusListUri {
+        StatusListUri::parse(uri).unwrap()
+    }
+
+    fn set(capacity: usize) -> StatusListSet {
+        let base_uri = StatusListUri::parse("https://example.com/statuslists").unwrap();
+        StatusListSet::new(base_uri, 1, capacity).unwrap()
+    }
+
+    #[test]
+    fn test_allocate_rolls_over() {
+        let mut set = set(2);
+        assert!(set.is_empty());
+
+        let references: Vec<_> = (0..5).map(|_| set.allocate().unwrap()).collect();
+        assert_eq!(
+            references,
+            vec![
+                StatusListReference::new(0, uri("https://example.com/statuslists/1")),
+                StatusListReference::new(1, uri("https://example.com/statuslists/1")),
+                StatusListReference::new(0, uri("https://example.com/statuslists/2")),
+                StatusListReference::new(1, uri("https://example.com/statuslists/2")),
+                StatusListReference::new(0, uri("https://example.com/statuslists/3")),
+            ]
+        );
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn test_set_status_routes_to_list() {
+        let mut set = set(8);
+        let first = set.allocate().unwrap();
+        let second = set.allocate().unwrap();
+
+        set.set_status(&second, StatusType::Invalid).unwrap();
+        assert_eq!(set.get_status(&first).unwrap(), Some(StatusType::Valid));
+        assert_eq!(set.get_status(&second).unwrap(), Some(StatusType::Invalid));
+
+        let lists = set.build_all().unwrap();
+        assert_eq!(lists.len(), 1);
+        assert_eq!(lists[0].0.as_str(), "https://example.com/statuslists/1");
+        assert_eq!(lists[0].1, set.build(&lists[0].0).unwrap());
+
+        match set.set_status(
+            &StatusListReference::new(5, first.uri.clone()),
+            StatusType::Invalid,
+        ) {
+            Err(StatusListSetError::ReferenceError(ReferenceError::Unallocated(5))) => (),
+            _ => panic!("Expected Unallocated"),
+        }
+        match set.set_status(
+            &StatusListReference::new(0, uri("https://example.com/statuslists/9")),
+            StatusType::Invalid,
+        ) {
+            Err(StatusListSetError::ReferenceError(ReferenceError::UnknownList(_))) => (),
+            _ => panic!("Expected UnknownList"),
+        }
+    }
+
+    #[test]
+    fn test_idempotency_keys() {
+        let mut set = set(8).with_key_retention(60);
+        let first = set.allocate().unwrap();
+        let second = set.allocate().unwrap();
+        let generation = || set.lists[0].builder.generation();
+
+        assert!(set
+            .set_status_at_with_key(&first, StatusType::Invalid, 100, Some("revoke-42"))
+            .unwrap());
+        let applied = generation();
+
+        // A redelivered command is acknowledged without a write.
+        assert!(!set
+            .set_status_at_with_key(&first, StatusType::Invalid, 150, Some("revoke-42"))
+            .unwrap());
+        assert_eq!(generation(), applied);
+
+        match set.set_status_at_with_key(&second, StatusType::Invalid, 150, Some("revoke-42")) {
+            Err(StatusListSetError::IdempotencyConflict(key)) => assert_eq!(key, "revoke-42"),
+            _ => panic!("Expected IdempotencyConflict"),
+        }
+
+        // Past the retention window the key may be reused.
+        assert!(set
+            .set_status_at_with_key(&second, StatusType::Invalid, 161, Some("revoke-42"))
+            .unwrap());
+        assert_eq!(set.get_status(&second).unwrap(), Some(StatusType::Invalid));
+        assert!(set
+            .set_status_with_key(&second, StatusType::Valid, None)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_aggregation() {
+        let mut set = set(1).with_aggregation_uri("https://example.com/statuslists");
+        set.allocate().unwrap();
+        set.allocate().unwrap();
+
+        assert_eq!(
+            set.aggregation().status_lists,
+            vec![
+                "https://example.com/statuslists/1",
+                "https://example.com/statuslists/2"
+            ]
+        );
+        for (_, status_list) in set.build_all().unwrap() {
+            assert_eq!(
+                status_list.aggregation_uri(),
+                Some("https://example.com/statuslists")
+            );
+        }
+    }
+
+    #[test]
+    fn test_profiles() {
+        let short_lived = ListPolicy::new(BitsPerStatus::TwoBit, 2)
+            .with_key_id("k2")
+            .with_ttl(300)
+            .with_lifetime(3600);
+        let mut set = set(4).with_profile("badges", short_lived.clone());
+
+        let default = set.allocate().unwrap();
+        let badges: Vec<_> = (0..3).map(|_| set.allocate_in("badges").unwrap()).collect();
+        assert_eq!(default.uri.as_str(), "https://example.com/statuslists/1");
+        assert_eq!(
+            badges.iter().map(|r| r.uri.as_str()).collect::<Vec<_>>(),
+            vec![
+                "https://example.com/statuslists/badges/1",
+                "https://example.com/statuslists/badges/1",
+                "https://example.com/statuslists/badges/2",
+            ]
+        );
+        assert_eq!(set.allocate().unwrap().uri, default.uri);
+
+        assert_eq!(set.policy(&badges[0].uri).unwrap(), &short_lived);
+        assert_eq!(set.policy(&default.uri).unwrap().key_id, None);
+        set.set_status(&badges[1], StatusType::Suspended).unwrap();
+        assert_eq!(set.build(&badges[1].uri).unwrap().bits(), 2);
+        assert_eq!(set.build(&default.uri).unwrap().bits(), 1);
+
+        match set.allocate_in("diplomas") {
+            Err(StatusListSetError::UnknownProfile(name)) => assert_eq!(name, "diplomas"),
+            _ => panic!("Expected UnknownProfile"),
+        }
+    }
+
+    #[cfg(feature = "mapping")]
+    #[test]
+    fn test_allocate_for() {
+        use crate::error::MappingError;
+        use crate::mapping::InMemoryMappingStore;
+
+        let store = InMemoryMappingStore::new();
+        let mut set = set(2);
+        set.allocate().unwrap();
+
+        let allocated = set.allocate_for(["a", "b", "c"], &store).unwrap();
+        assert_eq!(
+            allocated,
+            vec![
+                (
+                    "a".to_string(),
+                    StatusListReference::new(1, uri("https://example.com/statuslists/1"))
+                ),
+                (
+                    "b".to_string(),
+                    StatusListReference::new(0, uri("https://example.com/statuslists/2"))
+                ),
+                (
+                    "c".to_string(),
+                    StatusListReference::new(1, uri("https://example.com/statuslists/2"))
+                ),
+            ]
+        );
+        assert_eq!(
+            store.location_of("c").unwrap(),
+            Some(IndexLocation::new(
+                uri("https://example.com/statuslists/2"),
+                1
+            ))
+        );
+
+        match set.allocate_for(["d", "e", "a"], &store) {
+            Err(StatusListSetError::MappingError(MappingError::DuplicateId(id))) => {
+                assert_eq!(id, "a")
+            }
+            _ => panic!("Expected DuplicateId"),
+        }
+        assert_eq!(set.len(), 2);
+        assert_eq!(store.location_of("d").unwrap(), None);
+        assert_eq!(
+            set.allocate().unwrap(),
+            StatusListReference::new(0, uri("https://example.com/statuslists/3"))
+        );
+    }
+
+    #[test]
+    fn test_tenants() {
+        let tenant = |base: &str| {
+            StatusListSet::with_policy(uri(base), ListPolicy::new(BitsPerStatus::OneBit, 4))
+        };
+        let mut tenants = StatusListTenants::new();
+        tenants
+            .add("acme", tenant("https://acme.example/statuslists"))
+            .unwrap();
+        tenants
+            .add("globex", tenant("https://example.com/globex"))
+            .unwrap();
+
+        let acme = tenants.tenant_mut("acme").unwrap().allocate().unwrap();
+        let globex = tenants.tenant_mut("globex").unwrap().allocate().unwrap();
+        assert_eq!(acme.idx, globex.idx);
+        assert_eq!(tenants.tenant_of(&acme.uri), Some("acme"));
+        assert_eq!(tenants.tenant_of(&globex.uri), Some("globex"));
+        assert_eq!(
+            tenants.tenants().collect::<Vec<_>>(),
+            vec!["acme", "globex"]
+        );
+
+        tenants
+            .tenant("acme")
+            .unwrap()
+            .set_status(&acme, StatusType::Invalid)
+            .unwrap();
+        assert_eq!(
+            tenants
+                .tenant("globex")
+                .unwrap()
+                .get_status(&globex)
+                .unwrap(),
+            Some(StatusType::Valid)
+        );
+        assert!(tenants
+            .tenant("globex")
+            .unwrap()
+            .set_status(&acme, StatusType::Invalid)
+            .is_err());
+
+        match tenants.add("acme", tenant("https://other.example")) {
+            Err(StatusListSetError::DuplicateTenant(name)) => assert_eq!(name, "acme"),
+            _ => panic!("Expected DuplicateTenant"),
+        }
+        match tenants.add("initech", tenant("https://example.com/globex/initech/")) {
+            Err(StatusListSetError::OverlappingTenants(name, other)) => {
+                assert_eq!((name.as_str(), other.as_str()), ("initech", "globex"))
+            }
+            _ => panic!("Expected OverlappingTenants"),
+        }
+        tenants
+            .add("initech", tenant("https://example.com/globex2"))
+            .unwrap();
+        match tenants.tenant("umbrella") {
+            Err(StatusListSetError::UnknownTenant(_)) => (),
+            _ => panic!("Expected UnknownTenant"),
+        }
+        assert!(tenants.remove("acme").is_some());
+        assert_eq!(tenants.len(), 2);
+    }
+
+    #[cfg(feature = "cwt")]
+    #[test]
+    fn test_tokens_follow_policy() {
+        let mut set = set(4).with_profile(
+            "badges",
+            ListPolicy::new(BitsPerStatus::OneBit, 4)
+                .with_ttl(300)
+                .with_lifetime(3600),
+        );
+        let default = set.allocate().unwrap();
+        let badge = set.allocate_in("badges").unwrap();
+
+        let token = set.cwt(&badge.uri, 1000).unwrap();
+        assert_eq!((token.exp, token.ttl), (Some(4600), Some(300)));
+        assert_eq!(token.sub, badge.uri);
+        let token = set.cwt(&default.uri, 1000).unwrap();
+        assert_eq!((token.exp, token.ttl), (None, None));
+        #[cfg(feature = "jwt")]
+        assert_eq!(set.jwt(&badge.uri, 1000).unwrap().exp, Some(4600));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_aggregation_json() {
+        let mut set = set(4);
+        set.allocate().unwrap();
+
+        assert_eq!(
+            set.aggregation().to_json().unwrap(),
+            r#"{"status_lists":["https://example.com/statuslists/1"]}"#
+        );
+    }
+}
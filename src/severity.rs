@@ -0,0 +1,108 @@
+use std::cmp::Ordering;
+
+use crate::types::StatusType;
+
+// Ranks statuses from most to least severe, for combining the results of
+// several lists or mechanisms that apply to one credential.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeverityOrder {
+    most_severe_first: Vec<StatusType>,
+}
+
+impl Default for SeverityOrder {
+    /// Invalid, then Suspended, then the application-specific values, then Valid.
+    fn default() -> Self {
+        Self::new([
+            StatusType::Invalid,
+            StatusType::Suspended,
+            StatusType::ApplicationSpecific3,
+            StatusType::ApplicationSpecific11,
+            StatusType::ApplicationSpecific12,
+            StatusType::ApplicationSpecific13,
+            StatusType::ApplicationSpecific14,
+            StatusType::ApplicationSpecific15,
+            StatusType::Valid,
+        ])
+    }
+}
+
+impl SeverityOrder {
+    /// Statuses missing from `most_severe_first` rank below every listed one.
+    pub fn new(most_severe_first: impl IntoIterator<Item = StatusType>) -> Self {
+        Self {
+            most_severe_first: most_severe_first.into_iter().collect(),
+        }
+    }
+
+    pub fn compare(&self, a: StatusType, b: StatusType) -> Ordering {
+        self.rank(b).cmp(&self.rank(a))
+    }
+
+    pub fn worst_of(&self, statuses: impl IntoIterator<Item = StatusType>) -> Option<StatusType> {
+        statuses.into_iter().max_by(|a, b| self.compare(*a, *b))
+    }
+
+    fn rank(&self, status: StatusType) -> usize {
+        self.most_severe_first
+            .iter()
+            .position(|s| *s == status)
+            .unwrap_or(self.most_severe_first.len())
+    }
+}
+
+impl StatusType {
+    /// The most severe status under the default `SeverityOrder`.
+    pub fn worst_of(statuses: impl IntoIterator<Item = StatusType>) -> Option<StatusType> {
+        SeverityOrder::default().worst_of(statuses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_order() {
+        assert_eq!(
+            StatusType::worst_of([StatusType::Valid, StatusType::Suspended]),
+            Some(StatusType::Suspended)
+        );
+        assert_eq!(
+            StatusType::worst_of([
+                StatusType::Suspended,
+                StatusType::Invalid,
+                StatusType::ApplicationSpecific3
+            ]),
+            Some(StatusType::Invalid)
+        );
+        assert_eq!(StatusType::worst_of([]), None);
+
+        let order = SeverityOrder::default();
+        assert_eq!(
+            order.compare(StatusType::Invalid, StatusType::Valid),
+            Ordering::Greater
+        );
+        assert_eq!(
+            order.compare(StatusType::Valid, StatusType::Valid),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn test_custom_order() {
+        let order = SeverityOrder::new([
+            StatusType::Suspended,
+            StatusType::Invalid,
+            StatusType::Valid,
+        ]);
+
+        assert_eq!(
+            order.worst_of([StatusType::Invalid, StatusType::Suspended]),
+            Some(StatusType::Suspended)
+        );
+        assert_eq!(
+            order.worst_of([StatusType::ApplicationSpecific12, StatusType::Valid]),
+            Some(StatusType::Valid)
+        );
+    }
+}
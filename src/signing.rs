@@ -0,0 +1,248 @@
+use coset::cbor::value::Value;
+use coset::{iana, CoseSign1, CoseSign1Builder, HeaderBuilder, Label, TaggedCborSerializable};
+
+#[cfg(feature = "changelog")]
+use crate::error::ChangelogError;
+#[cfg(feature = "reasons")]
+use crate::error::ReasonsError;
+#[cfg(feature = "cwt")]
+use crate::error::TokenError;
+
+pub(crate) const TYP_HEADER: i64 = 16;
+
+pub trait Signer {
+    fn algorithm(&self) -> iana::Algorithm;
+
+    fn key_id(&self) -> Option<Vec<u8>> {
+        None
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, String>;
+}
+
+pub trait SignatureVerifier {
+    fn verify(
+        &self,
+        algorithm: iana::Algorithm,
+        data: &[u8],
+        signature: &[u8],
+    ) -> Result<(), String>;
+
+    /// Called with the key ID from the token header, if any. Verifiers
+    /// holding several keys (e.g. a JWK set) override this to pick one.
+    fn verify_with_key_id(
+        &self,
+        key_id: Option<&[u8]>,
+        algorithm: iana::Algorithm,
+        data: &[u8],
+        signature: &[u8],
+    ) -> Result<(), String> {
+        let _ = key_id;
+        self.verify(algorithm, data, signature)
+    }
+}
+
+// How the protected header labels a payload: with the `typ` header (RFC 9596)
+// for tokens, or with a content type for the crate's extension documents.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    not(all(feature = "cwt", any(feature = "changelog", feature = "reasons"))),
+    allow(dead_code)
+)]
+pub(crate) enum PayloadType {
+    Typ(&'static str),
+    ContentType(&'static str),
+}
+
+impl PayloadType {
+    fn matches(&self, sign1: &CoseSign1) -> bool {
+        let header = &sign1.protected.header;
+        match self {
+            PayloadType::Typ(typ) => header.rest.iter().any(|(label, value)| {
+                *label == Label::Int(TYP_HEADER) && value.as_text() == Some(typ)
+            }),
+            PayloadType::ContentType(content_type) => {
+                header.content_type == Some(coset::ContentType::Text(content_type.to_string()))
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum CoseError {
+    Encoding(String),
+    Signature(String),
+}
+
+/// Signs `payload` as a tagged COSE_Sign1 with the signer's algorithm and key
+/// ID in the protected header.
+pub(crate) fn sign_cose(
+    payload_type: PayloadType,
+    payload: Vec<u8>,
+    signer: &impl Signer,
+) -> Result<Vec<u8>, CoseError> {
+    let mut protected = HeaderBuilder::new().algorithm(signer.algorithm());
+    protected = match payload_type {
+        PayloadType::Typ(typ) => protected.value(TYP_HEADER, Value::Text(typ.to_string())),
+        PayloadType::ContentType(content_type) => protected.content_type(content_type.to_string()),
+    };
+    if let Some(key_id) = signer.key_id() {
+        protected = protected.key_id(key_id);
+    }
+
+    CoseSign1Builder::new()
+        .protected(protected.build())
+        .payload(payload)
+        .try_create_signature(&[], |data| signer.sign(data))
+        .map_err(CoseError::Signature)?
+        .build()
+        .to_tagged_vec()
+        .map_err(|e| CoseError::Encoding(e.to_string()))
+}
+
+/// Checks the payload type and signature of a tagged COSE_Sign1 and returns
+/// its payload.
+pub(crate) fn verify_cose(
+    bytes: &[u8],
+    payload_type: PayloadType,
+    verifier: &impl SignatureVerifier,
+) -> Result<Vec<u8>, CoseError> {
+    let sign1 =
+        CoseSign1::from_tagged_slice(bytes).map_err(|e| CoseError::Encoding(e.to_string()))?;
+
+    if !payload_type.matches(&sign1) {
+        return Err(CoseError::Encoding(match payload_type {
+            PayloadType::Typ(_) => "unexpected typ header".to_string(),
+            PayloadType::ContentType(_) => "unexpected content type".to_string(),
+        }));
+    }
+    let header = &sign1.protected.header;
+    let key_id = (!header.key_id.is_empty()).then_some(&header.key_id[..]);
+    let algorithm = match header.alg {
+        Some(coset::Algorithm::Assigned(algorithm)) => algorithm,
+        _ => {
+            return Err(CoseError::Signature(
+                "missing signature algorithm".to_string(),
+            ))
+        }
+    };
+
+    sign1
+        .verify_signature(&[], |signature, data| {
+            verifier.verify_with_key_id(key_id, algorithm, data, signature)
+        })
+        .map_err(CoseError::Signature)?;
+
+    sign1
+        .payload
+        .ok_or_else(|| CoseError::Encoding("missing payload".to_string()))
+}
+
+#[cfg(feature = "cwt")]
+impl From<CoseError> for TokenError {
+    fn from(error: CoseError) -> Self {
+        match error {
+            CoseError::Encoding(msg) => TokenError::EncodingError(msg),
+            CoseError::Signature(msg) => TokenError::SignatureError(msg),
+        }
+    }
+}
+
+#[cfg(feature = "changelog")]
+impl From<CoseError> for ChangelogError {
+    fn from(error: CoseError) -> Self {
+        match error {
+            CoseError::Encoding(msg) => ChangelogError::EncodingError(msg),
+            CoseError::Signature(msg) => ChangelogError::SignatureError(msg),
+        }
+    }
+}
+
+#[cfg(feature = "reasons")]
+impl From<CoseError> for ReasonsError {
+    fn from(error: CoseError) -> Self {
+        match error {
+            CoseError::Encoding(msg) => ReasonsError::EncodingError(msg),
+            CoseError::Signature(msg) => ReasonsError::SignatureError(msg),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    /// A keyed hash standing in for ES256 in tests. Keys with different
+    /// seeds reject each other's signatures.
+    pub(crate) struct TestKey(pub u64);
+
+    impl TestKey {
+        fn mac(&self, data: &[u8]) -> Vec<u8> {
+            let mut hasher = DefaultHasher::new();
+            self.0.hash(&mut hasher);
+            data.hash(&mut hasher);
+            hasher.finish().to_be_bytes().to_vec()
+        }
+    }
+
+    impl Signer for TestKey {
+        fn algorithm(&self) -> iana::Algorithm {
+            iana::Algorithm::ES256
+        }
+
+        fn key_id(&self) -> Option<Vec<u8>> {
+            Some(b"test-key".to_vec())
+        }
+
+        fn sign(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+            Ok(self.mac(data))
+        }
+    }
+
+    impl SignatureVerifier for TestKey {
+        fn verify(
+            &self,
+            algorithm: iana::Algorithm,
+            data: &[u8],
+            signature: &[u8],
+        ) -> Result<(), String> {
+            if algorithm != iana::Algorithm::ES256 || self.mac(data) != signature {
+                return Err("bad signature".to_string());
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify_cose() {
+        let typ = PayloadType::Typ("application/example+cwt");
+        let signed = sign_cose(typ, b"payload".to_vec(), &TestKey(1)).unwrap();
+        assert_eq!(
+            verify_cose(&signed, typ, &TestKey(1)).unwrap(),
+            b"payload".to_vec()
+        );
+
+        assert!(matches!(
+            verify_cose(&signed, typ, &TestKey(2)),
+            Err(CoseError::Signature(_))
+        ));
+        assert!(matches!(
+            verify_cose(
+                &signed,
+                PayloadType::Typ("application/other+cwt"),
+                &TestKey(1)
+            ),
+            Err(CoseError::Encoding(_))
+        ));
+        assert!(matches!(
+            verify_cose(
+                &signed,
+                PayloadType::ContentType("application/example+cwt"),
+                &TestKey(1)
+            ),
+            Err(CoseError::Encoding(_))
+        ));
+    }
+}
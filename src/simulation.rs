@@ -0,0 +1,283 @@
+use std::time::{Duration, Instant};
+
+use crate::builder::{FillStrategy, StatusListBuilder};
+use crate::error::{LoadTestError, StatusTypeError};
+use crate::rng::SplitMix64;
+use crate::types::{StatusList, StatusType};
+#[cfg(feature = "cwt")]
+use crate::{signing::Signer, token::CwtStatusListToken, uri::StatusListUri};
+
+// Generates lists that look like a real issuer's: mostly valid, with
+// revocations that optionally arrive in runs (a batch of credentials issued
+// together tends to be revoked together).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ListGenerator {
+    size: usize,
+    bits_per_status: u8,
+    revocation_rate: f64,
+    clustering: f64,
+    seed: u64,
+}
+
+impl ListGenerator {
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            bits_per_status: 1,
+            revocation_rate: 0.01,
+            clustering: 0.0,
+            seed: 0,
+        }
+    }
+
+    pub fn with_bits(mut self, bits_per_status: u8) -> Self {
+        self.bits_per_status = bits_per_status;
+        self
+    }
+
+    /// Fraction of indices that end up Invalid, between 0 and 1.
+    pub fn with_revocation_rate(mut self, revocation_rate: f64) -> Self {
+        self.revocation_rate = revocation_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// How strongly a revocation makes the next index revoked too: 0 spreads
+    /// them independently, values close to 1 produce long runs. The overall
+    /// rate is unchanged.
+    pub fn with_clustering(mut self, clustering: f64) -> Self {
+        self.clustering = clustering.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn generate(&self) -> Result<StatusListBuilder, StatusTypeError> {
+        let builder = StatusListBuilder::new(self.bits_per_status)?
+            .with_capacity(self.size, FillStrategy::AllValid);
+
+        // A two-state Markov chain whose stationary revocation probability is
+        // the configured rate.
+        let rate = self.revocation_rate;
+        let after_revoked = rate + self.clustering * (1.0 - rate);
+        let after_valid = rate * (1.0 - self.clustering);
+
+        let mut rng = SplitMix64::new(self.seed);
+        let mut revoked = false;
+        for index in 0..self.size {
+            let p = if revoked { after_revoked } else { after_valid };
+            revoked = unit(&mut rng) < p;
+            if revoked {
+                builder.set_status(index, StatusType::Invalid);
+            }
+        }
+        Ok(builder)
+    }
+}
+
+fn unit(rng: &mut SplitMix64) -> f64 {
+    (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Timings from a `LoadHarness` run, summed over all iterations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoadReport {
+    pub iterations: usize,
+    pub list_len: usize,
+    pub build: Duration,
+    pub sign: Duration,
+    pub serve: Duration,
+    pub bytes_served: usize,
+}
+
+impl LoadReport {
+    pub fn total(&self) -> Duration {
+        self.build + self.sign + self.serve
+    }
+
+    /// End-to-end lists published per second.
+    pub fn throughput(&self) -> f64 {
+        self.iterations as f64 / self.total().as_secs_f64()
+    }
+}
+
+// Repeatedly applies a batch of random revocations, then builds, signs and
+// serializes the list, timing each stage.
+#[derive(Debug)]
+pub struct LoadHarness {
+    builder: StatusListBuilder,
+    iterations: usize,
+    updates_per_iteration: usize,
+    seed: u64,
+}
+
+impl LoadHarness {
+    pub fn new(builder: StatusListBuilder) -> Self {
+        Self {
+            builder,
+            iterations: 10,
+            updates_per_iteration: 100,
+            seed: 0,
+        }
+    }
+
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn with_updates_per_iteration(mut self, updates: usize) -> Self {
+        self.updates_per_iteration = updates;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Runs the harness with `sign` producing the published bytes for each
+    /// built list.
+    pub fn run(
+        &self,
+        mut sign: impl FnMut(&StatusList) -> Result<Vec<u8>, String>,
+    ) -> Result<LoadReport, LoadTestError> {
+        let mut rng = SplitMix64::new(self.seed);
+        let mut report = LoadReport {
+            iterations: self.iterations,
+            list_len: self.builder.len(),
+            build: Duration::ZERO,
+            sign: Duration::ZERO,
+            serve: Duration::ZERO,
+            bytes_served: 0,
+        };
+
+        for _ in 0..self.iterations {
+            if !self.builder.is_empty() {
+                for _ in 0..self.updates_per_iteration {
+                    self.builder
+                        .try_set_status(rng.below(self.builder.len()), StatusType::Invalid)
+                        .map_err(LoadTestError::BuilderError)?;
+                }
+            }
+
+            let started = Instant::now();
+            let status_list = self.builder.build().map_err(LoadTestError::BuilderError)?;
+            report.build += started.elapsed();
+
+            let started = Instant::now();
+            let signed = sign(&status_list).map_err(LoadTestError::SigningError)?;
+            report.sign += started.elapsed();
+
+            let started = Instant::now();
+            let body = status_list
+                .to_json()
+                .map_err(|e| LoadTestError::SerializationError(e.to_string()))?;
+            report.serve += started.elapsed();
+            report.bytes_served += signed.len() + body.len();
+        }
+        Ok(report)
+    }
+
+    /// Runs the harness signing each list as a CWT Status List Token.
+    #[cfg(feature = "cwt")]
+    pub fn run_cwt(
+        &self,
+        signer: &impl Signer,
+        sub: &StatusListUri,
+        iat: u64,
+    ) -> Result<LoadReport, LoadTestError> {
+        self.run(|status_list| {
+            CwtStatusListToken::new(sub.clone(), iat, status_list.clone())
+                .sign(signer)
+                .map_err(|e| e.to_string())
+        })
+    }
+
+    pub fn into_builder(self) -> StatusListBuilder {
+        self.builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn revoked(builder: &StatusListBuilder) -> Vec<usize> {
+        (0..builder.len())
+            .filter(|&i| builder.get_status(i) == Some(StatusType::Invalid))
+            .collect()
+    }
+
+    #[test]
+    fn test_generator_rate() {
+        let builder = ListGenerator::new(100_000)
+            .with_revocation_rate(0.05)
+            .with_seed(3)
+            .generate()
+            .unwrap();
+        assert_eq!(builder.len(), 100_000);
+
+        let count = revoked(&builder).len();
+        assert!((4_000..6_000).contains(&count), "{} revoked", count);
+
+        let again = ListGenerator::new(100_000)
+            .with_revocation_rate(0.05)
+            .with_seed(3)
+            .generate()
+            .unwrap();
+        assert_eq!(again.build().unwrap(), builder.build().unwrap());
+    }
+
+    #[test]
+    fn test_generator_clustering() {
+        let runs = |clustering| {
+            let revoked = revoked(
+                &ListGenerator::new(100_000)
+                    .with_revocation_rate(0.05)
+                    .with_clustering(clustering)
+                    .generate()
+                    .unwrap(),
+            );
+            let starts = revoked
+                .windows(2)
+                .filter(|pair| pair[1] != pair[0] + 1)
+                .count();
+            (revoked.len(), starts)
+        };
+
+        let (spread, spread_runs) = runs(0.0);
+        let (clustered, clustered_runs) = runs(0.9);
+        assert!((3_000..7_000).contains(&clustered), "{} revoked", clustered);
+        assert!(clustered_runs * 4 < spread_runs);
+        assert!(spread > 0);
+    }
+
+    #[test]
+    fn test_load_harness() {
+        let builder = ListGenerator::new(10_000).generate().unwrap();
+        let harness = LoadHarness::new(builder)
+            .with_iterations(3)
+            .with_updates_per_iteration(10);
+
+        let mut signed = 0;
+        let report = harness
+            .run(|status_list| {
+                signed += 1;
+                Ok(status_list.lst().to_vec())
+            })
+            .unwrap();
+        assert_eq!(signed, 3);
+        assert_eq!(report.iterations, 3);
+        assert_eq!(report.list_len, 10_000);
+        assert!(report.bytes_served > 0);
+        assert!(report.throughput() > 0.0);
+
+        match harness.run(|_| Err("HSM offline".to_string())) {
+            Err(LoadTestError::SigningError(msg)) => assert_eq!(msg, "HSM offline"),
+            _ => panic!("Expected SigningError"),
+        }
+    }
+}
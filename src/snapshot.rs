@@ -0,0 +1,259 @@
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::decoder::StatusListDecoder;
+use crate::error::DecoderError;
+use crate::format::{FormatHeader, FormatKind};
+use crate::registry::StatusRegistry;
+#[cfg(feature = "cwt")]
+use crate::token::CwtStatusListToken;
+use crate::types::StatusList;
+
+/// Consecutive indices sharing one status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct StatusRun {
+    pub start: usize,
+    pub len: usize,
+    pub status: String,
+}
+
+// A stable description of a list's content for golden-file tests. It is built
+// from the decoded statuses rather than the compressed bytes, so it does not
+// change with the compression backend or level. Both the text and JSON forms
+// start with a `tsl-snapshot` format header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct StatusListSnapshot {
+    pub format: FormatHeader,
+    pub bits: u8,
+    pub len: usize,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub aggregation_uri: Option<String>,
+    pub runs: Vec<StatusRun>,
+}
+
+impl StatusListSnapshot {
+    pub fn new(status_list: &StatusList) -> Result<Self, DecoderError> {
+        Self::with_registry(status_list, &StatusRegistry::default())
+    }
+
+    /// Names statuses with `registry`, falling back to their hex value.
+    pub fn with_registry(
+        status_list: &StatusList,
+        registry: &StatusRegistry,
+    ) -> Result<Self, DecoderError> {
+        let decoder = StatusListDecoder::new(status_list)?;
+        let mut runs: Vec<StatusRun> = Vec::new();
+        for index in 0..decoder.len() {
+            let status = registry.describe(decoder.get_status(index)?).to_string();
+            match runs.last_mut() {
+                Some(run) if run.status == status => run.len += 1,
+                _ => runs.push(StatusRun {
+                    start: index,
+                    len: 1,
+                    status,
+                }),
+            }
+        }
+
+        Ok(Self {
+            format: FormatHeader::current(FormatKind::Snapshot),
+            bits: status_list.bits(),
+            len: decoder.len(),
+            aggregation_uri: status_list.aggregation_uri().map(str::to_string),
+            runs,
+        })
+    }
+}
+
+impl StatusListSnapshot {
+    fn write_list(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "bits: {}", self.bits)?;
+        writeln!(f, "len: {}", self.len)?;
+        if let Some(uri) = &self.aggregation_uri {
+            writeln!(f, "aggregation_uri: {}", uri)?;
+        }
+        for run in &self.runs {
+            writeln!(f, "{}..{}: {}", run.start, run.start + run.len, run.status)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for StatusListSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "format: {}", self.format)?;
+        self.write_list(f)
+    }
+}
+
+impl StatusList {
+    pub fn snapshot(&self) -> Result<StatusListSnapshot, DecoderError> {
+        StatusListSnapshot::new(self)
+    }
+}
+
+/// The claims of a status list token with its list as a `StatusListSnapshot`.
+/// Signatures are left out since many algorithms are randomized. The format
+/// header is given once, for the token.
+#[cfg(feature = "cwt")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TokenSnapshot {
+    pub format: FormatHeader,
+    pub sub: String,
+    pub iat: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u64>,
+    #[serde(serialize_with = "serialize_list")]
+    pub status_list: StatusListSnapshot,
+}
+
+#[cfg(feature = "cwt")]
+fn serialize_list<S: serde::Serializer>(
+    list: &StatusListSnapshot,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct List<'a> {
+        bits: u8,
+        len: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        aggregation_uri: &'a Option<String>,
+        runs: &'a [StatusRun],
+    }
+
+    List {
+        bits: list.bits,
+        len: list.len,
+        aggregation_uri: &list.aggregation_uri,
+        runs: &list.runs,
+    }
+    .serialize(serializer)
+}
+
+#[cfg(feature = "cwt")]
+impl fmt::Display for TokenSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "format: {}", self.format)?;
+        writeln!(f, "sub: {}", self.sub)?;
+        writeln!(f, "iat: {}", self.iat)?;
+        if let Some(exp) = self.exp {
+            writeln!(f, "exp: {}", exp)?;
+        }
+        if let Some(ttl) = self.ttl {
+            writeln!(f, "ttl: {}", ttl)?;
+        }
+        self.status_list.write_list(f)
+    }
+}
+
+#[cfg(feature = "cwt")]
+impl CwtStatusListToken {
+    pub fn snapshot(&self) -> Result<TokenSnapshot, DecoderError> {
+        Ok(TokenSnapshot {
+            format: FormatHeader::current(FormatKind::Snapshot),
+            sub: self.sub.to_string(),
+            iat: self.iat,
+            exp: self.exp,
+            ttl: self.ttl,
+            status_list: self.status_list.snapshot()?,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "encoder"))]
+mod tests {
+    use super::*;
+    use crate::builder::StatusListBuilder;
+    use crate::compression::ZlibCompression;
+    use crate::types::StatusType;
+
+    fn builder() -> StatusListBuilder {
+        let mut statuses = vec![StatusType::Valid; 16];
+        statuses[3] = StatusType::Invalid;
+        statuses[4] = StatusType::Invalid;
+        statuses[9] = StatusType::from(0x0B);
+        StatusListBuilder::from_vec(statuses, 4).unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_text() {
+        let status_list = builder()
+            .build()
+            .unwrap()
+            .with_aggregation_uri("https://example.com/lists");
+        assert_eq!(
+            status_list.snapshot().unwrap().to_string(),
+            "format: tsl-snapshot 1 draft-ietf-oauth-status-list-13\n\
+             bits: 4\n\
+             len: 16\n\
+             aggregation_uri: https://example.com/lists\n\
+             0..3: VALID\n\
+             3..5: INVALID\n\
+             5..9: VALID\n\
+             9..10: 0x0b\n\
+             10..16: VALID\n"
+        );
+    }
+
+    #[test]
+    fn test_snapshot_ignores_compression_level() {
+        let fast = builder()
+            .with_compressor(ZlibCompression::new(1))
+            .build()
+            .unwrap();
+        let best = builder().build().unwrap();
+        assert_eq!(fast.snapshot().unwrap(), best.snapshot().unwrap());
+
+        let registry =
+            StatusRegistry::default().with_status(StatusType::ApplicationSpecific11, "ON_HOLD");
+        let named = StatusListSnapshot::with_registry(&best, &registry).unwrap();
+        assert_eq!(named.runs[3].status, "ON_HOLD");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_snapshot_json() {
+        let status_list = StatusListBuilder::from_vec(vec![StatusType::Invalid; 8], 1)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_string(&status_list.snapshot().unwrap()).unwrap(),
+            r#"{"format":"tsl-snapshot 1 draft-ietf-oauth-status-list-13","bits":1,"len":8,"runs":[{"start":0,"len":8,"status":"INVALID"}]}"#
+        );
+    }
+
+    #[cfg(feature = "cwt")]
+    #[test]
+    fn test_token_snapshot() {
+        let token = CwtStatusListToken::new(
+            crate::uri::StatusListUri::parse("https://example.com/statuslists/1").unwrap(),
+            1686920170,
+            StatusListBuilder::from_vec(vec![StatusType::Valid; 8], 1)
+                .unwrap()
+                .build()
+                .unwrap(),
+        )
+        .with_ttl(43200);
+        assert_eq!(
+            token.snapshot().unwrap().to_string(),
+            "format: tsl-snapshot 1 draft-ietf-oauth-status-list-13\n\
+             sub: https://example.com/statuslists/1\n\
+             iat: 1686920170\n\
+             ttl: 43200\n\
+             bits: 1\n\
+             len: 8\n\
+             0..8: VALID\n"
+        );
+        assert_eq!(
+            serde_json::to_value(token.snapshot().unwrap()).unwrap()["status_list"],
+            serde_json::json!({"bits": 1, "len": 8, "runs": [{"start": 0, "len": 8, "status": "VALID"}]})
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+
+use crate::bitops;
+use crate::decoder::StatusListDecoder;
+use crate::types::{BitsPerStatus, StatusType};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatusStats {
+    pub total: usize,
+    pub counts: BTreeMap<u8, usize>,
+}
+
+impl StatusStats {
+    pub fn count(&self, value: u8) -> usize {
+        self.counts.get(&value).copied().unwrap_or(0)
+    }
+
+    pub fn valid(&self) -> usize {
+        self.count(StatusType::Valid.value())
+    }
+
+    pub fn invalid(&self) -> usize {
+        self.count(StatusType::Invalid.value())
+    }
+
+    pub fn suspended(&self) -> usize {
+        self.count(StatusType::Suspended.value())
+    }
+
+    pub fn application_specific(&self) -> usize {
+        self.counts
+            .range(StatusType::ApplicationSpecific3.value()..)
+            .map(|(_, n)| n)
+            .sum()
+    }
+
+    /// Share of statuses that are `Invalid`, between 0.0 and 1.0.
+    pub fn revocation_rate(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.invalid() as f64 / self.total as f64
+    }
+}
+
+impl StatusListDecoder {
+    pub fn stats(&self) -> StatusStats {
+        let len = self.len();
+        let bytes = self.get_raw_bytes();
+        let mut histogram = [0usize; 256];
+
+        match self.bits() {
+            BitsPerStatus::OneBit => {
+                let (full, rest) = (len / 8, len % 8);
+                let mut ones: usize = bytes[..full].iter().map(|b| b.count_ones() as usize).sum();
+                if rest > 0 {
+                    ones += (bytes[full] & ((1u8 << rest) - 1)).count_ones() as usize;
+                }
+                histogram[0] = len - ones;
+                histogram[1] = ones;
+            }
+            bits => {
+                let per_byte = bitops::statuses_per_byte(bits);
+                let mask = ((1u16 << bits as u8) - 1) as u8;
+                for (i, byte) in bytes.iter().enumerate() {
+                    let in_byte = len.saturating_sub(i * per_byte).min(per_byte);
+                    for slot in 0..in_byte {
+                        histogram[((byte >> (slot * bits as usize)) & mask) as usize] += 1;
+                    }
+                }
+            }
+        }
+
+        StatusStats {
+            total: len,
+            counts: (0..=255u8).zip(histogram).filter(|(_, n)| *n > 0).collect(),
+        }
+    }
+
+    pub fn revocation_rate(&self) -> f64 {
+        self.stats().revocation_rate()
+    }
+}
+
+#[cfg(all(test, feature = "encoder"))]
+mod tests {
+    use super::*;
+    use crate::builder::StatusListBuilder;
+
+    fn decoder(statuses: Vec<StatusType>, bits: u8) -> StatusListDecoder {
+        let len = statuses.len();
+        let status_list = StatusListBuilder::from_vec(statuses, bits)
+            .unwrap()
+            .build()
+            .unwrap();
+        StatusListDecoder::new(&status_list)
+            .unwrap()
+            .with_len(len)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_one_bit_stats() {
+        let mut statuses = vec![StatusType::Valid; 13];
+        for i in [0, 3, 8, 12] {
+            statuses[i] = StatusType::Invalid;
+        }
+        let decoder = decoder(statuses, 1);
+
+        let stats = decoder.stats();
+        assert_eq!(stats.total, 13);
+        assert_eq!(stats.valid(), 9);
+        assert_eq!(stats.invalid(), 4);
+        assert_eq!(stats.suspended(), 0);
+        assert!((decoder.revocation_rate() - 4.0 / 13.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_multi_bit_stats() {
+        let statuses = vec![
+            StatusType::Valid,
+            StatusType::Invalid,
+            StatusType::Suspended,
+            StatusType::ApplicationSpecific3,
+            StatusType::ApplicationSpecific15,
+            StatusType::Suspended,
+            StatusType::ApplicationSpecific11,
+        ];
+        let stats = decoder(statuses, 4).stats();
+
+        assert_eq!(stats.total, 7);
+        assert_eq!(stats.valid(), 1);
+        assert_eq!(stats.invalid(), 1);
+        assert_eq!(stats.suspended(), 2);
+        assert_eq!(stats.application_specific(), 3);
+        assert_eq!(stats.count(0x0F), 1);
+    }
+
+    #[test]
+    fn test_empty_stats() {
+        let stats = StatusStats::default();
+        assert_eq!(stats.revocation_rate(), 0.0);
+    }
+}
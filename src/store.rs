@@ -0,0 +1,515 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use crate::allocator::AllocatorState;
+use crate::error::StoreError;
+
+/// The shape of a persisted list: its bit width and how many statuses it holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoreMetadata {
+    pub bits_per_status: u8,
+    pub len: usize,
+}
+
+// Persists a builder's statuses one index at a time. Only indices holding a
+// non-zero value need a record; everything else up to `len` is Valid.
+pub trait StatusListStore {
+    fn load_metadata(&self) -> Result<Option<StoreMetadata>, StoreError>;
+
+    fn save_metadata(&self, metadata: StoreMetadata) -> Result<(), StoreError>;
+
+    fn load_statuses(&self) -> Result<Vec<(usize, u8)>, StoreError>;
+
+    /// Writes the given status values. A value of zero may drop the record.
+    fn save_statuses(&self, statuses: &[(usize, u8)]) -> Result<(), StoreError>;
+
+    fn clear_statuses(&self) -> Result<(), StoreError>;
+
+    fn load_allocator(&self) -> Result<Option<AllocatorState>, StoreError>;
+
+    fn save_allocator(&self, state: &AllocatorState) -> Result<(), StoreError>;
+
+    /// Makes all previous writes durable.
+    fn flush(&self) -> Result<(), StoreError>;
+}
+
+#[derive(Debug, Default)]
+struct StoredList {
+    metadata: Option<StoreMetadata>,
+    statuses: BTreeMap<usize, u8>,
+    allocator: Option<AllocatorState>,
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryStatusListStore {
+    list: Mutex<StoredList>,
+}
+
+impl InMemoryStatusListStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StatusListStore for InMemoryStatusListStore {
+    fn load_metadata(&self) -> Result<Option<StoreMetadata>, StoreError> {
+        Ok(self.list.lock().unwrap().metadata)
+    }
+
+    fn save_metadata(&self, metadata: StoreMetadata) -> Result<(), StoreError> {
+        self.list.lock().unwrap().metadata = Some(metadata);
+        Ok(())
+    }
+
+    fn load_statuses(&self) -> Result<Vec<(usize, u8)>, StoreError> {
+        Ok(self
+            .list
+            .lock()
+            .unwrap()
+            .statuses
+            .iter()
+            .map(|(index, value)| (*index, *value))
+            .collect())
+    }
+
+    fn save_statuses(&self, statuses: &[(usize, u8)]) -> Result<(), StoreError> {
+        let mut list = self.list.lock().unwrap();
+        for (index, value) in statuses {
+            if *value == 0 {
+                list.statuses.remove(index);
+            } else {
+                list.statuses.insert(*index, *value);
+            }
+        }
+        Ok(())
+    }
+
+    fn clear_statuses(&self) -> Result<(), StoreError> {
+        self.list.lock().unwrap().statuses.clear();
+        Ok(())
+    }
+
+    fn load_allocator(&self) -> Result<Option<AllocatorState>, StoreError> {
+        Ok(self.list.lock().unwrap().allocator.clone())
+    }
+
+    fn save_allocator(&self, state: &AllocatorState) -> Result<(), StoreError> {
+        self.list.lock().unwrap().allocator = Some(state.clone());
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<(), StoreError> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sled")]
+mod sled_store {
+    use super::*;
+    use crate::error::FormatError;
+    use crate::format::{self, FormatHeader, FormatKind, Migration};
+
+    const FORMAT_KEY: &[u8] = b"format";
+    const METADATA_KEY: &[u8] = b"meta";
+    const ALLOCATOR_KEY: &[u8] = b"allocator";
+    const STATUS_PREFIX: &[u8] = b"s/";
+
+    fn storage_error(error: sled::Error) -> StoreError {
+        StoreError::StorageError(error.to_string())
+    }
+
+    // How long to wait for another handle on the same directory to let go
+    // of sled's file lock. Dropping a `Db` releases the lock only once its
+    // background threads finish, so a reopen right after a drop can race it.
+    const LOCK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+    pub(super) fn open_db(path: &std::path::Path) -> sled::Result<sled::Db> {
+        let deadline = std::time::Instant::now() + LOCK_TIMEOUT;
+        loop {
+            match sled::open(path) {
+                Err(sled::Error::Io(e))
+                    if e.to_string().contains("could not acquire lock")
+                        && std::time::Instant::now() < deadline =>
+                {
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                }
+                result => return result,
+            }
+        }
+    }
+
+    // Status keys are the prefix followed by the big-endian index, so a
+    // prefix scan returns them in index order.
+    fn status_key(index: usize) -> Vec<u8> {
+        let mut key = STATUS_PREFIX.to_vec();
+        key.extend_from_slice(&(index as u64).to_be_bytes());
+        key
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct SledStatusListStore {
+        tree: sled::Tree,
+    }
+
+    impl SledStatusListStore {
+        pub fn new(tree: sled::Tree) -> Self {
+            Self { tree }
+        }
+
+        /// Opens the default tree of the database at `path`, waiting up to a
+        /// few seconds for a handle dropped elsewhere in this process to
+        /// release the directory.
+        pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, StoreError> {
+            let db = open_db(path.as_ref()).map_err(storage_error)?;
+            Ok(Self::new((*db).clone()))
+        }
+
+        /// Rewrites the format header of this store's tree for `to_version`.
+        /// `format::migrate` does the same for the default tree of a database
+        /// directory.
+        pub fn migrate(&self, to_version: u32) -> Result<Migration, FormatError> {
+            let storage_error = |e: sled::Error| FormatError::StorageError(e.to_string());
+            format::check_target(FormatKind::Store, to_version)?;
+            let from_version = match self.tree.get(FORMAT_KEY).map_err(storage_error)? {
+                Some(header) => {
+                    let header = FormatHeader::parse(&String::from_utf8_lossy(&header))?;
+                    if header.kind != FormatKind::Store {
+                        return Err(FormatError::UnknownFormat(format!(
+                            "expected a {} header, found {}",
+                            FormatKind::Store.name(),
+                            header.kind.name()
+                        )));
+                    }
+                    header.version
+                }
+                None if self
+                    .tree
+                    .contains_key(METADATA_KEY)
+                    .map_err(storage_error)? =>
+                {
+                    0
+                }
+                None => {
+                    return Err(FormatError::UnknownFormat(
+                        "status list store is empty".to_string(),
+                    ))
+                }
+            };
+
+            if to_version == 0 {
+                self.tree.remove(FORMAT_KEY).map_err(storage_error)?;
+            } else {
+                let header = FormatHeader {
+                    version: to_version,
+                    ..FormatHeader::current(FormatKind::Store)
+                }
+                .to_string();
+                self.tree
+                    .insert(FORMAT_KEY, header.as_bytes())
+                    .map_err(storage_error)?;
+            }
+            self.tree.flush().map_err(storage_error)?;
+            Ok(Migration {
+                kind: FormatKind::Store,
+                from_version,
+                to_version,
+            })
+        }
+
+        // A tree holding metadata but no header predates versioning.
+        fn check_format(&self) -> Result<(), StoreError> {
+            let header = self.tree.get(FORMAT_KEY).map_err(storage_error)?;
+            if header.is_none()
+                && !self
+                    .tree
+                    .contains_key(METADATA_KEY)
+                    .map_err(storage_error)?
+            {
+                return Ok(());
+            }
+            let header = header.map(|header| String::from_utf8_lossy(&header).into_owned());
+            FormatHeader::expect(FormatKind::Store, header.as_deref())
+                .map_err(StoreError::FormatError)
+        }
+    }
+
+    impl StatusListStore for SledStatusListStore {
+        fn load_metadata(&self) -> Result<Option<StoreMetadata>, StoreError> {
+            self.check_format()?;
+            let Some(bytes) = self.tree.get(METADATA_KEY).map_err(storage_error)? else {
+                return Ok(None);
+            };
+            let (bits, len) = bytes
+                .split_first()
+                .filter(|(_, len)| len.len() == 8)
+                .ok_or_else(|| StoreError::Corrupted("malformed metadata record".to_string()))?;
+            Ok(Some(StoreMetadata {
+                bits_per_status: *bits,
+                len: u64::from_be_bytes(len.try_into().unwrap()) as usize,
+            }))
+        }
+
+        fn save_metadata(&self, metadata: StoreMetadata) -> Result<(), StoreError> {
+            self.check_format()?;
+            let mut bytes = vec![metadata.bits_per_status];
+            bytes.extend_from_slice(&(metadata.len as u64).to_be_bytes());
+            let mut batch = sled::Batch::default();
+            batch.insert(
+                FORMAT_KEY,
+                FormatHeader::current(FormatKind::Store)
+                    .to_string()
+                    .as_bytes(),
+            );
+            batch.insert(METADATA_KEY, bytes);
+            self.tree.apply_batch(batch).map_err(storage_error)
+        }
+
+        fn load_statuses(&self) -> Result<Vec<(usize, u8)>, StoreError> {
+            self.tree
+                .scan_prefix(STATUS_PREFIX)
+                .map(|entry| {
+                    let (key, value) = entry.map_err(storage_error)?;
+                    let index = key[STATUS_PREFIX.len()..]
+                        .try_into()
+                        .map(u64::from_be_bytes)
+                        .map_err(|_| StoreError::Corrupted("malformed status key".to_string()))?;
+                    match *value {
+                        [value] => Ok((index as usize, value)),
+                        _ => Err(StoreError::Corrupted(format!(
+                            "malformed status record at index {}",
+                            index
+                        ))),
+                    }
+                })
+                .collect()
+        }
+
+        fn save_statuses(&self, statuses: &[(usize, u8)]) -> Result<(), StoreError> {
+            let mut batch = sled::Batch::default();
+            for (index, value) in statuses {
+                if *value == 0 {
+                    batch.remove(status_key(*index));
+                } else {
+                    batch.insert(status_key(*index), vec![*value]);
+                }
+            }
+            self.tree.apply_batch(batch).map_err(storage_error)
+        }
+
+        fn clear_statuses(&self) -> Result<(), StoreError> {
+            let mut batch = sled::Batch::default();
+            for key in self.tree.scan_prefix(STATUS_PREFIX).keys() {
+                batch.remove(key.map_err(storage_error)?);
+            }
+            self.tree.apply_batch(batch).map_err(storage_error)
+        }
+
+        fn load_allocator(&self) -> Result<Option<AllocatorState>, StoreError> {
+            self.check_format()?;
+            self.tree
+                .get(ALLOCATOR_KEY)
+                .map_err(storage_error)?
+                .map(|bytes| {
+                    serde_json::from_slice(&bytes)
+                        .map_err(|e| StoreError::Corrupted(format!("allocator state: {}", e)))
+                })
+                .transpose()
+        }
+
+        fn save_allocator(&self, state: &AllocatorState) -> Result<(), StoreError> {
+            let bytes = serde_json::to_vec(state)
+                .map_err(|e| StoreError::StorageError(format!("allocator state: {}", e)))?;
+            self.tree
+                .insert(ALLOCATOR_KEY, bytes)
+                .map_err(storage_error)?;
+            Ok(())
+        }
+
+        fn flush(&self) -> Result<(), StoreError> {
+            self.tree.flush().map_err(storage_error)?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "sled")]
+pub use sled_store::SledStatusListStore;
+
+#[cfg(feature = "sled")]
+pub(crate) fn migrate_sled(
+    path: &std::path::Path,
+    to_version: u32,
+) -> Result<crate::format::Migration, crate::error::FormatError> {
+    let storage_error = |e: sled::Error| crate::error::FormatError::StorageError(e.to_string());
+    let db = sled_store::open_db(path).map_err(storage_error)?;
+    let migration = SledStatusListStore::new((*db).clone()).migrate(to_version)?;
+    db.flush().map_err(storage_error)?;
+    Ok(migration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocator::{AllocationStrategy, IndexAllocator};
+    use crate::builder::{FillStrategy, StatusListBuilder};
+    use crate::types::StatusType;
+
+    fn round_trip(store: &impl StatusListStore) {
+        let builder = StatusListBuilder::new(2)
+            .unwrap()
+            .with_capacity(1000, FillStrategy::AllValid);
+        builder
+            .set_status(3, StatusType::Invalid)
+            .set_status(500, StatusType::Suspended);
+        builder.flush(store).unwrap();
+
+        let loaded = StatusListBuilder::load(store).unwrap();
+        assert_eq!(loaded.len(), 1000);
+        assert_eq!(loaded.get_bits_per_status(), 2);
+        assert_eq!(loaded.get_status(3), Some(StatusType::Invalid));
+        assert_eq!(loaded.get_status(500), Some(StatusType::Suspended));
+        assert_eq!(loaded.build().unwrap(), builder.build().unwrap());
+
+        // Later flushes only write what changed, including statuses set back to Valid.
+        loaded
+            .set_status(3, StatusType::Valid)
+            .set_status(1200, StatusType::Invalid);
+        loaded.flush(store).unwrap();
+        assert_eq!(store.load_statuses().unwrap(), vec![(500, 2), (1200, 1)]);
+
+        let reloaded = StatusListBuilder::load(store).unwrap();
+        assert_eq!(reloaded.len(), 1201);
+        assert_eq!(reloaded.build().unwrap(), loaded.build().unwrap());
+
+        let mut allocator = IndexAllocator::new(64, AllocationStrategy::Shuffled(7));
+        allocator.allocate().unwrap();
+        store.save_allocator(&allocator.to_state()).unwrap();
+        assert_eq!(store.load_allocator().unwrap(), Some(allocator.to_state()));
+    }
+
+    #[test]
+    fn test_in_memory_round_trip() {
+        round_trip(&InMemoryStatusListStore::new());
+    }
+
+    #[test]
+    fn test_first_flush_replaces_stored_statuses() {
+        let store = InMemoryStatusListStore::new();
+        store.save_statuses(&[(7, 1)]).unwrap();
+
+        let builder = StatusListBuilder::new(1).unwrap();
+        builder.set_status(2, StatusType::Invalid);
+        builder.flush(&store).unwrap();
+        assert_eq!(store.load_statuses().unwrap(), vec![(2, 1)]);
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let store = InMemoryStatusListStore::new();
+        assert!(matches!(
+            StatusListBuilder::load(&store),
+            Err(StoreError::Empty)
+        ));
+
+        store
+            .save_metadata(StoreMetadata {
+                bits_per_status: 1,
+                len: 4,
+            })
+            .unwrap();
+        store.save_statuses(&[(9, 1)]).unwrap();
+        match StatusListBuilder::load(&store) {
+            Err(StoreError::Corrupted(msg)) => {
+                assert_eq!(msg, "status at index 9 is past the list length 4")
+            }
+            _ => panic!("Expected Corrupted"),
+        }
+    }
+
+    #[cfg(feature = "sled")]
+    #[test]
+    fn test_sled_round_trip() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        round_trip(&SledStatusListStore::new(db.open_tree("list").unwrap()));
+    }
+
+    #[cfg(feature = "sled")]
+    #[test]
+    fn test_sled_format_header() {
+        use crate::error::FormatError;
+
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let tree = db.open_tree("list").unwrap();
+        let store = SledStatusListStore::new(tree.clone());
+        store
+            .save_metadata(StoreMetadata {
+                bits_per_status: 1,
+                len: 4,
+            })
+            .unwrap();
+        assert_eq!(
+            &*tree.get("format").unwrap().unwrap(),
+            b"tsl-store 1 draft-ietf-oauth-status-list-13"
+        );
+
+        // Stores written before the header was introduced need a migration.
+        tree.remove("format").unwrap();
+        assert!(matches!(
+            StatusListBuilder::load(&store),
+            Err(StoreError::FormatError(FormatError::UnsupportedVersion(
+                _,
+                0
+            )))
+        ));
+        tree.insert("format", "tsl-store 2 draft-x").unwrap();
+        assert!(matches!(
+            store.load_allocator(),
+            Err(StoreError::FormatError(FormatError::UnsupportedVersion(
+                _,
+                2
+            )))
+        ));
+    }
+
+    #[cfg(feature = "sled")]
+    #[test]
+    fn test_sled_migrate() {
+        static RUN: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "sled-migrate-{}-{}",
+            std::process::id(),
+            RUN.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&path);
+        {
+            let store = SledStatusListStore::open(&path).unwrap();
+            let builder = StatusListBuilder::new(1).unwrap();
+            builder.set_status(2, StatusType::Invalid);
+            builder.flush(&store).unwrap();
+            store.migrate(0).unwrap();
+            assert!(StatusListBuilder::load(&store).is_err());
+            store.flush().unwrap();
+        }
+
+        let migration = crate::format::migrate(&path, 1).unwrap();
+        assert_eq!((migration.from_version, migration.to_version), (0, 1));
+        let store = SledStatusListStore::open(&path).unwrap();
+        let loaded = StatusListBuilder::load(&store).unwrap();
+        assert_eq!(loaded.get_status(2), Some(StatusType::Invalid));
+
+        let empty = SledStatusListStore::new(
+            sled::Config::new()
+                .temporary(true)
+                .open()
+                .unwrap()
+                .open_tree("list")
+                .unwrap(),
+        );
+        assert!(matches!(
+            empty.migrate(1),
+            Err(crate::error::FormatError::UnknownFormat(_))
+        ));
+        drop(store);
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+}
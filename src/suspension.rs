@@ -0,0 +1,140 @@
+use crate::builder::{FillStrategy, StatusListBuilder};
+use crate::error::{BuilderError, TransitionError};
+use crate::types::{StatusList, StatusType};
+
+// A 2-bit list of Valid, Suspended and Invalid statuses. Suspension can be
+// lifted, revocation cannot.
+#[derive(Debug)]
+pub struct SuspendableStatusList {
+    builder: StatusListBuilder,
+}
+
+impl Default for SuspendableStatusList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SuspendableStatusList {
+    pub fn new() -> Self {
+        Self {
+            builder: StatusListBuilder::new(2).expect("2 bits per status is valid"),
+        }
+    }
+
+    pub fn with_capacity(self, size: usize) -> Self {
+        Self {
+            builder: self.builder.with_capacity(size, FillStrategy::AllValid),
+        }
+    }
+
+    pub fn revoke(&self, index: usize) -> &Self {
+        self.builder.set_status(index, StatusType::Invalid);
+        self
+    }
+
+    pub fn suspend(&self, index: usize) -> Result<&Self, TransitionError> {
+        self.transition(index, StatusType::Suspended)
+    }
+
+    pub fn reinstate(&self, index: usize) -> Result<&Self, TransitionError> {
+        self.transition(index, StatusType::Valid)
+    }
+
+    pub fn is_valid(&self, index: usize) -> bool {
+        self.status(index) == StatusType::Valid
+    }
+
+    pub fn is_suspended(&self, index: usize) -> bool {
+        self.status(index) == StatusType::Suspended
+    }
+
+    pub fn is_revoked(&self, index: usize) -> bool {
+        self.status(index) == StatusType::Invalid
+    }
+
+    pub fn len(&self) -> usize {
+        self.builder.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.builder.is_empty()
+    }
+
+    pub fn build(&self) -> Result<StatusList, BuilderError> {
+        self.builder.build()
+    }
+
+    pub fn into_builder(self) -> StatusListBuilder {
+        self.builder
+    }
+
+    // Only Valid and Suspended move between each other; Invalid is final.
+    fn transition(&self, index: usize, to: StatusType) -> Result<&Self, TransitionError> {
+        match self.status(index) {
+            StatusType::Valid | StatusType::Suspended => {
+                self.builder
+                    .try_set_status(index, to)
+                    .map_err(TransitionError::BuilderError)?;
+                Ok(self)
+            }
+            StatusType::Invalid => Err(TransitionError::AlreadyRevoked(index)),
+            other => Err(TransitionError::UnexpectedStatus(index, other.value())),
+        }
+    }
+
+    fn status(&self, index: usize) -> StatusType {
+        self.builder.get_status(index).unwrap_or(StatusType::Valid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suspend_and_reinstate() {
+        let list = SuspendableStatusList::new().with_capacity(8);
+
+        list.suspend(2).unwrap();
+        assert!(list.is_suspended(2));
+        assert!(list.is_valid(3));
+
+        list.reinstate(2).unwrap();
+        assert!(list.is_valid(2));
+
+        list.suspend(10).unwrap();
+        assert_eq!(list.len(), 11);
+        assert_eq!(list.build().unwrap().bits(), 2);
+    }
+
+    #[test]
+    fn test_revocation_is_final() {
+        let list = SuspendableStatusList::new().with_capacity(4);
+        list.suspend(1).unwrap();
+        list.revoke(1);
+        assert!(list.is_revoked(1));
+
+        match list.reinstate(1) {
+            Err(TransitionError::AlreadyRevoked(1)) => (),
+            _ => panic!("Expected AlreadyRevoked"),
+        }
+        match list.suspend(1) {
+            Err(TransitionError::AlreadyRevoked(1)) => (),
+            _ => panic!("Expected AlreadyRevoked"),
+        }
+    }
+
+    #[test]
+    fn test_unexpected_status() {
+        let list = SuspendableStatusList::new().with_capacity(4);
+        let builder = list.into_builder();
+        builder.set_status(0, StatusType::ApplicationSpecific3);
+        let list = SuspendableStatusList { builder };
+
+        match list.suspend(0) {
+            Err(TransitionError::UnexpectedStatus(0, 3)) => (),
+            _ => panic!("Expected UnexpectedStatus"),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::encoder::StatusListEncoder;
 use crate::error::DecoderError;
 use crate::{builder::StatusListBuilder, decoder::StatusListDecoder, types::StatusType};
 use serde_json::Value;
@@ -82,6 +83,81 @@ mod spec_compliance_tests {
     }
 }
 
+#[cfg(test)]
+mod bit_order_tests {
+    use super::*;
+
+    const ALL_STATUSES: [StatusType; 9] = [
+        StatusType::Valid,
+        StatusType::Invalid,
+        StatusType::Suspended,
+        StatusType::ApplicationSpecific3,
+        StatusType::ApplicationSpecific11,
+        StatusType::ApplicationSpecific12,
+        StatusType::ApplicationSpecific13,
+        StatusType::ApplicationSpecific14,
+        StatusType::ApplicationSpecific15,
+    ];
+
+    // Bit k of the status at index i lives at overall bit i * bits + k,
+    // counting from the least significant bit of byte 0.
+    fn reference_pack(values: &[u8], bits: usize) -> Vec<u8> {
+        let mut bytes = vec![0u8; (values.len() * bits).div_ceil(8)];
+        for (i, value) in values.iter().enumerate() {
+            for k in 0..bits {
+                if value & (1 << k) != 0 {
+                    let bit = i * bits + k;
+                    bytes[bit / 8] |= 1 << (bit % 8);
+                }
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_all_bit_widths_match_reference_layout() -> Result<(), DecoderError> {
+        for bits in [1u8, 2, 4, 8] {
+            let representable: Vec<StatusType> = ALL_STATUSES
+                .iter()
+                .copied()
+                .filter(|status| (status.value() as u16) < (1 << bits))
+                .collect();
+
+            // Every representable status at every position within three bytes
+            for status in &representable {
+                for len in 1..=(3 * 8 / bits as usize) {
+                    let mut statuses = vec![StatusType::Valid; len];
+                    statuses[len - 1] = *status;
+                    statuses[0] = representable[representable.len() - 1];
+
+                    let values: Vec<u8> = statuses.iter().map(|s| s.value()).collect();
+                    let bytes = StatusListEncoder::new(bits)
+                        .encode_statuses(&statuses)
+                        .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?;
+                    assert_eq!(
+                        bytes,
+                        reference_pack(&values, bits as usize),
+                        "{}-bit layout mismatch for {:?}",
+                        bits,
+                        statuses
+                    );
+
+                    let status_list = StatusListBuilder::from_vec(statuses.clone(), bits)
+                        .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?
+                        .build()
+                        .map_err(|e| DecoderError::StatusListCreationError(e.to_string()))?;
+                    let decoder = StatusListDecoder::new(&status_list)?;
+                    for (i, expected) in statuses.iter().enumerate() {
+                        assert_eq!(decoder.get_status(i)?, *expected);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod error_handling_tests {
     use super::*;
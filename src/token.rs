@@ -0,0 +1,943 @@
+use coset::cbor::value::{Integer, Value};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "decoder")]
+use sha2::{Digest, Sha256};
+#[cfg(feature = "decoder")]
+use std::collections::HashMap;
+#[cfg(feature = "decoder")]
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "decoder")]
+use crate::decoder::StatusListDecoder;
+#[cfg(feature = "decoder")]
+use crate::error::DecoderError;
+use crate::error::TokenError;
+#[cfg(all(feature = "decoder", feature = "jwt"))]
+use crate::jwt::JwtStatusListToken;
+#[cfg(all(feature = "decoder", feature = "reasons"))]
+use crate::reasons::StatusReasons;
+use crate::signing::{self, PayloadType, SignatureVerifier, Signer};
+#[cfg(feature = "decoder")]
+use crate::types::BitsPerStatus;
+use crate::types::{CborStatusList, StatusList};
+use crate::uri::StatusListUri;
+
+pub const CWT_TYPE: &str = "application/statuslist+cwt";
+
+pub(crate) const SUB_CLAIM: i64 = 2;
+const EXP_CLAIM: i64 = 4;
+pub(crate) const IAT_CLAIM: i64 = 6;
+pub(crate) const STATUS_LIST_CLAIM: i64 = 65533;
+const TTL_CLAIM: i64 = 65534;
+// A crate extension, so a text key rather than a registered claim number.
+const PROVENANCE_CLAIM: &str = "provenance";
+
+/// Where a token re-signed by a federation gateway came from (crate
+/// extension, not part of the specification).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Provenance {
+    /// The upstream issuer's URI for the list.
+    pub uri: StatusListUri,
+    /// Hex SHA-256 of the upstream token as the gateway received it.
+    pub token_sha256: String,
+    pub verified_at: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CwtStatusListToken {
+    pub sub: StatusListUri,
+    pub iat: u64,
+    pub exp: Option<u64>,
+    pub ttl: Option<u64>,
+    pub status_list: StatusList,
+    pub provenance: Option<Provenance>,
+}
+
+impl CwtStatusListToken {
+    pub fn new(sub: StatusListUri, iat: u64, status_list: StatusList) -> Self {
+        Self {
+            sub,
+            iat,
+            exp: None,
+            ttl: None,
+            status_list,
+            provenance: None,
+        }
+    }
+
+    pub fn with_exp(mut self, exp: u64) -> Self {
+        self.exp = Some(exp);
+        self
+    }
+
+    pub fn with_ttl(mut self, ttl: u64) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub fn with_provenance(mut self, provenance: Provenance) -> Self {
+        self.provenance = Some(provenance);
+        self
+    }
+
+    /// The same token with a new `iat` and `exp`, for periodically re-signing
+    /// a list that has not changed. The already compressed `lst` is reused
+    /// as is, so nothing is re-packed or re-compressed.
+    pub fn reissue(&self, iat: u64, exp: Option<u64>) -> Self {
+        Self {
+            iat,
+            exp,
+            ..self.clone()
+        }
+    }
+
+    pub fn sign(&self, signer: &impl Signer) -> Result<Vec<u8>, TokenError> {
+        Ok(signing::sign_cose(
+            PayloadType::Typ(CWT_TYPE),
+            self.claims()?,
+            signer,
+        )?)
+    }
+
+    pub fn verify(bytes: &[u8], verifier: &impl SignatureVerifier) -> Result<Self, TokenError> {
+        let payload = signing::verify_cose(bytes, PayloadType::Typ(CWT_TYPE), verifier)?;
+        Self::from_claims(&payload)
+    }
+
+    fn claims(&self) -> Result<Vec<u8>, TokenError> {
+        let status_list = Value::serialized(&self.status_list)
+            .map_err(|e| TokenError::EncodingError(e.to_string()))?;
+
+        let mut claims = vec![
+            (claim_key(SUB_CLAIM), Value::Text(self.sub.to_string())),
+            (claim_key(IAT_CLAIM), Value::Integer(self.iat.into())),
+        ];
+        if let Some(exp) = self.exp {
+            claims.push((claim_key(EXP_CLAIM), Value::Integer(exp.into())));
+        }
+        if let Some(ttl) = self.ttl {
+            claims.push((claim_key(TTL_CLAIM), Value::Integer(ttl.into())));
+        }
+        claims.push((claim_key(STATUS_LIST_CLAIM), status_list));
+        if let Some(provenance) = &self.provenance {
+            let provenance = Value::serialized(provenance)
+                .map_err(|e| TokenError::EncodingError(e.to_string()))?;
+            claims.push((Value::Text(PROVENANCE_CLAIM.to_string()), provenance));
+        }
+
+        let mut payload = Vec::new();
+        ciborium::ser::into_writer(&Value::Map(claims), &mut payload)
+            .map_err(|e| TokenError::EncodingError(e.to_string()))?;
+        Ok(payload)
+    }
+
+    fn from_claims(payload: &[u8]) -> Result<Self, TokenError> {
+        let claims = match ciborium::de::from_reader(payload) {
+            Ok(Value::Map(claims)) => claims,
+            Ok(_) => {
+                return Err(TokenError::EncodingError(
+                    "claims set is not a map".to_string(),
+                ))
+            }
+            Err(e) => return Err(TokenError::EncodingError(e.to_string())),
+        };
+
+        let mut sub = None;
+        let mut iat = None;
+        let mut exp = None;
+        let mut ttl = None;
+        let mut status_list = None;
+        let mut provenance = None;
+        for (key, value) in claims {
+            if key.as_text() == Some(PROVENANCE_CLAIM) {
+                provenance = Some(
+                    value
+                        .deserialized()
+                        .map_err(|_| TokenError::InvalidClaim("provenance".to_string()))?,
+                );
+                continue;
+            }
+            let key = key.as_integer().and_then(|key| i64::try_from(key).ok());
+            match key {
+                Some(SUB_CLAIM) => {
+                    let uri = value
+                        .as_text()
+                        .ok_or_else(|| TokenError::InvalidClaim("sub".to_string()))?;
+                    sub = Some(
+                        StatusListUri::parse_with_schemes(uri, &["https", "http"])
+                            .map_err(|_| TokenError::InvalidClaim("sub".to_string()))?,
+                    );
+                }
+                Some(IAT_CLAIM) => iat = Some(claim_u64(&value, "iat")?),
+                Some(EXP_CLAIM) => exp = Some(claim_u64(&value, "exp")?),
+                Some(TTL_CLAIM) => ttl = Some(claim_u64(&value, "ttl")?),
+                Some(STATUS_LIST_CLAIM) => {
+                    let cbor_list: CborStatusList = value
+                        .deserialized()
+                        .map_err(|_| TokenError::InvalidClaim("status_list".to_string()))?;
+                    status_list = Some(
+                        StatusList::try_from(cbor_list)
+                            .map_err(|_| TokenError::InvalidClaim("status_list".to_string()))?,
+                    );
+                }
+                _ => (),
+            }
+        }
+
+        Ok(Self {
+            sub: sub.ok_or_else(|| TokenError::MissingClaim("sub".to_string()))?,
+            iat: iat.ok_or_else(|| TokenError::MissingClaim("iat".to_string()))?,
+            exp,
+            ttl,
+            status_list: status_list
+                .ok_or_else(|| TokenError::MissingClaim("status_list".to_string()))?,
+            provenance,
+        })
+    }
+}
+
+#[cfg(feature = "decoder")]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StatusCheckReport {
+    pub uri: String,
+    pub idx: usize,
+    pub status: u8,
+    pub token_sha256: String,
+    pub token_iat: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_exp: Option<u64>,
+    pub checked_at: u64,
+    #[serde(default)]
+    pub rollback_detected: bool,
+    /// The reason code for `status`, when the verifier was given
+    /// `StatusReasons` for this list.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[cfg(feature = "decoder")]
+#[derive(Debug, Clone, Default)]
+pub struct ReplayWindow {
+    highest_iat: Arc<Mutex<HashMap<String, u64>>>,
+}
+
+#[cfg(feature = "decoder")]
+impl ReplayWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn highest_iat(&self, uri: &StatusListUri) -> Option<u64> {
+        self.highest_iat.lock().unwrap().get(uri.as_str()).copied()
+    }
+
+    // Records iat and reports whether a newer token was already seen.
+    fn observe(&self, uri: &StatusListUri, iat: u64) -> bool {
+        let mut highest_iat = self.highest_iat.lock().unwrap();
+        let highest = highest_iat.entry(uri.as_str().to_string()).or_insert(iat);
+        if iat < *highest {
+            return true;
+        }
+        *highest = iat;
+        false
+    }
+}
+
+#[cfg(feature = "decoder")]
+#[derive(Debug, Clone)]
+pub struct StatusListTokenVerifier {
+    expected_uri: StatusListUri,
+    leeway: u64,
+    replay_window: Option<ReplayWindow>,
+    expected_bits: Option<BitsPerStatus>,
+    migration_bits: Option<BitsPerStatus>,
+    max_decompressed_len: Option<usize>,
+    known_len: Option<usize>,
+    reject_stale: bool,
+    #[cfg(feature = "reasons")]
+    reasons: Option<StatusReasons>,
+}
+
+#[cfg(feature = "decoder")]
+impl StatusListTokenVerifier {
+    pub fn new(expected_uri: StatusListUri) -> Self {
+        Self {
+            expected_uri,
+            leeway: 0,
+            replay_window: None,
+            expected_bits: None,
+            migration_bits: None,
+            max_decompressed_len: None,
+            known_len: None,
+            reject_stale: false,
+            #[cfg(feature = "reasons")]
+            reasons: None,
+        }
+    }
+
+    pub fn with_leeway(mut self, leeway: u64) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    pub fn with_replay_window(mut self, replay_window: ReplayWindow) -> Self {
+        self.replay_window = Some(replay_window);
+        self
+    }
+
+    pub fn with_expected_bits(mut self, bits: BitsPerStatus) -> Self {
+        self.expected_bits = Some(bits);
+        self
+    }
+
+    // Also accepts `bits` while an announced change of width rolls out.
+    pub fn with_bits_migration(mut self, bits: BitsPerStatus) -> Self {
+        self.migration_bits = Some(bits);
+        self
+    }
+
+    /// Rejects lists that inflate past `len` bytes, instead of past
+    /// `DEFAULT_MAX_DECOMPRESSED_LEN`.
+    pub fn with_max_decompressed_len(mut self, len: usize) -> Self {
+        self.max_decompressed_len = Some(len);
+        self
+    }
+
+    /// The number of statuses the issuer allocated, when known out of band.
+    /// Indices at or past it fail with `TokenError::IndexNotPresent` instead
+    /// of reading the padding bits of the last byte, which decode as valid.
+    pub fn with_known_len(mut self, len: usize) -> Self {
+        self.known_len = Some(len);
+        self
+    }
+
+    /// Rejects tokens past `iat + ttl` with `TokenError::Stale`. By default
+    /// `ttl` is only a hint for when to refetch and `exp` alone expires a
+    /// token.
+    pub fn with_reject_stale(mut self) -> Self {
+        self.reject_stale = true;
+        self
+    }
+
+    /// Fills in `StatusCheckReport::reason` from an already verified
+    /// reasons document. Reasons for another list are ignored.
+    #[cfg(feature = "reasons")]
+    pub fn with_reasons(mut self, reasons: StatusReasons) -> Self {
+        self.reasons = Some(reasons);
+        self
+    }
+
+    pub fn verify_cwt(
+        &self,
+        bytes: &[u8],
+        verifier: &impl SignatureVerifier,
+        now: u64,
+    ) -> Result<StatusListDecoder, TokenError> {
+        let token = CwtStatusListToken::verify(bytes, verifier)?;
+        self.decode((&token).into(), now)
+    }
+
+    pub fn check_cwt(
+        &self,
+        bytes: &[u8],
+        verifier: &impl SignatureVerifier,
+        idx: usize,
+        now: u64,
+    ) -> Result<StatusCheckReport, TokenError> {
+        let token = CwtStatusListToken::verify(bytes, verifier)?;
+        self.report((&token).into(), bytes, idx, now)
+    }
+
+    /// Like `verify_cwt`, for a Status List Token in JWT form.
+    #[cfg(feature = "jwt")]
+    pub fn verify_jwt(
+        &self,
+        token: &str,
+        verifier: &impl SignatureVerifier,
+        now: u64,
+    ) -> Result<StatusListDecoder, TokenError> {
+        let jwt = JwtStatusListToken::verify(token, verifier)?;
+        self.decode((&jwt).into(), now)
+    }
+
+    #[cfg(feature = "jwt")]
+    pub fn check_jwt(
+        &self,
+        token: &str,
+        verifier: &impl SignatureVerifier,
+        idx: usize,
+        now: u64,
+    ) -> Result<StatusCheckReport, TokenError> {
+        let jwt = JwtStatusListToken::verify(token, verifier)?;
+        self.report((&jwt).into(), token.as_bytes(), idx, now)
+    }
+
+    // For gateways: returns the verified token itself.
+    pub(crate) fn verify_cwt_token(
+        &self,
+        bytes: &[u8],
+        verifier: &impl SignatureVerifier,
+        now: u64,
+    ) -> Result<CwtStatusListToken, TokenError> {
+        let token = CwtStatusListToken::verify(bytes, verifier)?;
+        self.decode((&token).into(), now)?;
+        Ok(token)
+    }
+
+    #[cfg(feature = "jwt")]
+    pub(crate) fn verify_jwt_token(
+        &self,
+        token: &str,
+        verifier: &impl SignatureVerifier,
+        now: u64,
+    ) -> Result<JwtStatusListToken, TokenError> {
+        let jwt = JwtStatusListToken::verify(token, verifier)?;
+        self.decode((&jwt).into(), now)?;
+        Ok(jwt)
+    }
+
+    // For caches and probes: also returns when the token was issued and
+    // goes stale.
+    pub(crate) fn verify_cwt_until(
+        &self,
+        bytes: &[u8],
+        verifier: &impl SignatureVerifier,
+        now: u64,
+    ) -> Result<(StatusListDecoder, Freshness), TokenError> {
+        let token = CwtStatusListToken::verify(bytes, verifier)?;
+        let claims = Claims::from(&token);
+        let freshness = claims.freshness();
+        Ok((self.decode(claims, now)?, freshness))
+    }
+
+    #[cfg(feature = "jwt")]
+    pub(crate) fn verify_jwt_until(
+        &self,
+        token: &str,
+        verifier: &impl SignatureVerifier,
+        now: u64,
+    ) -> Result<(StatusListDecoder, Freshness), TokenError> {
+        let jwt = JwtStatusListToken::verify(token, verifier)?;
+        let claims = Claims::from(&jwt);
+        let freshness = claims.freshness();
+        Ok((self.decode(claims, now)?, freshness))
+    }
+
+    fn decode(&self, claims: Claims<'_>, now: u64) -> Result<StatusListDecoder, TokenError> {
+        self.check(&claims, now)?;
+        if self.is_rollback(&claims) {
+            return Err(TokenError::Rollback(claims.iat));
+        }
+
+        self.decoder(claims.status_list)
+    }
+
+    fn report(
+        &self,
+        claims: Claims<'_>,
+        bytes: &[u8],
+        idx: usize,
+        now: u64,
+    ) -> Result<StatusCheckReport, TokenError> {
+        self.check(&claims, now)?;
+
+        let status = self
+            .decoder(claims.status_list)?
+            .get_status(idx)
+            .map_err(|e| match e {
+                DecoderError::IndexNotPresent(idx) => TokenError::IndexNotPresent(idx),
+                _ => TokenError::StatusUnavailable(idx),
+            })?;
+        let rollback_detected = self.is_rollback(&claims);
+        #[cfg(feature = "reasons")]
+        let reason = self
+            .reasons
+            .as_ref()
+            .filter(|reasons| reasons.uri.ensure_matches(claims.sub).is_ok())
+            .and_then(|reasons| reasons.reason_for(status))
+            .map(str::to_string);
+        #[cfg(not(feature = "reasons"))]
+        let reason = None;
+
+        Ok(StatusCheckReport {
+            uri: claims.sub.to_string(),
+            idx,
+            status: status.value(),
+            token_sha256: hex::encode(Sha256::digest(bytes)),
+            token_iat: claims.iat,
+            token_exp: claims.exp,
+            checked_at: now,
+            rollback_detected,
+            reason,
+        })
+    }
+
+    fn decoder(&self, status_list: &StatusList) -> Result<StatusListDecoder, TokenError> {
+        let decoder = match self.max_decompressed_len {
+            Some(len) => StatusListDecoder::with_max_decompressed_len(status_list, len),
+            None => StatusListDecoder::new(status_list),
+        };
+        match self.known_len {
+            Some(len) => decoder.and_then(|decoder| decoder.with_len(len)),
+            None => decoder,
+        }
+        .map_err(|e| TokenError::InvalidClaim(format!("status_list ({})", e)))
+    }
+
+    fn is_rollback(&self, claims: &Claims<'_>) -> bool {
+        self.replay_window
+            .as_ref()
+            .is_some_and(|window| window.observe(claims.sub, claims.iat))
+    }
+
+    pub fn check_claims(&self, token: &CwtStatusListToken, now: u64) -> Result<(), TokenError> {
+        self.check(&token.into(), now)
+    }
+
+    fn check(&self, claims: &Claims<'_>, now: u64) -> Result<(), TokenError> {
+        self.expected_uri
+            .ensure_matches(claims.sub)
+            .map_err(|e| TokenError::SubjectMismatch(e.to_string()))?;
+
+        if let Some(expected) = self.expected_bits {
+            let bits = claims.status_list.bits;
+            if bits != expected as u8 && self.migration_bits.map(|b| b as u8) != Some(bits) {
+                return Err(TokenError::UnexpectedBits(expected as u8, bits));
+            }
+        }
+        if claims.iat > now.saturating_add(self.leeway) {
+            return Err(TokenError::NotYetValid(claims.iat));
+        }
+        if let Some(exp) = claims.exp {
+            if now >= exp.saturating_add(self.leeway) {
+                return Err(TokenError::Expired(exp));
+            }
+        }
+        if let Some(ttl) = claims.ttl.filter(|_| self.reject_stale) {
+            let stale_at = claims.iat.saturating_add(ttl);
+            if now >= stale_at.saturating_add(self.leeway) {
+                return Err(TokenError::Stale(stale_at));
+            }
+        }
+        Ok(())
+    }
+}
+
+// The claims the verifier checks, shared by the CWT and JWT forms.
+#[cfg(feature = "decoder")]
+struct Claims<'a> {
+    sub: &'a StatusListUri,
+    iat: u64,
+    exp: Option<u64>,
+    ttl: Option<u64>,
+    status_list: &'a StatusList,
+}
+
+#[cfg(feature = "decoder")]
+impl Claims<'_> {
+    fn freshness(&self) -> Freshness {
+        Freshness {
+            iat: self.iat,
+            exp: self.exp,
+            ttl: self.ttl,
+        }
+    }
+}
+
+// The time claims of a verified token, which bound how long a cache may
+// serve its list.
+#[cfg(feature = "decoder")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Freshness {
+    pub(crate) iat: u64,
+    pub(crate) exp: Option<u64>,
+    pub(crate) ttl: Option<u64>,
+}
+
+#[cfg(feature = "decoder")]
+impl Freshness {
+    // The earlier of exp and iat + ttl.
+    pub(crate) fn stale_at(&self) -> Option<u64> {
+        [self.exp, self.ttl.map(|ttl| self.iat.saturating_add(ttl))]
+            .into_iter()
+            .flatten()
+            .min()
+    }
+}
+
+#[cfg(feature = "decoder")]
+impl<'a> From<&'a CwtStatusListToken> for Claims<'a> {
+    fn from(token: &'a CwtStatusListToken) -> Self {
+        Self {
+            sub: &token.sub,
+            iat: token.iat,
+            exp: token.exp,
+            ttl: token.ttl,
+            status_list: &token.status_list,
+        }
+    }
+}
+
+#[cfg(all(feature = "decoder", feature = "jwt"))]
+impl<'a> From<&'a JwtStatusListToken> for Claims<'a> {
+    fn from(token: &'a JwtStatusListToken) -> Self {
+        Self {
+            sub: &token.sub,
+            iat: token.iat,
+            exp: token.exp,
+            ttl: token.ttl,
+            status_list: &token.status_list,
+        }
+    }
+}
+
+fn claim_key(key: i64) -> Value {
+    Value::Integer(key.into())
+}
+
+fn claim_u64(value: &Value, name: &str) -> Result<u64, TokenError> {
+    value
+        .as_integer()
+        .and_then(|value: Integer| u64::try_from(value).ok())
+        .ok_or_else(|| TokenError::InvalidClaim(name.to_string()))
+}
+
+#[cfg(all(test, feature = "encoder"))]
+mod tests {
+    use super::*;
+    use crate::builder::StatusListBuilder;
+    use crate::signing::tests::TestKey;
+    use crate::types::StatusType;
+    use coset::{CoseSign1, TaggedCborSerializable};
+
+    fn sample_token() -> CwtStatusListToken {
+        let status_list = StatusListBuilder::from_vec(vec![StatusType::Invalid; 2], 1)
+            .unwrap()
+            .build()
+            .unwrap();
+        let sub = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+
+        CwtStatusListToken::new(sub, 1686920170, status_list)
+            .with_exp(2291720170)
+            .with_ttl(43200)
+    }
+
+    #[test]
+    fn test_reissue_reuses_lst() {
+        use crate::compression::{Compressor, ZlibCompression};
+
+        // Stored rather than deflated, so re-compressing would change it.
+        let lst = ZlibCompression::new(0).compress(&[0b10]).unwrap();
+        let status_list = StatusList::from_compressed(1, lst.clone()).unwrap();
+        let sub = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        let token = CwtStatusListToken::new(sub, 1000, status_list).with_ttl(300);
+
+        let reissued = token.reissue(2000, Some(5000));
+        assert_eq!(
+            (reissued.iat, reissued.exp, reissued.ttl),
+            (2000, Some(5000), Some(300))
+        );
+        let signed = reissued.sign(&TestKey(1)).unwrap();
+        let verified = CwtStatusListToken::verify(&signed, &TestKey(1)).unwrap();
+        assert_eq!(verified.status_list.lst(), &lst[..]);
+        assert_eq!(verified, reissued);
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let token = sample_token();
+        let signed = token.sign(&TestKey(1)).unwrap();
+
+        let sign1 = CoseSign1::from_tagged_slice(&signed).unwrap();
+        assert_eq!(sign1.protected.header.key_id, b"test-key".to_vec());
+
+        let verified = CwtStatusListToken::verify(&signed, &TestKey(1)).unwrap();
+        assert_eq!(verified, token);
+    }
+
+    #[test]
+    fn test_claim_keys() {
+        let payload = sample_token().claims().unwrap();
+        let claims: Value = ciborium::de::from_reader(&payload[..]).unwrap();
+        let keys: Vec<i64> = claims
+            .as_map()
+            .unwrap()
+            .iter()
+            .map(|(key, _)| i64::try_from(key.as_integer().unwrap()).unwrap())
+            .collect();
+
+        assert_eq!(keys, vec![2, 6, 4, 65534, 65533]);
+    }
+
+    #[test]
+    fn test_verify_with_wrong_key() {
+        let signed = sample_token().sign(&TestKey(1)).unwrap();
+
+        match CwtStatusListToken::verify(&signed, &TestKey(2)) {
+            Err(TokenError::SignatureError(msg)) => assert_eq!(msg, "bad signature"),
+            _ => panic!("Expected SignatureError"),
+        }
+    }
+
+    #[cfg(feature = "decoder")]
+    #[test]
+    fn test_token_verifier() {
+        let signed = sample_token().sign(&TestKey(1)).unwrap();
+        let expected = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        let verifier = StatusListTokenVerifier::new(expected);
+
+        let decoder = verifier
+            .verify_cwt(&signed, &TestKey(1), 1686920200)
+            .unwrap();
+        assert_eq!(decoder.get_status(1).unwrap(), StatusType::Invalid);
+
+        match verifier.verify_cwt(&signed, &TestKey(1), 1686920100) {
+            Err(TokenError::NotYetValid(1686920170)) => (),
+            _ => panic!("Expected NotYetValid"),
+        }
+        assert!(verifier
+            .clone()
+            .with_leeway(120)
+            .verify_cwt(&signed, &TestKey(1), 1686920100)
+            .is_ok());
+
+        assert!(verifier
+            .verify_cwt(&signed, &TestKey(1), 1686920170 + 43200)
+            .is_ok());
+        match verifier.clone().with_reject_stale().verify_cwt(
+            &signed,
+            &TestKey(1),
+            1686920170 + 43200,
+        ) {
+            Err(TokenError::Stale(1686963370)) => (),
+            _ => panic!("Expected Stale from ttl"),
+        }
+        let token = sample_token().with_ttl(u64::MAX);
+        match verifier.check_claims(&token, 2291720170) {
+            Err(TokenError::Expired(2291720170)) => (),
+            _ => panic!("Expected Expired from exp"),
+        }
+
+        match verifier.verify_cwt(&signed, &TestKey(2), 1686920200) {
+            Err(TokenError::SignatureError(_)) => (),
+            _ => panic!("Expected SignatureError"),
+        }
+    }
+
+    #[cfg(feature = "decoder")]
+    #[test]
+    fn test_check_report() {
+        let signed = sample_token().sign(&TestKey(1)).unwrap();
+        let expected = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        let verifier = StatusListTokenVerifier::new(expected);
+
+        let report = verifier
+            .check_cwt(&signed, &TestKey(1), 1, 1686920200)
+            .unwrap();
+        assert_eq!(report.status, StatusType::Invalid.value());
+        assert_eq!(report.token_sha256, hex::encode(Sha256::digest(&signed)));
+        assert_eq!(report.token_sha256.len(), 64);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "uri": "https://example.com/statuslists/1",
+                "idx": 1,
+                "status": 1,
+                "token_sha256": report.token_sha256,
+                "token_iat": 1686920170u64,
+                "token_exp": 2291720170u64,
+                "checked_at": 1686920200u64,
+                "rollback_detected": false,
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<StatusCheckReport>(json).unwrap(),
+            report
+        );
+
+        match verifier.check_cwt(&signed, &TestKey(1), 8, 1686920200) {
+            Err(TokenError::StatusUnavailable(8)) => (),
+            _ => panic!("Expected StatusUnavailable"),
+        }
+
+        // Index 5 is padding in the list's only byte.
+        let verifier = verifier.with_known_len(2);
+        match verifier.check_cwt(&signed, &TestKey(1), 5, 1686920200) {
+            Err(TokenError::IndexNotPresent(5)) => (),
+            _ => panic!("Expected IndexNotPresent"),
+        }
+        assert!(verifier
+            .check_cwt(&signed, &TestKey(1), 1, 1686920200)
+            .is_ok());
+        assert!(matches!(
+            verifier
+                .with_known_len(9)
+                .verify_cwt(&signed, &TestKey(1), 1686920200),
+            Err(TokenError::InvalidClaim(_))
+        ));
+    }
+
+    #[cfg(all(feature = "decoder", feature = "reasons"))]
+    #[test]
+    fn test_check_report_reason() {
+        use crate::reasons::StatusReasons;
+
+        let signed = sample_token().sign(&TestKey(1)).unwrap();
+        let uri = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        let reasons = StatusReasons::verify(
+            &StatusReasons::new(uri.clone())
+                .with_reason(StatusType::Invalid, "key-compromise")
+                .sign(&TestKey(1))
+                .unwrap(),
+            &TestKey(1),
+        )
+        .unwrap();
+
+        let report = StatusListTokenVerifier::new(uri.clone())
+            .with_reasons(reasons)
+            .check_cwt(&signed, &TestKey(1), 1, 1686920200)
+            .unwrap();
+        assert_eq!(report.reason.as_deref(), Some("key-compromise"));
+        assert_eq!(
+            serde_json::to_value(&report).unwrap()["reason"],
+            "key-compromise"
+        );
+
+        let other = StatusListUri::parse("https://example.com/statuslists/2").unwrap();
+        let report = StatusListTokenVerifier::new(uri)
+            .with_reasons(StatusReasons::new(other).with_reason(StatusType::Invalid, "superseded"))
+            .check_cwt(&signed, &TestKey(1), 1, 1686920200)
+            .unwrap();
+        assert_eq!(report.reason, None);
+    }
+
+    #[cfg(feature = "decoder")]
+    #[test]
+    fn test_replay_window() {
+        let older = sample_token().sign(&TestKey(1)).unwrap();
+        let mut newer = sample_token();
+        newer.iat += 60;
+        let newer = newer.sign(&TestKey(1)).unwrap();
+
+        let uri = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        let window = ReplayWindow::new();
+        let verifier = StatusListTokenVerifier::new(uri.clone()).with_replay_window(window.clone());
+        let now = 1686920300;
+
+        assert!(verifier.verify_cwt(&older, &TestKey(1), now).is_ok());
+        assert!(verifier.verify_cwt(&newer, &TestKey(1), now).is_ok());
+        assert!(verifier.verify_cwt(&newer, &TestKey(1), now).is_ok());
+        assert_eq!(window.highest_iat(&uri), Some(1686920230));
+
+        match verifier.verify_cwt(&older, &TestKey(1), now) {
+            Err(TokenError::Rollback(1686920170)) => (),
+            _ => panic!("Expected Rollback"),
+        }
+        let report = verifier.check_cwt(&older, &TestKey(1), 0, now).unwrap();
+        assert!(report.rollback_detected);
+
+        let unguarded = StatusListTokenVerifier::new(uri);
+        assert!(unguarded.verify_cwt(&older, &TestKey(1), now).is_ok());
+    }
+
+    #[cfg(feature = "decoder")]
+    #[test]
+    fn test_token_verifier_subject_mismatch() {
+        let signed = sample_token().sign(&TestKey(1)).unwrap();
+        let expected = StatusListUri::parse("https://example.com/statuslists/2").unwrap();
+
+        match StatusListTokenVerifier::new(expected).verify_cwt(&signed, &TestKey(1), 1686920200) {
+            Err(TokenError::SubjectMismatch(msg)) => assert!(msg.contains("path")),
+            _ => panic!("Expected SubjectMismatch"),
+        }
+    }
+
+    #[cfg(feature = "decoder")]
+    #[test]
+    fn test_token_verifier_pinned_bits() {
+        let signed = sample_token().sign(&TestKey(1)).unwrap();
+        let uri = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        let now = 1686920200;
+
+        let pinned =
+            StatusListTokenVerifier::new(uri.clone()).with_expected_bits(BitsPerStatus::OneBit);
+        assert!(pinned.verify_cwt(&signed, &TestKey(1), now).is_ok());
+
+        let pinned = StatusListTokenVerifier::new(uri).with_expected_bits(BitsPerStatus::TwoBit);
+        match pinned.verify_cwt(&signed, &TestKey(1), now) {
+            Err(TokenError::UnexpectedBits(2, 1)) => (),
+            _ => panic!("Expected UnexpectedBits"),
+        }
+
+        let migrating = pinned.with_bits_migration(BitsPerStatus::OneBit);
+        assert!(migrating.verify_cwt(&signed, &TestKey(1), now).is_ok());
+    }
+
+    #[cfg(feature = "decoder")]
+    #[test]
+    fn test_token_verifier_max_decompressed_len() {
+        let signed = sample_token().sign(&TestKey(1)).unwrap();
+        let uri = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        let now = 1686920200;
+
+        let verifier = StatusListTokenVerifier::new(uri);
+        assert!(verifier
+            .clone()
+            .with_max_decompressed_len(1)
+            .verify_cwt(&signed, &TestKey(1), now)
+            .is_ok());
+        match verifier
+            .with_max_decompressed_len(0)
+            .check_cwt(&signed, &TestKey(1), 0, now)
+        {
+            Err(TokenError::InvalidClaim(msg)) => assert!(msg.contains("exceeds 0 bytes")),
+            _ => panic!("Expected InvalidClaim"),
+        }
+    }
+
+    #[cfg(all(feature = "decoder", feature = "jwt"))]
+    #[test]
+    fn test_token_verifier_jwt() {
+        let token = sample_token();
+        let jwt = JwtStatusListToken::new(token.sub.clone(), token.iat, token.status_list)
+            .with_exp(1686920300)
+            .sign(&TestKey(1))
+            .unwrap();
+        let verifier = StatusListTokenVerifier::new(token.sub);
+
+        let report = verifier
+            .check_jwt(&jwt, &TestKey(1), 1, 1686920200)
+            .unwrap();
+        assert_eq!(report.status, StatusType::Invalid.value());
+        assert_eq!(
+            report.token_sha256,
+            hex::encode(Sha256::digest(jwt.as_bytes()))
+        );
+
+        match verifier.verify_jwt(&jwt, &TestKey(1), 1686920300) {
+            Err(TokenError::Expired(1686920300)) => (),
+            _ => panic!("Expected Expired"),
+        }
+        match verifier.verify_jwt(&jwt, &TestKey(2), 1686920200) {
+            Err(TokenError::SignatureError(_)) => (),
+            _ => panic!("Expected SignatureError"),
+        }
+    }
+
+    #[test]
+    fn test_missing_claim() {
+        let mut payload = Vec::new();
+        ciborium::ser::into_writer(
+            &Value::Map(vec![(claim_key(IAT_CLAIM), Value::Integer(1.into()))]),
+            &mut payload,
+        )
+        .unwrap();
+
+        match CwtStatusListToken::from_claims(&payload) {
+            Err(TokenError::MissingClaim(claim)) => assert_eq!(claim, "sub"),
+            _ => panic!("Expected MissingClaim"),
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::future::Future;
+
+use crate::error::ClientError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransportResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub cache_control: Option<String>,
+    pub body: Vec<u8>,
+}
+
+// The HTTP layer under `StatusListClient`. Futures carry no `Send` bound so
+// browser implementations, whose futures hold JavaScript values, can satisfy it.
+pub trait Transport {
+    /// Performs a GET request for `url` with the given `Accept` header.
+    fn get(
+        &self,
+        url: &str,
+        accept: &str,
+    ) -> impl Future<Output = Result<TransportResponse, ClientError>>;
+}
+
+#[cfg(feature = "http")]
+pub type DefaultTransport = ReqwestTransport;
+#[cfg(not(feature = "http"))]
+pub type DefaultTransport = FetchTransport;
+
+#[cfg(feature = "http")]
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    http: reqwest::Client,
+}
+
+#[cfg(feature = "http")]
+impl ReqwestTransport {
+    pub fn new(http: reqwest::Client) -> Self {
+        Self { http }
+    }
+}
+
+#[cfg(feature = "http")]
+impl Transport for ReqwestTransport {
+    async fn get(&self, url: &str, accept: &str) -> Result<TransportResponse, ClientError> {
+        use reqwest::header::{ACCEPT, CACHE_CONTROL, CONTENT_TYPE};
+
+        let response = self
+            .http
+            .get(url)
+            .header(ACCEPT, accept)
+            .send()
+            .await
+            .map_err(|e| ClientError::RequestError(e.to_string()))?;
+
+        let status = response.status().as_u16();
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let content_type = header(CONTENT_TYPE);
+        let cache_control = header(CACHE_CONTROL);
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| ClientError::RequestError(e.to_string()))?;
+        Ok(TransportResponse {
+            status,
+            content_type,
+            cache_control,
+            body: body.to_vec(),
+        })
+    }
+}
+
+// Uses the `fetch` API of the global scope, so it works in windows and in
+// (service) workers alike. Only usable when compiled to wasm32 and run in a
+// JavaScript host.
+#[cfg(feature = "wasm-fetch")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FetchTransport;
+
+#[cfg(feature = "wasm-fetch")]
+impl FetchTransport {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[cfg(feature = "wasm-fetch")]
+fn js_error(value: wasm_bindgen::JsValue) -> ClientError {
+    ClientError::RequestError(value.as_string().unwrap_or_else(|| format!("{:?}", value)))
+}
+
+#[cfg(feature = "wasm-fetch")]
+impl Transport for FetchTransport {
+    async fn get(&self, url: &str, accept: &str) -> Result<TransportResponse, ClientError> {
+        use wasm_bindgen::JsCast;
+        use wasm_bindgen_futures::JsFuture;
+        use web_sys::{Request, RequestInit, Response, Window, WorkerGlobalScope};
+
+        let init = RequestInit::new();
+        init.set_method("GET");
+        let request = Request::new_with_str_and_init(url, &init).map_err(js_error)?;
+        request.headers().set("Accept", accept).map_err(js_error)?;
+
+        let global = js_sys::global();
+        let promise = if let Some(window) = global.dyn_ref::<Window>() {
+            window.fetch_with_request(&request)
+        } else if let Some(worker) = global.dyn_ref::<WorkerGlobalScope>() {
+            worker.fetch_with_request(&request)
+        } else {
+            return Err(ClientError::RequestError(
+                "fetch is not available in this JavaScript environment".to_string(),
+            ));
+        };
+        let response: Response = JsFuture::from(promise)
+            .await
+            .map_err(js_error)?
+            .dyn_into()
+            .map_err(js_error)?;
+
+        let content_type = response.headers().get("Content-Type").map_err(js_error)?;
+        let cache_control = response.headers().get("Cache-Control").map_err(js_error)?;
+        let buffer = JsFuture::from(response.array_buffer().map_err(js_error)?)
+            .await
+            .map_err(js_error)?;
+        Ok(TransportResponse {
+            status: response.status(),
+            content_type,
+            cache_control,
+            body: js_sys::Uint8Array::new(&buffer).to_vec(),
+        })
+    }
+}
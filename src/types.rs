@@ -1,17 +1,66 @@
-use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "serde")]
 use std::fmt::Write;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg(feature = "uri")]
+use crate::uri::StatusListUri;
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StatusType {
-    Valid = 0x00,
-    Invalid = 0x01,
-    Suspended = 0x02,
-    ApplicationSpecific3 = 0x03,
-    ApplicationSpecific11 = 0x0B,
-    ApplicationSpecific12 = 0x0C,
-    ApplicationSpecific13 = 0x0D,
-    ApplicationSpecific14 = 0x0E,
-    ApplicationSpecific15 = 0x0F,
+    Valid,
+    Invalid,
+    Suspended,
+    ApplicationSpecific3,
+    ApplicationSpecific11,
+    ApplicationSpecific12,
+    ApplicationSpecific13,
+    ApplicationSpecific14,
+    ApplicationSpecific15,
+    /// Any other value. 4-bit lists can hold up to 0x0F, 8-bit lists up to 0xFF.
+    ApplicationSpecific(RawStatus),
+}
+
+/// A status value without a named variant. It can only be obtained through
+/// `StatusType::from(u8)`, so every value has exactly one `StatusType`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct RawStatus(u8);
+
+impl RawStatus {
+    pub const fn value(self) -> u8 {
+        self.0
+    }
+}
+
+impl fmt::Debug for RawStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl StatusType {
+    pub const fn value(self) -> u8 {
+        match self {
+            StatusType::Valid => 0x00,
+            StatusType::Invalid => 0x01,
+            StatusType::Suspended => 0x02,
+            StatusType::ApplicationSpecific3 => 0x03,
+            StatusType::ApplicationSpecific11 => 0x0B,
+            StatusType::ApplicationSpecific12 => 0x0C,
+            StatusType::ApplicationSpecific13 => 0x0D,
+            StatusType::ApplicationSpecific14 => 0x0E,
+            StatusType::ApplicationSpecific15 => 0x0F,
+            StatusType::ApplicationSpecific(raw) => raw.value(),
+        }
+    }
+}
+
+impl From<StatusType> for u8 {
+    fn from(status: StatusType) -> Self {
+        status.value()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -36,81 +85,256 @@ impl TryFrom<u8> for BitsPerStatus {
     }
 }
 
-impl TryFrom<u8> for StatusType {
-    type Error = StatusTypeError;
-
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+impl From<u8> for StatusType {
+    fn from(value: u8) -> Self {
         match value {
-            0x00 => Ok(StatusType::Valid),
-            0x01 => Ok(StatusType::Invalid),
-            0x02 => Ok(StatusType::Suspended),
-            0x03 => Ok(StatusType::ApplicationSpecific3),
-            0x0B => Ok(StatusType::ApplicationSpecific11),
-            0x0C => Ok(StatusType::ApplicationSpecific12),
-            0x0D => Ok(StatusType::ApplicationSpecific13),
-            0x0E => Ok(StatusType::ApplicationSpecific14),
-            0x0F => Ok(StatusType::ApplicationSpecific15),
-            _ => Err(StatusTypeError::UndefinedStatusType(value)),
+            0x00 => StatusType::Valid,
+            0x01 => StatusType::Invalid,
+            0x02 => StatusType::Suspended,
+            0x03 => StatusType::ApplicationSpecific3,
+            0x0B => StatusType::ApplicationSpecific11,
+            0x0C => StatusType::ApplicationSpecific12,
+            0x0D => StatusType::ApplicationSpecific13,
+            0x0E => StatusType::ApplicationSpecific14,
+            0x0F => StatusType::ApplicationSpecific15,
+            value => StatusType::ApplicationSpecific(RawStatus(value)),
         }
     }
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct StatusList {
-    pub bits: u8,
-    #[serde(with = "serde_bytes")]
-    pub lst: Vec<u8>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub aggregation_uri: Option<String>,
+    pub(crate) bits: u8,
+    pub(crate) lst: Vec<u8>,
+    pub(crate) aggregation_uri: Option<String>,
 }
 
-#[derive(Serialize)]
-pub struct JsonStatusList<'a> {
+// Human-readable formats such as JSON carry `lst` as a base64url string,
+// binary formats such as CBOR as a byte string.
+#[cfg(feature = "serde")]
+impl Serialize for StatusList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            JsonStatusList::from(self).serialize(serializer)
+        } else {
+            CborStatusListRef::from(self).serialize(serializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for StatusList {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            StatusList::try_from(JsonStatusList::deserialize(deserializer)?)
+        } else {
+            StatusList::try_from(CborStatusList::deserialize(deserializer)?)
+        }
+        .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JsonStatusList {
     pub bits: u8,
     pub lst: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub aggregation_uri: Option<&'a String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregation_uri: Option<String>,
 }
 
-#[derive(Serialize, Debug)]
-pub struct CborStatusList<'a> {
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CborStatusList {
     pub bits: u8,
     #[serde(rename = "lst")]
     #[serde(with = "serde_bytes")]
     pub lst: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregation_uri: Option<String>,
+}
+
+// Borrowing counterpart of CborStatusList, so encoding does not copy `lst`.
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct CborStatusListRef<'a> {
+    bits: u8,
+    #[serde(with = "serde_bytes")]
+    lst: &'a [u8],
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub aggregation_uri: Option<&'a String>,
+    aggregation_uri: Option<&'a str>,
+}
+
+#[cfg(feature = "serde")]
+impl<'a> From<&'a StatusList> for CborStatusListRef<'a> {
+    fn from(status_list: &'a StatusList) -> Self {
+        Self {
+            bits: status_list.bits,
+            lst: &status_list.lst,
+            aggregation_uri: status_list.aggregation_uri.as_deref(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<&StatusList> for JsonStatusList {
+    fn from(status_list: &StatusList) -> Self {
+        Self {
+            bits: status_list.bits,
+            lst: status_list.lst_base64url(),
+            aggregation_uri: status_list.aggregation_uri.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<StatusList> for JsonStatusList {
+    fn from(status_list: StatusList) -> Self {
+        Self {
+            bits: status_list.bits,
+            lst: status_list.lst_base64url(),
+            aggregation_uri: status_list.aggregation_uri,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<JsonStatusList> for StatusList {
+    type Error = SerializationError;
+
+    fn try_from(json_list: JsonStatusList) -> Result<Self, Self::Error> {
+        let lst = base64url::decode(&json_list.lst)
+            .map_err(|e| SerializationError::JsonError(format!("invalid base64url lst: {}", e)))?;
+
+        let status_list = Self::from_compressed(json_list.bits, lst)
+            .map_err(|e| SerializationError::JsonError(e.to_string()))?;
+        Ok(Self {
+            aggregation_uri: json_list.aggregation_uri,
+            ..status_list
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<&StatusList> for CborStatusList {
+    fn from(status_list: &StatusList) -> Self {
+        Self {
+            bits: status_list.bits,
+            lst: status_list.lst.clone(),
+            aggregation_uri: status_list.aggregation_uri.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<StatusList> for CborStatusList {
+    fn from(status_list: StatusList) -> Self {
+        Self {
+            bits: status_list.bits,
+            lst: status_list.lst,
+            aggregation_uri: status_list.aggregation_uri,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<CborStatusList> for StatusList {
+    type Error = SerializationError;
+
+    fn try_from(cbor_list: CborStatusList) -> Result<Self, Self::Error> {
+        let status_list = Self::from_compressed(cbor_list.bits, cbor_list.lst)
+            .map_err(|e| SerializationError::CborError(e.to_string()))?;
+        Ok(Self {
+            aggregation_uri: cbor_list.aggregation_uri,
+            ..status_list
+        })
+    }
 }
 
 use crate::error::StatusTypeError;
 
+impl StatusList {
+    pub fn from_compressed(bits: u8, lst: Vec<u8>) -> Result<Self, StatusTypeError> {
+        BitsPerStatus::try_from(bits)?;
+
+        // RFC 1950 header: deflate method, and CMF/FLG must be a multiple of 31
+        match lst[..] {
+            [cmf, flg, ..]
+                if cmf & 0x0F == 8 && (u16::from(cmf) << 8 | u16::from(flg)) % 31 == 0 =>
+            {
+                Ok(Self {
+                    bits,
+                    lst,
+                    aggregation_uri: None,
+                })
+            }
+            _ => Err(StatusTypeError::NotZlibCompressed),
+        }
+    }
+
+    #[cfg(feature = "encoder")]
+    pub fn from_packed_bytes(bits: u8, bytes: &[u8]) -> Result<Self, crate::error::BuilderError> {
+        crate::encoder::StatusListEncoder::new(bits).finalize(bytes)
+    }
+
+    pub fn with_aggregation_uri(mut self, aggregation_uri: impl Into<String>) -> Self {
+        self.aggregation_uri = Some(aggregation_uri.into());
+        self
+    }
+
+    pub fn bits(&self) -> u8 {
+        self.bits
+    }
+
+    pub fn lst(&self) -> &[u8] {
+        &self.lst
+    }
+
+    /// `lst` as it appears in the JSON form and in CWT or HTTP payloads that
+    /// carry it as text: base64url without padding.
+    pub fn lst_base64url(&self) -> String {
+        base64url::encode(&self.lst)
+    }
+
+    pub fn from_base64url(bits: u8, lst: &str) -> Result<Self, StatusTypeError> {
+        let lst =
+            base64url::decode(lst).map_err(|e| StatusTypeError::InvalidBase64(e.to_string()))?;
+        Self::from_compressed(bits, lst)
+    }
+
+    pub fn aggregation_uri(&self) -> Option<&str> {
+        self.aggregation_uri.as_deref()
+    }
+
+    pub fn into_parts(self) -> (u8, Vec<u8>, Option<String>) {
+        (self.bits, self.lst, self.aggregation_uri)
+    }
+}
+
+#[cfg(feature = "serde")]
 #[derive(Debug)]
 pub enum SerializationError {
     JsonError(String),
     CborError(String),
 }
 
+#[cfg(feature = "serde")]
 impl StatusList {
     pub fn to_json(&self) -> Result<String, SerializationError> {
-        let json_list = JsonStatusList {
-            bits: self.bits,
-            lst: base64url::encode(&self.lst),
-            aggregation_uri: self.aggregation_uri.as_ref(),
-        };
+        let json_list = JsonStatusList::from(self);
 
         serde_json::to_string(&json_list).map_err(|e| SerializationError::JsonError(e.to_string()))
     }
 
-    pub fn to_cbor(&self) -> Result<String, SerializationError> {
-        let cbor_list = CborStatusList {
-            bits: self.bits,
-            lst: self.lst.clone(),
-            aggregation_uri: self.aggregation_uri.as_ref(),
-        };
-
+    pub fn to_cbor_bytes(&self) -> Result<Vec<u8>, SerializationError> {
         let mut cbor_data = Vec::new();
-        ciborium::ser::into_writer(&cbor_list, &mut cbor_data)
+        ciborium::ser::into_writer(&CborStatusListRef::from(self), &mut cbor_data)
             .map_err(|e| SerializationError::CborError(e.to_string()))?;
+        Ok(cbor_data)
+    }
+
+    pub fn to_cbor_hex(&self) -> Result<String, SerializationError> {
+        let cbor_data = self.to_cbor_bytes()?;
 
         let mut hex = String::with_capacity(cbor_data.len() * 2);
         for byte in cbor_data {
@@ -119,8 +343,36 @@ impl StatusList {
         }
         Ok(hex)
     }
+
+    /// Hex-encoded CBOR, same as `to_cbor_hex`. Use `to_cbor_bytes` to embed
+    /// the list in a CWT or another CBOR structure.
+    pub fn to_cbor(&self) -> Result<String, SerializationError> {
+        self.to_cbor_hex()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, SerializationError> {
+        let json_list: JsonStatusList =
+            serde_json::from_str(json).map_err(|e| SerializationError::JsonError(e.to_string()))?;
+
+        Self::try_from(json_list)
+    }
+
+    pub fn from_cbor(cbor: &[u8]) -> Result<Self, SerializationError> {
+        let cbor_list: CborStatusList = ciborium::de::from_reader(cbor)
+            .map_err(|e| SerializationError::CborError(e.to_string()))?;
+
+        Self::try_from(cbor_list)
+    }
+
+    pub fn from_cbor_hex(hex: &str) -> Result<Self, SerializationError> {
+        let cbor =
+            hex::decode(hex.trim()).map_err(|e| SerializationError::CborError(e.to_string()))?;
+
+        Self::from_cbor(&cbor)
+    }
 }
 
+#[cfg(feature = "serde")]
 impl std::fmt::Display for SerializationError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -130,9 +382,96 @@ impl std::fmt::Display for SerializationError {
     }
 }
 
+#[cfg(feature = "serde")]
+impl std::error::Error for SerializationError {}
+
+// The URI is validated when a reference is deserialized, so a claim read
+// from a credential can be compared against owned lists directly.
+#[cfg(feature = "uri")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StatusListReference {
+    pub idx: usize,
+    pub uri: StatusListUri,
+}
+
+#[cfg(feature = "uri")]
+impl StatusListReference {
+    pub fn new(idx: usize, uri: StatusListUri) -> Self {
+        Self { idx, uri }
+    }
+}
+
+#[cfg(feature = "uri")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StatusClaim {
+    pub status_list: StatusListReference,
+}
+
+#[cfg(feature = "uri")]
+impl StatusClaim {
+    pub fn new(idx: usize, uri: StatusListUri) -> Self {
+        Self {
+            status_list: StatusListReference::new(idx, uri),
+        }
+    }
+
+    pub fn with_idx(mut self, idx: usize) -> Self {
+        self.status_list.idx = idx;
+        self
+    }
+
+    pub fn with_uri(mut self, uri: StatusListUri) -> Self {
+        self.status_list.uri = uri;
+        self
+    }
+}
+
+#[cfg(feature = "uri")]
+impl From<StatusListReference> for StatusClaim {
+    fn from(status_list: StatusListReference) -> Self {
+        Self { status_list }
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "uri"))]
+impl StatusClaim {
+    pub fn to_json(&self) -> Result<String, SerializationError> {
+        serde_json::to_string(self).map_err(|e| SerializationError::JsonError(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, SerializationError> {
+        serde_json::from_str(json).map_err(|e| SerializationError::JsonError(e.to_string()))
+    }
+
+    pub fn to_cbor_bytes(&self) -> Result<Vec<u8>, SerializationError> {
+        let mut cbor_data = Vec::new();
+        ciborium::ser::into_writer(self, &mut cbor_data)
+            .map_err(|e| SerializationError::CborError(e.to_string()))?;
+        Ok(cbor_data)
+    }
+
+    pub fn to_cbor(&self) -> Result<String, SerializationError> {
+        Ok(hex::encode(self.to_cbor_bytes()?))
+    }
+
+    pub fn from_cbor(cbor: &[u8]) -> Result<Self, SerializationError> {
+        ciborium::de::from_reader(cbor).map_err(|e| SerializationError::CborError(e.to_string()))
+    }
+
+    pub fn from_cbor_hex(hex: &str) -> Result<Self, SerializationError> {
+        let cbor =
+            hex::decode(hex.trim()).map_err(|e| SerializationError::CborError(e.to_string()))?;
+
+        Self::from_cbor(&cbor)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "serde")]
     #[test]
     fn test_serialization_errors() {
         let invalid_status_list = StatusList {
@@ -146,6 +485,39 @@ mod tests {
         assert!(invalid_status_list.to_cbor().is_ok());
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let status_list = StatusList::from_cbor_hex("a2646269747301636c73744a78dadbb918000217015d")
+            .unwrap()
+            .with_aggregation_uri("https://example.com/statuslists");
+
+        let json = serde_json::to_string(&status_list).unwrap();
+        assert_eq!(json, status_list.to_json().unwrap());
+        assert!(json.contains(r#""lst":"eNrbuRgAAhcBXQ""#));
+        let parsed: StatusList = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, status_list);
+
+        let mut cbor = Vec::new();
+        ciborium::ser::into_writer(&status_list, &mut cbor).unwrap();
+        assert_eq!(cbor, status_list.to_cbor_bytes().unwrap());
+        assert_eq!(hex::encode(&cbor), status_list.to_cbor_hex().unwrap());
+        assert_eq!(StatusList::from_cbor(&cbor).unwrap(), status_list);
+        let parsed: StatusList = ciborium::de::from_reader(&cbor[..]).unwrap();
+        assert_eq!(parsed, status_list);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_validates() {
+        assert!(
+            serde_json::from_str::<StatusList>(r#"{"bits":3,"lst":"eNrbuRgAAhcBXQ"}"#).is_err()
+        );
+        assert!(serde_json::from_str::<StatusList>(r#"{"bits":1,"lst":"AAAA"}"#).is_err());
+        assert!(serde_json::from_str::<StatusList>(r#"{"bits":1,"lst":"!!"}"#).is_err());
+    }
+
+    #[cfg(feature = "serde")]
     #[test]
     fn test_serialization_formats() {
         let status_list = StatusList {
@@ -164,6 +536,114 @@ mod tests {
         assert!(cbor.contains("636c7374"));
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_parse_spec_examples() {
+        let expected = StatusList {
+            bits: 1,
+            lst: vec![0x78, 0xda, 0xdb, 0xb9, 0x18, 0x00, 0x02, 0x17, 0x01, 0x5d],
+            aggregation_uri: None,
+        };
+
+        let from_json = StatusList::from_json(r#"{"bits":1,"lst":"eNrbuRgAAhcBXQ"}"#).unwrap();
+        assert_eq!(from_json, expected);
+
+        let from_hex =
+            StatusList::from_cbor_hex("a2646269747301636c73744a78dadbb918000217015d").unwrap();
+        assert_eq!(from_hex, expected);
+
+        let with_uri = expected
+            .clone()
+            .with_aggregation_uri("https://example.com/statuslists");
+        assert_eq!(
+            StatusList::from_json(&with_uri.to_json().unwrap()).unwrap(),
+            with_uri
+        );
+        assert_eq!(
+            StatusList::from_cbor_hex(&with_uri.to_cbor().unwrap()).unwrap(),
+            with_uri
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_parse_malformed_input() {
+        assert!(matches!(
+            StatusList::from_json("{\"bits\":1}"),
+            Err(SerializationError::JsonError(_))
+        ));
+        assert!(matches!(
+            StatusList::from_json(r#"{"bits":3,"lst":"eNrbuRgAAhcBXQ"}"#),
+            Err(SerializationError::JsonError(_))
+        ));
+        assert!(matches!(
+            StatusList::from_json(r#"{"bits":1,"lst":"AAAA"}"#),
+            Err(SerializationError::JsonError(_))
+        ));
+        assert!(matches!(
+            StatusList::from_cbor(&[0xa2, 0x64]),
+            Err(SerializationError::CborError(_))
+        ));
+        assert!(matches!(
+            StatusList::from_cbor_hex("not hex"),
+            Err(SerializationError::CborError(_))
+        ));
+    }
+
+    #[cfg(all(feature = "serde", feature = "uri"))]
+    #[test]
+    fn test_status_claim_round_trip() {
+        let uri = |uri: &str| StatusListUri::parse(uri).unwrap();
+        let claim = StatusClaim::new(0, uri("https://example.com/statuslists/1"));
+
+        let json = claim.to_json().unwrap();
+        assert_eq!(
+            json,
+            r#"{"status_list":{"idx":0,"uri":"https://example.com/statuslists/1"}}"#
+        );
+        assert_eq!(StatusClaim::from_json(&json).unwrap(), claim);
+
+        let cbor = claim.to_cbor().unwrap();
+        assert!(cbor.starts_with("a16b7374617475735f6c697374a263696478"));
+        assert_eq!(StatusClaim::from_cbor_hex(&cbor).unwrap(), claim);
+
+        let moved = claim
+            .with_idx(42)
+            .with_uri(uri("https://example.com/statuslists/2"));
+        assert_eq!(
+            moved,
+            StatusClaim::from(StatusListReference::new(
+                42,
+                uri("https://example.com/statuslists/2")
+            ))
+        );
+
+        assert!(StatusClaim::from_json(r#"{"status_list":{"idx":-1,"uri":"x"}}"#).is_err());
+        assert!(StatusClaim::from_cbor(&[0xa1]).is_err());
+    }
+
+    #[cfg(all(feature = "serde", feature = "uri"))]
+    #[test]
+    fn test_status_claim_validates_uri() {
+        let claim = StatusClaim::from_json(
+            r#"{"status_list":{"idx":3,"uri":"HTTPS://Example.COM/statuslists/1"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            claim.status_list.uri.as_str(),
+            "https://example.com/statuslists/1"
+        );
+
+        for uri in ["not a uri", "http://example.com/statuslists/1"] {
+            let json = format!(r#"{{"status_list":{{"idx":3,"uri":"{}"}}}}"#, uri);
+            assert!(matches!(
+                StatusClaim::from_json(&json),
+                Err(SerializationError::JsonError(_))
+            ));
+        }
+    }
+
+    #[cfg(feature = "serde")]
     #[test]
     fn test_serialization_error_display() {
         let json_error = SerializationError::JsonError("test error".to_string());
@@ -179,52 +659,160 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "serde")]
     #[test]
-    fn test_application_specific_status_types() {
-        // Test all application-specific status types as per draft-13
-        assert_eq!(
-            StatusType::try_from(0x03).unwrap(),
-            StatusType::ApplicationSpecific3
-        );
-        assert_eq!(
-            StatusType::try_from(0x0B).unwrap(),
-            StatusType::ApplicationSpecific11
-        );
+    fn test_json_status_list_conversions() {
+        let status_list = StatusList {
+            bits: 1,
+            lst: vec![0x78, 0xda, 0xdb, 0xb9, 0x18, 0x00, 0x02, 0x17, 0x01, 0x5d],
+            aggregation_uri: Some("https://example.com/statuslists".to_string()),
+        };
+
+        let json_list = JsonStatusList::from(&status_list);
+        assert_eq!(json_list.lst, "eNrbuRgAAhcBXQ");
         assert_eq!(
-            StatusType::try_from(0x0C).unwrap(),
-            StatusType::ApplicationSpecific12
+            json_list.aggregation_uri.as_deref(),
+            Some("https://example.com/statuslists")
         );
+        assert_eq!(StatusList::try_from(json_list).unwrap(), status_list);
+
+        let parsed: JsonStatusList =
+            serde_json::from_str(r#"{"bits":1,"lst":"eNrbuRgAAhcBXQ"}"#).unwrap();
+        assert_eq!(parsed.aggregation_uri, None);
+        let parsed = StatusList::try_from(parsed).unwrap();
+        assert_eq!(parsed.lst, status_list.lst);
+
+        let invalid = JsonStatusList {
+            bits: 1,
+            lst: "not base64url!".to_string(),
+            aggregation_uri: None,
+        };
+        match StatusList::try_from(invalid) {
+            Err(SerializationError::JsonError(msg)) => {
+                assert!(msg.contains("invalid base64url lst"))
+            }
+            _ => panic!("Expected JsonError"),
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_cbor_status_list_conversions() {
+        let status_list = StatusList {
+            bits: 2,
+            lst: vec![0x78, 0xda, 0x63, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01],
+            aggregation_uri: None,
+        };
+
+        let cbor_list = CborStatusList::from(status_list.clone());
+        let mut cbor_data = Vec::new();
+        ciborium::ser::into_writer(&cbor_list, &mut cbor_data).unwrap();
+        let parsed: CborStatusList = ciborium::de::from_reader(&cbor_data[..]).unwrap();
+
+        assert_eq!(parsed, cbor_list);
+        assert_eq!(StatusList::try_from(parsed).unwrap(), status_list);
+
+        let invalid = CborStatusList {
+            bits: 3,
+            ..cbor_list
+        };
+        match StatusList::try_from(invalid) {
+            Err(SerializationError::CborError(msg)) => {
+                assert!(msg.contains("Invalid bits per status value: 3"))
+            }
+            _ => panic!("Expected CborError"),
+        }
+    }
+
+    #[test]
+    fn test_from_compressed_validation() {
+        let lst = vec![0x78, 0xda, 0xdb, 0xb9, 0x18, 0x00, 0x02, 0x17, 0x01, 0x5d];
+
+        let status_list = StatusList::from_compressed(1, lst.clone())
+            .unwrap()
+            .with_aggregation_uri("https://example.com/statuslists");
+        assert_eq!(status_list.bits(), 1);
+        assert_eq!(status_list.lst(), &lst[..]);
         assert_eq!(
-            StatusType::try_from(0x0D).unwrap(),
-            StatusType::ApplicationSpecific13
+            status_list.aggregation_uri(),
+            Some("https://example.com/statuslists")
         );
         assert_eq!(
-            StatusType::try_from(0x0E).unwrap(),
-            StatusType::ApplicationSpecific14
+            status_list.into_parts(),
+            (
+                1,
+                lst.clone(),
+                Some("https://example.com/statuslists".to_string())
+            )
         );
+
+        match StatusList::from_compressed(3, lst) {
+            Err(StatusTypeError::InvalidBitsPerStatus(3)) => (),
+            _ => panic!("Expected InvalidBitsPerStatus error"),
+        }
+        for lst in [vec![], vec![0x78], vec![0xB9, 0xA3], vec![0x78, 0xdb]] {
+            match StatusList::from_compressed(1, lst) {
+                Err(StatusTypeError::NotZlibCompressed) => (),
+                _ => panic!("Expected NotZlibCompressed error"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_base64url_round_trip() {
+        let status_list = StatusList::from_base64url(1, "eNrbuRgAAhcBXQ").unwrap();
         assert_eq!(
-            StatusType::try_from(0x0F).unwrap(),
-            StatusType::ApplicationSpecific15
+            status_list.lst(),
+            &[0x78, 0xda, 0xdb, 0xb9, 0x18, 0x00, 0x02, 0x17, 0x01, 0x5d]
         );
+        assert_eq!(status_list.lst_base64url(), "eNrbuRgAAhcBXQ");
+
+        match StatusList::from_base64url(1, "eNrb*") {
+            Err(StatusTypeError::InvalidBase64(_)) => (),
+            _ => panic!("Expected InvalidBase64 error"),
+        }
+        match StatusList::from_base64url(1, "uaM") {
+            Err(StatusTypeError::NotZlibCompressed) => (),
+            _ => panic!("Expected NotZlibCompressed error"),
+        }
+    }
+
+    #[test]
+    fn test_application_specific_status_types() {
+        // Test all application-specific status types as per draft-13
+        assert_eq!(StatusType::from(0x03), StatusType::ApplicationSpecific3);
+        assert_eq!(StatusType::from(0x0B), StatusType::ApplicationSpecific11);
+        assert_eq!(StatusType::from(0x0C), StatusType::ApplicationSpecific12);
+        assert_eq!(StatusType::from(0x0D), StatusType::ApplicationSpecific13);
+        assert_eq!(StatusType::from(0x0E), StatusType::ApplicationSpecific14);
+        assert_eq!(StatusType::from(0x0F), StatusType::ApplicationSpecific15);
     }
 
     #[test]
     fn test_all_standard_status_types() {
         // Test standard status types as per draft-13
-        assert_eq!(StatusType::try_from(0x00).unwrap(), StatusType::Valid);
-        assert_eq!(StatusType::try_from(0x01).unwrap(), StatusType::Invalid);
-        assert_eq!(StatusType::try_from(0x02).unwrap(), StatusType::Suspended);
+        assert_eq!(StatusType::from(0x00), StatusType::Valid);
+        assert_eq!(StatusType::from(0x01), StatusType::Invalid);
+        assert_eq!(StatusType::from(0x02), StatusType::Suspended);
     }
 
     #[test]
-    fn test_reserved_status_types_error() {
-        // Values 0x04-0x0A are reserved for future registration
-        for value in 0x04..=0x0A {
-            assert!(StatusType::try_from(value).is_err());
+    fn test_other_status_values_are_application_specific() {
+        for value in (0x04..=0x0A).chain(0x10..=0xFF) {
+            let status = StatusType::from(value);
+            assert!(matches!(status, StatusType::ApplicationSpecific(raw) if raw.value() == value));
+            assert_eq!(u8::from(status), value);
         }
 
-        // Value 0x10 and above are also invalid
-        assert!(StatusType::try_from(0x10).is_err());
-        assert!(StatusType::try_from(0xFF).is_err());
+        for value in [0x00, 0x01, 0x02, 0x03, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F] {
+            assert!(!matches!(
+                StatusType::from(value),
+                StatusType::ApplicationSpecific(_)
+            ));
+        }
+        assert_eq!(
+            format!("{:?}", StatusType::from(0x42)),
+            "ApplicationSpecific(66)"
+        );
     }
 }
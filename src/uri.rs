@@ -0,0 +1,329 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::error::UriError;
+
+pub const DEFAULT_URI_SCHEMES: &[&str] = &["https"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct StatusListUri(String);
+
+impl StatusListUri {
+    pub fn parse(uri: &str) -> Result<Self, UriError> {
+        Self::parse_with_schemes(uri, DEFAULT_URI_SCHEMES)
+    }
+
+    pub fn parse_with_schemes(uri: &str, schemes: &[&str]) -> Result<Self, UriError> {
+        let mut url =
+            Url::parse(uri).map_err(|e| UriError::InvalidUri(format!("{}: {}", uri, e)))?;
+
+        if !schemes.contains(&url.scheme()) {
+            return Err(UriError::UnsupportedScheme(url.scheme().to_string()));
+        }
+        if !url.has_host() {
+            return Err(UriError::InvalidUri(format!("{}: missing host", uri)));
+        }
+        if url.fragment().is_some() {
+            return Err(UriError::InvalidUri(format!(
+                "{}: fragments are not allowed",
+                uri
+            )));
+        }
+
+        // Scheme and host case, default ports and IDNs are normalized by the
+        // parser; percent-encoding and trailing slashes are ours to handle.
+        let mut path = normalize_percent_encoding(url.path());
+        if path.len() > 1 && path.ends_with('/') {
+            path = path.trim_end_matches('/').to_string();
+        }
+        url.set_path(&path);
+        if let Some(query) = url.query().map(normalize_percent_encoding) {
+            url.set_query(Some(&query));
+        }
+
+        Ok(Self(url.into()))
+    }
+
+    pub fn ensure_matches(&self, found: &StatusListUri) -> Result<(), UriError> {
+        if self == found {
+            return Ok(());
+        }
+
+        let expected_url = Url::parse(&self.0).map_err(|e| UriError::InvalidUri(e.to_string()))?;
+        let found_url = Url::parse(&found.0).map_err(|e| UriError::InvalidUri(e.to_string()))?;
+        let detail = if expected_url.scheme() != found_url.scheme() {
+            format!(
+                "scheme differs ({} vs {})",
+                expected_url.scheme(),
+                found_url.scheme()
+            )
+        } else if expected_url.host_str() != found_url.host_str() {
+            format!(
+                "host differs ({} vs {})",
+                expected_url.host_str().unwrap_or_default(),
+                found_url.host_str().unwrap_or_default()
+            )
+        } else if expected_url.port() != found_url.port() {
+            format!(
+                "port differs ({:?} vs {:?})",
+                expected_url.port_or_known_default(),
+                found_url.port_or_known_default()
+            )
+        } else if expected_url.path() != found_url.path() {
+            format!(
+                "path differs ({} vs {})",
+                expected_url.path(),
+                found_url.path()
+            )
+        } else {
+            format!(
+                "query differs ({} vs {})",
+                expected_url.query().unwrap_or_default(),
+                found_url.query().unwrap_or_default()
+            )
+        };
+
+        Err(UriError::Mismatch(self.0.clone(), found.0.clone(), detail))
+    }
+
+    /// The scheme, host and port of the URI, which identify the list's
+    /// issuer.
+    pub fn origin(&self) -> String {
+        let url = Url::parse(&self.0).expect("a parsed StatusListUri is a valid URL");
+        let host = url.host_str().unwrap_or_default();
+        match url.port() {
+            Some(port) => format!("{}://{}:{}", url.scheme(), host, port),
+            None => format!("{}://{}", url.scheme(), host),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+// RFC 3986 section 6.2.2: uppercase percent-encoded octets and decode the
+// ones that stand for unreserved characters.
+fn normalize_percent_encoding(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut output = String::with_capacity(input.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit()
+        {
+            let value = u8::from_str_radix(&input[i + 1..i + 3], 16).unwrap_or_default();
+            if value.is_ascii_alphanumeric() || b"-._~".contains(&value) {
+                output.push(value as char);
+            } else {
+                output.push_str(&format!("%{:02X}", value));
+            }
+            i += 3;
+            continue;
+        }
+        output.push(bytes[i] as char);
+        i += 1;
+    }
+    output
+}
+
+impl fmt::Display for StatusListUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for StatusListUri {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for StatusListUri {
+    type Err = UriError;
+
+    fn from_str(uri: &str) -> Result<Self, Self::Err> {
+        Self::parse(uri)
+    }
+}
+
+impl TryFrom<&str> for StatusListUri {
+    type Error = UriError;
+
+    fn try_from(uri: &str) -> Result<Self, Self::Error> {
+        Self::parse(uri)
+    }
+}
+
+impl TryFrom<String> for StatusListUri {
+    type Error = UriError;
+
+    fn try_from(uri: String) -> Result<Self, Self::Error> {
+        Self::parse(&uri)
+    }
+}
+
+impl From<StatusListUri> for String {
+    fn from(uri: StatusListUri) -> Self {
+        uri.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalization() {
+        let expected = "https://example.com/statuslists/1";
+        for uri in [
+            "https://example.com/statuslists/1",
+            "https://example.com/statuslists/1/",
+            "HTTPS://Example.COM/statuslists/1",
+            "https://example.com:443/statuslists/1//",
+        ] {
+            assert_eq!(StatusListUri::parse(uri).unwrap().as_str(), expected);
+        }
+
+        assert_eq!(
+            StatusListUri::parse("https://example.com")
+                .unwrap()
+                .as_str(),
+            "https://example.com/"
+        );
+        assert_eq!(
+            StatusListUri::parse("https://example.com/lists?id=3")
+                .unwrap()
+                .to_string(),
+            "https://example.com/lists?id=3"
+        );
+        assert_eq!(
+            StatusListUri::parse("https://Example.com:443/lists/1")
+                .unwrap()
+                .origin(),
+            "https://example.com"
+        );
+        assert_eq!(
+            StatusListUri::parse("https://example.com:8443/lists/1")
+                .unwrap()
+                .origin(),
+            "https://example.com:8443"
+        );
+    }
+
+    #[test]
+    fn test_idn_and_percent_encoding_normalization() {
+        let issuer =
+            StatusListUri::parse("https://Bücher.example/lists/%7euser/a%2fb?x=%41").unwrap();
+        let fetched =
+            StatusListUri::parse("https://xn--bcher-kva.example/lists/~user/a%2Fb?x=A").unwrap();
+
+        assert_eq!(
+            issuer.as_str(),
+            "https://xn--bcher-kva.example/lists/~user/a%2Fb?x=A"
+        );
+        assert_eq!(issuer, fetched);
+        assert!(issuer.ensure_matches(&fetched).is_ok());
+    }
+
+    #[test]
+    fn test_mismatch_explains_difference() {
+        let expected = StatusListUri::parse("https://example.com/statuslists/1").unwrap();
+        let cases = [
+            (
+                "https://example.org/statuslists/1",
+                "host differs (example.com vs example.org)",
+            ),
+            (
+                "https://example.com:8443/statuslists/1",
+                "port differs (Some(443) vs Some(8443))",
+            ),
+            (
+                "https://example.com/statuslists/2",
+                "path differs (/statuslists/1 vs /statuslists/2)",
+            ),
+            (
+                "https://example.com/statuslists/1?v=2",
+                "query differs ( vs v=2)",
+            ),
+        ];
+
+        for (found, detail) in cases {
+            let found = StatusListUri::parse(found).unwrap();
+            match expected.ensure_matches(&found) {
+                Err(UriError::Mismatch(e, f, d)) => {
+                    assert_eq!(e, expected.as_str());
+                    assert_eq!(f, found.as_str());
+                    assert_eq!(d, detail);
+                }
+                _ => panic!("Expected Mismatch error for {}", found),
+            }
+        }
+
+        let error = expected
+            .ensure_matches(&StatusListUri::parse("https://example.org/statuslists/1").unwrap())
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Status list URI mismatch: expected https://example.com/statuslists/1, \
+             found https://example.org/statuslists/1 (host differs (example.com vs example.org))"
+        );
+    }
+
+    #[test]
+    fn test_scheme_validation() {
+        match StatusListUri::parse("http://example.com/statuslists/1") {
+            Err(UriError::UnsupportedScheme(scheme)) => assert_eq!(scheme, "http"),
+            _ => panic!("Expected UnsupportedScheme error"),
+        }
+
+        let uri =
+            StatusListUri::parse_with_schemes("http://localhost:8080/lists/1/", &["http", "https"])
+                .unwrap();
+        assert_eq!(uri.as_str(), "http://localhost:8080/lists/1");
+    }
+
+    #[test]
+    fn test_invalid_uris() {
+        for uri in [
+            "not a uri",
+            "/statuslists/1",
+            "https://example.com/statuslists/1#part",
+        ] {
+            match StatusListUri::parse(uri) {
+                Err(UriError::InvalidUri(msg)) => assert!(msg.starts_with(uri)),
+                other => panic!("Expected InvalidUri error for {}, got {:?}", uri, other),
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let uri: StatusListUri =
+            serde_json::from_str(r#""https://Example.com/statuslists/1/""#).unwrap();
+        assert_eq!(uri.as_str(), "https://example.com/statuslists/1");
+        assert_eq!(
+            serde_json::to_string(&uri).unwrap(),
+            r#""https://example.com/statuslists/1""#
+        );
+
+        assert!(serde_json::from_str::<StatusListUri>(r#""ftp://example.com/x""#).is_err());
+    }
+}
@@ -0,0 +1,86 @@
+use crate::allocator::IndexAllocator;
+use crate::error::ReferenceError;
+use crate::types::StatusListReference;
+use crate::uri::StatusListUri;
+
+// Checks references before they are embedded into a credential, so that an
+// issuer never hands out an index on a list it does not own or has not
+// allocated.
+#[derive(Debug, Default)]
+pub struct ReferenceValidator<'a> {
+    lists: Vec<(StatusListUri, &'a IndexAllocator)>,
+}
+
+impl<'a> ReferenceValidator<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_list(mut self, uri: StatusListUri, allocator: &'a IndexAllocator) -> Self {
+        self.lists.push((uri, allocator));
+        self
+    }
+
+    pub fn validate(&self, reference: &StatusListReference) -> Result<(), ReferenceError> {
+        let allocator = self
+            .lists
+            .iter()
+            .find(|(owned, _)| owned.ensure_matches(&reference.uri).is_ok())
+            .map(|(_, allocator)| *allocator)
+            .ok_or_else(|| ReferenceError::UnknownList(reference.uri.to_string()))?;
+
+        if reference.idx >= allocator.capacity() {
+            return Err(ReferenceError::IndexOutOfRange(reference.idx));
+        }
+        if !allocator.is_allocated(reference.idx) {
+            return Err(ReferenceError::Unallocated(reference.idx));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocator::AllocationStrategy;
+
+    #[test]
+    fn test_validate_reference() {
+        let mut allocator = IndexAllocator::new(16, AllocationStrategy::Sequential);
+        let idx = allocator.allocate().unwrap();
+        let uri = |uri: &str| StatusListUri::parse(uri).unwrap();
+        let validator = ReferenceValidator::new()
+            .with_list(uri("https://example.com/statuslists/1"), &allocator);
+
+        assert!(validator
+            .validate(&StatusListReference::new(
+                idx,
+                uri("https://EXAMPLE.com/statuslists/1")
+            ))
+            .is_ok());
+
+        match validator.validate(&StatusListReference::new(
+            1,
+            uri("https://example.com/statuslists/1"),
+        )) {
+            Err(ReferenceError::Unallocated(1)) => (),
+            _ => panic!("Expected Unallocated"),
+        }
+        match validator.validate(&StatusListReference::new(
+            16,
+            uri("https://example.com/statuslists/1"),
+        )) {
+            Err(ReferenceError::IndexOutOfRange(16)) => (),
+            _ => panic!("Expected IndexOutOfRange"),
+        }
+        match validator.validate(&StatusListReference::new(
+            idx,
+            uri("https://example.com/statuslists/2"),
+        )) {
+            Err(ReferenceError::UnknownList(uri)) => {
+                assert_eq!(uri, "https://example.com/statuslists/2")
+            }
+            _ => panic!("Expected UnknownList"),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use wasm_bindgen::prelude::*;
+
+use crate::builder::StatusListBuilder;
+use crate::decoder::StatusListDecoder;
+use crate::types::{StatusList, StatusType};
+
+// JavaScript bindings. Statuses cross the boundary as plain numbers, lists as
+// JSON strings or CBOR `Uint8Array`s.
+
+#[wasm_bindgen(js_name = StatusListBuilder)]
+pub struct WasmStatusListBuilder {
+    inner: StatusListBuilder,
+}
+
+#[wasm_bindgen(js_class = StatusListBuilder)]
+impl WasmStatusListBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new(bits_per_status: u8) -> Result<WasmStatusListBuilder, JsError> {
+        Ok(Self {
+            inner: StatusListBuilder::new(bits_per_status)?,
+        })
+    }
+
+    #[wasm_bindgen(js_name = addStatus)]
+    pub fn add_status(&self, status: u8) {
+        self.inner.add_status(StatusType::from(status));
+    }
+
+    #[wasm_bindgen(js_name = setStatus)]
+    pub fn set_status(&self, index: usize, status: u8) {
+        self.inner.set_status(index, StatusType::from(status));
+    }
+
+    #[wasm_bindgen(js_name = getStatus)]
+    pub fn get_status(&self, index: usize) -> Option<u8> {
+        self.inner.get_status(index).map(|status| status.value())
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[wasm_bindgen(js_name = isEmpty)]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsError> {
+        Ok(self.inner.build()?.to_json()?)
+    }
+
+    #[wasm_bindgen(js_name = toCbor)]
+    pub fn to_cbor(&self) -> Result<Vec<u8>, JsError> {
+        Ok(self.inner.build()?.to_cbor_bytes()?)
+    }
+}
+
+#[wasm_bindgen(js_name = StatusListDecoder)]
+pub struct WasmStatusListDecoder {
+    inner: StatusListDecoder,
+}
+
+#[wasm_bindgen(js_class = StatusListDecoder)]
+impl WasmStatusListDecoder {
+    /// Decodes a `status_list` JSON object.
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<WasmStatusListDecoder, JsError> {
+        Ok(Self {
+            inner: StatusListDecoder::new(&StatusList::from_json(json)?)?,
+        })
+    }
+
+    /// Decodes a CBOR-encoded status list, as found in a CWT.
+    #[wasm_bindgen(js_name = fromCbor)]
+    pub fn from_cbor(cbor: &[u8]) -> Result<WasmStatusListDecoder, JsError> {
+        Ok(Self {
+            inner: StatusListDecoder::new(&StatusList::from_cbor(cbor)?)?,
+        })
+    }
+
+    #[wasm_bindgen(js_name = getStatus)]
+    pub fn get_status(&self, index: usize) -> Result<u8, JsError> {
+        Ok(self.inner.get_status(index)?.value())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn bits(&self) -> u8 {
+        self.inner.get_bits_per_status()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[wasm_bindgen(js_name = isEmpty)]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
+// Error paths call into JavaScript, so native tests only cover success.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let builder = WasmStatusListBuilder::new(2).unwrap();
+        builder.add_status(0);
+        builder.set_status(3, 2);
+        assert_eq!(builder.get_status(3), Some(2));
+        assert_eq!(builder.len(), 4);
+
+        let decoder = WasmStatusListDecoder::from_json(&builder.to_json().unwrap()).unwrap();
+        assert_eq!(decoder.bits(), 2);
+        assert_eq!(decoder.get_status(3).unwrap(), 2);
+
+        let decoder = WasmStatusListDecoder::from_cbor(&builder.to_cbor().unwrap()).unwrap();
+        assert_eq!(decoder.get_status(0).unwrap(), 0);
+        assert!(!decoder.is_empty());
+    }
+}
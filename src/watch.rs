@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+
+use crate::decoder::{SharedStatusListDecoder, StatusListDecoder};
+use crate::error::DecoderError;
+use crate::types::{StatusList, StatusType};
+
+// Holds the current decoder for a list. A refresh task swaps in a new one
+// while readers keep lock-free access to whichever decoder they loaded.
+#[derive(Debug, Clone)]
+pub struct WatchableStatusList {
+    current: Arc<ArcSwap<StatusListDecoder>>,
+}
+
+impl WatchableStatusList {
+    pub fn new(decoder: StatusListDecoder) -> Self {
+        Self {
+            current: Arc::new(ArcSwap::from_pointee(decoder)),
+        }
+    }
+
+    pub fn current(&self) -> SharedStatusListDecoder {
+        SharedStatusListDecoder::from(self.current.load_full())
+    }
+
+    pub fn get_status(&self, index: usize) -> Result<StatusType, DecoderError> {
+        self.current.load().get_status(index)
+    }
+
+    /// Replaces the decoder for all clones of this handle, returning the old one.
+    pub fn replace(&self, decoder: StatusListDecoder) -> SharedStatusListDecoder {
+        SharedStatusListDecoder::from(self.current.swap(Arc::new(decoder)))
+    }
+
+    pub fn replace_from(&self, status_list: &StatusList) -> Result<(), DecoderError> {
+        self.replace(StatusListDecoder::new(status_list)?);
+        Ok(())
+    }
+}
+
+impl From<StatusListDecoder> for WatchableStatusList {
+    fn from(decoder: StatusListDecoder) -> Self {
+        Self::new(decoder)
+    }
+}
+
+#[cfg(all(test, feature = "encoder"))]
+mod tests {
+    use super::*;
+
+    fn decoder(bytes: &[u8]) -> StatusListDecoder {
+        StatusListDecoder::new(&StatusList::from_packed_bytes(1, bytes).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_replace() {
+        let watched = WatchableStatusList::new(decoder(&[0b0000_0000]));
+        let reader = watched.clone();
+        let before = reader.current();
+
+        let old = watched.replace(decoder(&[0b0000_0010]));
+        assert_eq!(old.get_status(1).unwrap(), StatusType::Valid);
+        assert_eq!(reader.get_status(1).unwrap(), StatusType::Invalid);
+        assert_eq!(before.get_status(1).unwrap(), StatusType::Valid);
+
+        watched
+            .replace_from(&StatusList::from_packed_bytes(1, &[0b0000_0000]).unwrap())
+            .unwrap();
+        assert_eq!(reader.get_status(1).unwrap(), StatusType::Valid);
+    }
+
+    #[test]
+    fn test_concurrent_readers() {
+        let watched = WatchableStatusList::new(decoder(&[0x00]));
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let watched = watched.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..1000 {
+                        let status = watched.get_status(0).unwrap();
+                        assert!(status == StatusType::Valid || status == StatusType::Invalid);
+                    }
+                })
+            })
+            .collect();
+
+        for i in 0..100u8 {
+            watched.replace(decoder(&[i & 1]));
+        }
+        for reader in readers {
+            reader.join().unwrap();
+        }
+    }
+}